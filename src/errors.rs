@@ -1,6 +1,8 @@
 //! Errors.
 #![allow(missing_docs)]
-use crate::metadata::{Id, PropertyType, TypeId, TypeKind};
+use crate::metadata::{
+    ArtifactId, ContextId, EventType, ExecutionId, Id, PropertyType, TypeId, TypeKind,
+};
 
 /// Possible errors during database initialization.
 #[derive(Debug, thiserror::Error)]
@@ -17,9 +19,20 @@ pub enum InitError {
     #[error("only SQLite or MySQL are supported by ml-metadata")]
     UnsupportedDatabase,
 
+    /// The database URI is malformed.
+    #[error("invalid database URI {uri:?}: {reason}")]
+    InvalidUri {
+        /// The given URI.
+        uri: String,
+
+        /// The reason why the URI is invalid.
+        reason: &'static str,
+    },
+
     /// Incompatible database schema is used in the ml-metadata database.
     ///
     /// Please upgrade or downgrade the database by following [the official doc][migration].
+    /// Databases of the versions 6 and 7 can also be upgraded by [`MetadataStore::migrate`](crate::MetadataStore::migrate).
     ///
     /// [migration]: https://github.com/google/ml-metadata/blob/master/g3doc/get_started.md#upgrade-the-mlmd-library
     #[error("schema version {actual} is not supported (supported version is {expected})")]
@@ -30,6 +43,23 @@ pub enum InitError {
         /// The schema version supported by this crate.
         expected: i32,
     },
+
+    /// The database hasn't been initialized as an ml-metadata database.
+    ///
+    /// This is returned by [`MetadataStore::connect_read_only`](crate::MetadataStore::connect_read_only)
+    /// which never creates tables.
+    #[error("the database is not initialized")]
+    UninitializedDatabase,
+
+    /// The environment variable specifying the database URI couldn't be read.
+    #[error("failed to read the environment variable {key:?}")]
+    EnvVar {
+        /// The name of the environment variable.
+        key: String,
+
+        /// The reason why the variable couldn't be read.
+        source: std::env::VarError,
+    },
 }
 
 /// Possible errors while getting items from database.
@@ -66,6 +96,26 @@ pub enum PutError {
         property_type: PropertyType,
     },
 
+    /// Artifact, execution or context has a property of which value type differs from the one declared by the type.
+    #[error("{item_id} has a property {property_name:?} of {actual} type but {expected} type is expected")]
+    PropertyTypeMismatch {
+        item_id: Id,
+        property_name: String,
+        expected: PropertyType,
+        actual: PropertyType,
+    },
+
+    /// A custom property is updated with a value of another type than the stored one.
+    ///
+    /// This is only returned when [`PutArtifactRequest::strict_custom_properties`](crate::requests::PutArtifactRequest::strict_custom_properties) is specified.
+    #[error("{item_id} has a custom property {property_name:?} of {expected} type but {actual} type value is specified")]
+    CustomPropertyTypeMismatch {
+        item_id: Id,
+        property_name: String,
+        expected: PropertyType,
+        actual: PropertyType,
+    },
+
     /// A name which already exists is specified.
     #[error("{item_id} has a name {item_name:?} that already exists")]
     NameAlreadyExists { item_id: Id, item_name: String },
@@ -73,6 +123,64 @@ pub enum PutError {
     /// The artifact, execution or context hasn't been created yet.
     #[error("{item_id} is not found")]
     NotFound { item_id: Id },
+
+    /// Specified type hasn't been defined.
+    #[error("type {type_id} is not found")]
+    TypeIdNotFound { type_id: TypeId },
+
+    /// The artifact has a URI that is longer than the limit of the store.
+    #[error("{item_id} has a URI of {len} bytes that exceeds the limit of {max} bytes")]
+    UriTooLong { item_id: Id, len: usize, max: usize },
+
+    /// An empty name or a name longer than 255 bytes is specified for a type or an item.
+    #[error("name {name:?} is empty or longer than 255 bytes")]
+    InvalidName { name: String },
+
+    /// An empty property name or a property name longer than 255 bytes is specified.
+    #[error("property name {name:?} is empty or longer than 255 bytes")]
+    InvalidPropertyName { name: String },
+
+    /// An event with the same execution, artifact and type already exists.
+    #[error("{ty:?} event between {execution_id} and {artifact_id} already exists")]
+    EventAlreadyExists {
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+        ty: EventType,
+    },
+
+    /// The event hasn't been created yet.
+    #[error("{ty:?} event between {execution_id} and {artifact_id} is not found")]
+    EventNotFound {
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+        ty: EventType,
+    },
+
+    /// Specified type has a different kind from the expected one.
+    #[error("type {type_id} is expected to be a {expected} type but is a {actual} type")]
+    TypeKindMismatch {
+        type_id: TypeId,
+        expected: TypeKind,
+        actual: TypeKind,
+    },
+
+    /// A type is specified as the parent of itself.
+    #[error("type {type_id} cannot be the parent of itself")]
+    SelfParentType { type_id: TypeId },
+
+    /// A context is specified as the parent of itself.
+    #[error("context {context_id} cannot be the parent of itself")]
+    SelfParentContext { context_id: ContextId },
+}
+
+impl AsDbError for PutError {
+    fn as_db_error(&self) -> Option<&sqlx::Error> {
+        if let Self::Db(e) = self {
+            Some(e)
+        } else {
+            None
+        }
+    }
 }
 
 impl From<GetError> for PutError {
@@ -96,6 +204,15 @@ pub enum PostError {
         type_id: TypeId,
     },
 
+    /// Specified type has a different kind from the one of the new item
+    /// (e.g., an execution type is specified for a new artifact).
+    #[error("type {type_id} is expected to be a {expected} type but is a {actual} type")]
+    TypeKindMismatch {
+        type_id: TypeId,
+        expected: TypeKind,
+        actual: TypeKind,
+    },
+
     /// Specified property isn't defined by the type.
     #[error("new {type_kind} with the type {type_id} has an undefined property {property_name:?}")]
     UndefinedProperty {
@@ -104,12 +221,42 @@ pub enum PostError {
         property_name: String,
     },
 
+    /// Specified property value has a different type from the one declared by the type.
+    #[error("new item has a property {property_name:?} of {actual} type but {expected} type is expected")]
+    PropertyTypeMismatch {
+        property_name: String,
+        expected: PropertyType,
+        actual: PropertyType,
+    },
+
     /// A name which already exists is specified.
     #[error("new {type_kind} has a name {item_name:?} that already exists")]
     NameAlreadyExists {
         type_kind: TypeKind,
         item_name: String,
     },
+
+    /// The new artifact has a URI that is longer than the limit of the store.
+    #[error("new artifact has a URI of {len} bytes that exceeds the limit of {max} bytes")]
+    UriTooLong { len: usize, max: usize },
+
+    /// An empty name or a name longer than 255 bytes is specified.
+    #[error("name {name:?} is empty or longer than 255 bytes")]
+    InvalidName { name: String },
+
+    /// An empty property name or a property name longer than 255 bytes is specified.
+    #[error("property name {name:?} is empty or longer than 255 bytes")]
+    InvalidPropertyName { name: String },
+}
+
+impl AsDbError for PostError {
+    fn as_db_error(&self) -> Option<&sqlx::Error> {
+        if let Self::Db(e) = self {
+            Some(e)
+        } else {
+            None
+        }
+    }
 }
 
 impl From<GetError> for PostError {
@@ -118,3 +265,36 @@ impl From<GetError> for PostError {
         Self::Db(e)
     }
 }
+
+/// Possible errors while importing a JSON dump into database.
+#[cfg(feature = "serde")]
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    /// The dump isn't a valid JSON or doesn't have the expected structure.
+    #[error("malformed JSON dump")]
+    Json(#[from] serde_json::Error),
+
+    /// The dump contains an unknown name of an enum value (e.g., a state or an event type).
+    #[error("unknown enum value {value:?}")]
+    UnknownEnumValue { value: String },
+
+    /// An item in the dump refers to a type that isn't included in the dump.
+    #[error("type {type_id} is referred but not included in the dump")]
+    UndefinedTypeId { type_id: TypeId },
+
+    /// An event or a relation in the dump refers to an item that isn't included in the dump.
+    #[error("{item_id} is referred but not included in the dump")]
+    UndefinedItemId { item_id: Id },
+
+    /// Failed to put a type, an event or a relation.
+    #[error(transparent)]
+    Put(#[from] PutError),
+
+    /// Failed to create an item.
+    #[error(transparent)]
+    Post(#[from] PostError),
+}
+
+pub(crate) trait AsDbError {
+    fn as_db_error(&self) -> Option<&sqlx::Error>;
+}
@@ -42,11 +42,11 @@
 //!
 //! The following features are not supported yet:
 //! - gRPC client
-//! - `input_type` and `output_type` fields of `Execution`
 //!
 //! The following features are not planned to be supported:
 //! - gRPC server
-//! - Database schema migration
+//!
+//! Database schema migration is only supported from the versions 6 and 7 (see [`MetadataStore::migrate`]).
 //!
 //! # `ml-metadata` References
 //!
@@ -57,8 +57,12 @@
 pub mod errors;
 pub mod metadata;
 pub mod requests;
+pub mod snapshot;
 
 mod metadata_store;
 mod query;
 
-pub use self::metadata_store::MetadataStore;
+pub use self::metadata_store::{MetadataStore, MetadataStoreConfig, Transaction};
+
+#[cfg(feature = "serde")]
+pub use self::metadata_store::ImportSummary;
@@ -9,6 +9,7 @@ use std::time::Duration;
 
 /// Type kind.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum TypeKind {
     Execution = 0,
@@ -17,6 +18,17 @@ pub enum TypeKind {
 }
 
 impl TypeKind {
+    pub(crate) fn from_i32(v: i32) -> Result<Self, sqlx::Error> {
+        match v {
+            0 => Ok(Self::Execution),
+            1 => Ok(Self::Artifact),
+            2 => Ok(Self::Context),
+            _ => Err(sqlx::Error::Decode(
+                anyhow::anyhow!("type kind {} is undefined", v).into(),
+            )),
+        }
+    }
+
     pub(crate) fn item_table_name(&self) -> &'static str {
         match self {
             Self::Execution => "Execution",
@@ -38,6 +50,8 @@ impl std::fmt::Display for TypeKind {
 
 /// Type identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct TypeId(i32);
 
 impl TypeId {
@@ -60,6 +74,8 @@ impl std::fmt::Display for TypeId {
 
 /// Artifact identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ArtifactId(i32);
 
 impl ArtifactId {
@@ -82,6 +98,8 @@ impl std::fmt::Display for ArtifactId {
 
 /// Execution identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ExecutionId(i32);
 
 impl ExecutionId {
@@ -104,6 +122,8 @@ impl std::fmt::Display for ExecutionId {
 
 /// Context identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ContextId(i32);
 
 impl ContextId {
@@ -126,6 +146,7 @@ impl std::fmt::Display for ContextId {
 
 /// Identifier of artifact, execution or context.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum Id {
     Artifact(ArtifactId),
@@ -175,19 +196,51 @@ pub type PropertyValues = BTreeMap<String, PropertyValue>;
 
 /// Property type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum PropertyType {
     Int = 1,
     Double = 2,
     String = 3,
+    Bytes = 4,
+    Bool = 6,
 }
 
 impl PropertyType {
+    /// Returns the name of this type used by ml-metadata (e.g., `"BOOLEAN"`).
+    ///
+    /// Note that ml-metadata doesn't define a bytes type, so `"BYTES"` is specific to this crate.
+    pub fn as_str_name(self) -> &'static str {
+        match self {
+            Self::Int => "INT",
+            Self::Double => "DOUBLE",
+            Self::String => "STRING",
+            Self::Bytes => "BYTES",
+            Self::Bool => "BOOLEAN",
+        }
+    }
+
+    /// Makes a type from its name used by ml-metadata (e.g., `"BOOLEAN"`).
+    ///
+    /// If the name is unknown, [`None`] is returned.
+    pub fn from_str_name(name: &str) -> Option<Self> {
+        match name {
+            "INT" => Some(Self::Int),
+            "DOUBLE" => Some(Self::Double),
+            "STRING" => Some(Self::String),
+            "BYTES" => Some(Self::Bytes),
+            "BOOLEAN" => Some(Self::Bool),
+            _ => None,
+        }
+    }
+
     pub(crate) fn from_i32(value: i32) -> Result<Self, sqlx::Error> {
         match value {
             1 => Ok(Self::Int),
             2 => Ok(Self::Double),
             3 => Ok(Self::String),
+            4 => Ok(Self::Bytes),
+            6 => Ok(Self::Bool),
             _ => Err(sqlx::Error::Decode(
                 anyhow::anyhow!("property type {} is undefined", value).into(),
             )),
@@ -201,44 +254,130 @@ impl std::fmt::Display for PropertyType {
             Self::Int => write!(f, "int"),
             Self::Double => write!(f, "double"),
             Self::String => write!(f, "string"),
+            Self::Bytes => write!(f, "bytes"),
+            Self::Bool => write!(f, "bool"),
         }
     }
 }
 
 /// Artifact type.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct ArtifactType {
     pub id: TypeId,
     pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
     pub properties: PropertyTypes,
 }
 
 /// Execution type.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct ExecutionType {
     pub id: TypeId,
     pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+
+    /// JSON representation of the artifact structure that the executions of this type take as input.
+    ///
+    /// This field is only available with the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub input_type: Option<serde_json::Value>,
+
+    /// JSON representation of the artifact structure that the executions of this type produce as output.
+    ///
+    /// This field is only available with the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub output_type: Option<serde_json::Value>,
+
     pub properties: PropertyTypes,
 }
 
 /// Context type.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct ContextType {
     pub id: TypeId,
     pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
     pub properties: PropertyTypes,
 }
 
+/// Type of any kind.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum AnyType {
+    Artifact(ArtifactType),
+    Execution(ExecutionType),
+    Context(ContextType),
+}
+
+impl AnyType {
+    /// Returns the kind of this type.
+    pub fn kind(&self) -> TypeKind {
+        match self {
+            Self::Artifact(_) => TypeKind::Artifact,
+            Self::Execution(_) => TypeKind::Execution,
+            Self::Context(_) => TypeKind::Context,
+        }
+    }
+
+    /// Returns the ID of this type.
+    pub fn id(&self) -> TypeId {
+        match self {
+            Self::Artifact(x) => x.id,
+            Self::Execution(x) => x.id,
+            Self::Context(x) => x.id,
+        }
+    }
+
+    /// Returns the name of this type.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Artifact(x) => &x.name,
+            Self::Execution(x) => &x.name,
+            Self::Context(x) => &x.name,
+        }
+    }
+
+    /// Returns the property definitions of this type.
+    pub fn properties(&self) -> &PropertyTypes {
+        match self {
+            Self::Artifact(x) => &x.properties,
+            Self::Execution(x) => &x.properties,
+            Self::Context(x) => &x.properties,
+        }
+    }
+}
+
 /// Property value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 #[allow(missing_docs)]
 pub enum PropertyValue {
     Int(i32),
-    Double(f64),
+
+    /// Double value.
+    ///
+    /// If the `serde` feature is enabled, non-finite values are serialized as
+    /// the strings `"NaN"`, `"Infinity"` and `"-Infinity"` as JSON cannot represent them as numbers.
+    Double(#[cfg_attr(feature = "serde", serde(with = "non_finite_f64"))] f64),
     String(String),
+    Bytes(Vec<u8>),
+
+    /// Boolean value.
+    ///
+    /// This is stored as an integer in the database,
+    /// so custom properties of this type are read back as [`PropertyValue::Int`].
+    Bool(bool),
 }
 
 impl PropertyValue {
@@ -248,6 +387,8 @@ impl PropertyValue {
             Self::Int(_) => PropertyType::Int,
             Self::Double(_) => PropertyType::Double,
             Self::String(_) => PropertyType::String,
+            Self::Bytes(_) => PropertyType::Bytes,
+            Self::Bool(_) => PropertyType::Bool,
         }
     }
 
@@ -283,6 +424,73 @@ impl PropertyValue {
             None
         }
     }
+
+    /// Gets the value of this property as a byte slice.
+    ///
+    /// If this is not a [`PropertyValue::Bytes`], [`None`] is returned .
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let Self::Bytes(v) = &self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the value of this property as [`bool`].
+    ///
+    /// If this is not a [`PropertyValue::Bool`], [`None`] is returned .
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Self::Bool(v) = &self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyValue {
+    /// Formats this value so that [`PropertyValue::from_str`](std::str::FromStr::from_str) can parse it back
+    /// (except for [`PropertyValue::Bytes`] and [`PropertyValue::Bool`] which are parsed as strings).
+    ///
+    /// Strings that would be parsed as numbers or quoted strings are surrounded by double quotes.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Int(v) => write!(f, "{}", v),
+            Self::Double(v) => write!(f, "{:?}", v),
+            Self::String(v) => {
+                if v.parse::<Self>().ok().as_ref() == Some(self) {
+                    write!(f, "{}", v)
+                } else {
+                    write!(f, "\"{}\"", v)
+                }
+            }
+            Self::Bytes(v) => write!(f, "b\"{}\"", v.escape_ascii()),
+            Self::Bool(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl std::str::FromStr for PropertyValue {
+    type Err = std::convert::Infallible;
+
+    /// Parses a property value.
+    ///
+    /// The string is parsed as [`PropertyValue::Int`] if possible, then as [`PropertyValue::Double`]
+    /// (including `"NaN"` and `"inf"`), and otherwise it is regarded as [`PropertyValue::String`].
+    ///
+    /// To force a string, surround it with double quotes (e.g., `"\"1\""` is parsed as the string `"1"`).
+    /// Only the outermost quotes are removed and no escape sequences are interpreted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+            Ok(Self::String(s[1..s.len() - 1].to_owned()))
+        } else if let Ok(v) = s.parse() {
+            Ok(Self::Int(v))
+        } else if let Ok(v) = s.parse() {
+            Ok(Self::Double(v))
+        } else {
+            Ok(Self::String(s.to_owned()))
+        }
+    }
 }
 
 impl From<i32> for PropertyValue {
@@ -303,6 +511,18 @@ impl From<String> for PropertyValue {
     }
 }
 
+impl From<Vec<u8>> for PropertyValue {
+    fn from(v: Vec<u8>) -> Self {
+        Self::Bytes(v)
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
 impl<'a> From<&'a str> for PropertyValue {
     fn from(v: &'a str) -> Self {
         Self::String(v.to_owned())
@@ -311,6 +531,7 @@ impl<'a> From<&'a str> for PropertyValue {
 
 /// Artifact.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Artifact {
     pub id: ArtifactId,
@@ -320,7 +541,9 @@ pub struct Artifact {
     pub properties: PropertyValues,
     pub custom_properties: PropertyValues,
     pub state: ArtifactState,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub create_time_since_epoch: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub last_update_time_since_epoch: Duration,
 }
 
@@ -356,9 +579,11 @@ impl<'a> sqlx::FromRow<'a, sqlx::any::AnyRow> for Artifact {
 }
 
 /// Artifact state.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArtifactState {
     /// Unknown state (default).
+    #[default]
     Unknown = 0,
 
     /// A state indicating that the artifact may exist.
@@ -375,6 +600,31 @@ pub enum ArtifactState {
 }
 
 impl ArtifactState {
+    /// Returns the name of this state used by ml-metadata (e.g., `"MARKED_FOR_DELETION"`).
+    pub fn as_str_name(self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKNOWN",
+            Self::Pending => "PENDING",
+            Self::Live => "LIVE",
+            Self::MarkedForDeletion => "MARKED_FOR_DELETION",
+            Self::Deleted => "DELETED",
+        }
+    }
+
+    /// Makes a state from its name used by ml-metadata (e.g., `"MARKED_FOR_DELETION"`).
+    ///
+    /// If the name is unknown, [`None`] is returned.
+    pub fn from_str_name(name: &str) -> Option<Self> {
+        match name {
+            "UNKNOWN" => Some(Self::Unknown),
+            "PENDING" => Some(Self::Pending),
+            "LIVE" => Some(Self::Live),
+            "MARKED_FOR_DELETION" => Some(Self::MarkedForDeletion),
+            "DELETED" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+
     pub(crate) fn from_i32(v: i32) -> Result<Self, sqlx::Error> {
         match v {
             0 => Ok(Self::Unknown),
@@ -389,14 +639,9 @@ impl ArtifactState {
     }
 }
 
-impl Default for ArtifactState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Execution.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Execution {
     pub id: ExecutionId,
@@ -405,7 +650,9 @@ pub struct Execution {
     pub last_known_state: ExecutionState,
     pub properties: PropertyValues,
     pub custom_properties: PropertyValues,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub create_time_since_epoch: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub last_update_time_since_epoch: Duration,
 }
 
@@ -444,9 +691,11 @@ impl<'a> sqlx::FromRow<'a, sqlx::any::AnyRow> for Execution {
 /// Execution state.
 ///
 /// The state transitions are `New -> Running -> Complete | Cached | Failed | Canceled`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExecutionState {
     /// Unknown state (default).
+    #[default]
     Unknown = 0,
 
     /// The execution is created but not started yet.
@@ -474,6 +723,35 @@ pub enum ExecutionState {
 }
 
 impl ExecutionState {
+    /// Returns the name of this state used by ml-metadata (e.g., `"COMPLETE"`).
+    pub fn as_str_name(self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKNOWN",
+            Self::New => "NEW",
+            Self::Running => "RUNNING",
+            Self::Complete => "COMPLETE",
+            Self::Failed => "FAILED",
+            Self::Cached => "CACHED",
+            Self::Canceled => "CANCELED",
+        }
+    }
+
+    /// Makes a state from its name used by ml-metadata (e.g., `"COMPLETE"`).
+    ///
+    /// If the name is unknown, [`None`] is returned.
+    pub fn from_str_name(name: &str) -> Option<Self> {
+        match name {
+            "UNKNOWN" => Some(Self::Unknown),
+            "NEW" => Some(Self::New),
+            "RUNNING" => Some(Self::Running),
+            "COMPLETE" => Some(Self::Complete),
+            "FAILED" => Some(Self::Failed),
+            "CACHED" => Some(Self::Cached),
+            "CANCELED" => Some(Self::Canceled),
+            _ => None,
+        }
+    }
+
     pub(crate) fn from_i32(v: i32) -> Result<Self, sqlx::Error> {
         match v {
             0 => Ok(Self::Unknown),
@@ -490,14 +768,9 @@ impl ExecutionState {
     }
 }
 
-impl Default for ExecutionState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Context.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Context {
     pub id: ContextId,
@@ -505,7 +778,9 @@ pub struct Context {
     pub name: String,
     pub properties: PropertyValues,
     pub custom_properties: PropertyValues,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub create_time_since_epoch: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub last_update_time_since_epoch: Duration,
 }
 
@@ -546,9 +821,11 @@ impl<'a> sqlx::FromRow<'a, sqlx::any::AnyRow> for Context {
 /// For more information on what `Declared` and `Iternal` mean, see [the comment on the original repo][comment].
 ///
 /// [comment]: https://github.com/google/ml-metadata/blob/v0.26.0/ml_metadata/proto/metadata_store.proto#L94-L161
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum EventType {
+    #[default]
     Unknown = 0,
     DeclaredOutput = 1,
     DeclaredInput = 2,
@@ -559,6 +836,51 @@ pub enum EventType {
 }
 
 impl EventType {
+    /// Returns `true` if this is [`EventType::DeclaredInput`], [`EventType::Input`] or [`EventType::InternalInput`].
+    pub fn is_input(self) -> bool {
+        matches!(
+            self,
+            Self::DeclaredInput | Self::Input | Self::InternalInput
+        )
+    }
+
+    /// Returns `true` if this is [`EventType::DeclaredOutput`], [`EventType::Output`] or [`EventType::InternalOutput`].
+    pub fn is_output(self) -> bool {
+        matches!(
+            self,
+            Self::DeclaredOutput | Self::Output | Self::InternalOutput
+        )
+    }
+
+    /// Returns the name of this type used by ml-metadata (e.g., `"DECLARED_OUTPUT"`).
+    pub fn as_str_name(self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKNOWN",
+            Self::DeclaredOutput => "DECLARED_OUTPUT",
+            Self::DeclaredInput => "DECLARED_INPUT",
+            Self::Input => "INPUT",
+            Self::Output => "OUTPUT",
+            Self::InternalInput => "INTERNAL_INPUT",
+            Self::InternalOutput => "INTERNAL_OUTPUT",
+        }
+    }
+
+    /// Makes an event type from its name used by ml-metadata (e.g., `"DECLARED_OUTPUT"`).
+    ///
+    /// If the name is unknown, [`None`] is returned.
+    pub fn from_str_name(name: &str) -> Option<Self> {
+        match name {
+            "UNKNOWN" => Some(Self::Unknown),
+            "DECLARED_OUTPUT" => Some(Self::DeclaredOutput),
+            "DECLARED_INPUT" => Some(Self::DeclaredInput),
+            "INPUT" => Some(Self::Input),
+            "OUTPUT" => Some(Self::Output),
+            "INTERNAL_INPUT" => Some(Self::InternalInput),
+            "INTERNAL_OUTPUT" => Some(Self::InternalOutput),
+            _ => None,
+        }
+    }
+
     pub(crate) fn from_i32(v: i32) -> Result<Self, sqlx::Error> {
         match v {
             0 => Ok(Self::Unknown),
@@ -575,16 +897,11 @@ impl EventType {
     }
 }
 
-impl Default for EventType {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Event step.
 ///
 /// A path (i.e., a vector of event steps) can name an artifact in the context of an execution.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum EventStep {
     Index(i32),
@@ -595,15 +912,100 @@ pub enum EventStep {
 ///
 /// An event represents a relationship between an artifact and an execution.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Event {
     pub artifact_id: ArtifactId,
     pub execution_id: ExecutionId,
     pub path: Vec<EventStep>,
     pub ty: EventType,
+    #[cfg_attr(feature = "serde", serde(with = "duration_millis"))]
     pub create_time_since_epoch: Duration,
 }
 
+/// Lineage graph around an artifact, execution or context.
+///
+/// A lineage is created by [`MetadataStore::get_lineage`](crate::MetadataStore::get_lineage).
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lineage {
+    /// Artifacts in the graph (ordered by id).
+    pub artifacts: Vec<Artifact>,
+
+    /// Executions in the graph (ordered by id).
+    pub executions: Vec<Execution>,
+
+    /// Events connecting the artifacts and executions.
+    pub events: Vec<Event>,
+}
+
+/// A violation of the input or output type declared by an execution type.
+///
+/// Violations are reported by [`MetadataStore::validate_execution_io`](crate::MetadataStore::validate_execution_io).
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionIoViolation {
+    /// `true` if the violation is in the output artifacts, `false` if in the input ones.
+    pub is_output: bool,
+
+    /// Event path to the position where the violation was detected.
+    pub path: Vec<EventStep>,
+
+    /// Human-readable reason of the violation.
+    pub reason: String,
+}
+
 fn none_if_empty(s: Option<String>) -> Option<String> {
     s.and_then(|s| if s.is_empty() { None } else { Some(s) })
 }
+
+#[cfg(feature = "serde")]
+mod duration_millis {
+    use serde::{Deserialize as _, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(d.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) mod non_finite_f64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if v.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if v.is_infinite() && v.is_sign_positive() {
+            serializer.serialize_str("Infinity")
+        } else if v.is_infinite() {
+            serializer.serialize_str("-Infinity")
+        } else {
+            serializer.serialize_f64(*v)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Double {
+            Number(f64),
+            String(String),
+        }
+
+        match Double::deserialize(deserializer)? {
+            Double::Number(v) => Ok(v),
+            Double::String(s) if s == "NaN" => Ok(f64::NAN),
+            Double::String(s) if s == "Infinity" => Ok(f64::INFINITY),
+            Double::String(s) if s == "-Infinity" => Ok(f64::NEG_INFINITY),
+            Double::String(s) => Err(serde::de::Error::custom(format!(
+                "expected a number, \"NaN\", \"Infinity\" or \"-Infinity\", but got {:?}",
+                s
+            ))),
+        }
+    }
+}
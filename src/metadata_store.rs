@@ -1,136 +1,644 @@
 use self::options::{
-    GetEventsOptions, GetItemsOptions, GetTypesOptions, ItemOptions, PutEventOptions,
+    ArtifactOptions, ContextOptions, GetArtifactsOptions, GetContextsOptions, GetEventsOptions,
+    GetExecutionsOptions, GetItemsOptions, GetTypesOptions, ItemOptions, PutEventOptions,
     PutTypeOptions,
 };
-use crate::errors::{GetError, InitError, PostError, PutError};
+use self::type_cache::TypeCache;
+#[cfg(feature = "serde")]
+use crate::errors::ImportError;
+use crate::errors::{AsDbError, GetError, InitError, PostError, PutError};
+#[cfg(feature = "serde")]
+use crate::metadata::ExecutionIoViolation;
 use crate::metadata::{
-    ArtifactId, ContextId, Event, EventStep, EventType, ExecutionId, Id, PropertyType,
-    PropertyTypes, TypeId, TypeKind,
+    AnyType, Artifact, ArtifactId, ArtifactState, ArtifactType, Context, ContextId, ContextType,
+    Event, EventStep, EventType, Execution, ExecutionId, ExecutionState, ExecutionType, Id,
+    Lineage, PropertyType, PropertyTypes, PropertyValue, TypeId, TypeKind,
 };
-use crate::query::{self, InsertProperty, Query};
+use crate::query::{self, InsertProperty, Query, QueryArgs};
 use crate::requests;
-use futures::TryStreamExt as _;
-use sqlx::{AnyConnection, Connection as _, Row as _};
-use std::collections::BTreeMap;
+use crate::snapshot::Snapshot;
+use futures::lock::{Mutex, MutexGuard};
+use futures::{Stream, TryStreamExt as _};
+use sqlx::any::{Any, AnyConnectOptions, AnyConnection, AnyPool, AnyPoolOptions};
+use sqlx::mysql::{MySqlDatabaseError, MySqlSslMode};
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection as _, Row as _};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, UNIX_EPOCH};
 
+pub use self::config::MetadataStoreConfig;
+#[cfg(feature = "serde")]
+pub use self::proto_json::ImportSummary;
+pub use self::transaction::Transaction;
+
+#[cfg(feature = "serde")]
+mod artifact_struct;
+mod config;
 pub mod options;
+#[cfg(feature = "serde")]
+mod proto_json;
 #[cfg(test)]
 mod tests;
+mod transaction;
+mod type_cache;
 
 const SCHEMA_VERSION: i32 = 8;
 
+const MIN_MIGRATABLE_SCHEMA_VERSION: i32 = 6;
+
+const DATABASE_URI_ENV_VAR: &str = "MLMD_DATABASE_URI";
+
+const TYPE_BATCH_SIZE: usize = 100;
+
+// The maximum length of the `VARCHAR(255)` name columns.
+const MAX_NAME_LENGTH: usize = 255;
+
+// The event types for which `EventType::is_output` returns `true`.
+const OUTPUT_EVENT_TYPES: [EventType; 3] = [
+    EventType::DeclaredOutput,
+    EventType::Output,
+    EventType::InternalOutput,
+];
+
 /// Metadata store.
 ///
 /// `MetadataStore` provides the API to operate on a database to store and fetch metadata.
+///
+/// Database connections are managed by a connection pool and
+/// each request acquires a connection from the pool while it is executed.
+///
+/// Use [`MetadataStore::transaction`] to execute multiple requests atomically.
+///
+/// Cloning a store is cheap as the clones share the same connection pool (and type cache).
+/// So a store created by [`MetadataStore::connect_pool`] can be put into the state of a web application
+/// and cloned per request.
+/// Note that the clones of a store created by [`MetadataStore::connect`] share its single connection,
+/// so the requests issued via them are executed one at a time.
 #[derive(Debug)]
 pub struct MetadataStore {
-    connection: sqlx::AnyConnection,
+    pool: AnyPool,
     pub(crate) query: Query,
+    transaction: Option<Mutex<sqlx::Transaction<'static, Any>>>,
+    max_uri_length: Option<usize>,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    type_cache: Option<Arc<std::sync::Mutex<TypeCache>>>,
+}
+
+impl Clone for MetadataStore {
+    fn clone(&self) -> Self {
+        // The stores bound to transactions are owned by `Transaction` and never cloned.
+        debug_assert!(self.transaction.is_none());
+        Self {
+            pool: self.pool.clone(),
+            query: self.query.clone(),
+            transaction: None,
+            max_uri_length: self.max_uri_length,
+            max_retries: self.max_retries,
+            retry_base_delay: self.retry_base_delay,
+            type_cache: self.type_cache.clone(),
+        }
+    }
 }
 
 impl MetadataStore {
     /// Connects to the database specified by the given URI.
+    ///
+    /// The returned store keeps using a single connection.
+    /// Use [`MetadataStore::connect_pool`] to issue requests concurrently.
     pub async fn connect(database_uri: &str) -> Result<Self, InitError> {
-        let query = if database_uri.starts_with("sqlite") {
-            Query::sqlite()
-        } else if database_uri.starts_with("mysql") {
-            Query::mysql()
+        Self::connect_with(MetadataStoreConfig::new(database_uri)).await
+    }
+
+    /// Connects to the database specified by the given URI using a pool of at most `max_connections` connections.
+    pub async fn connect_pool(database_uri: &str, max_connections: u32) -> Result<Self, InitError> {
+        Self::connect_with(MetadataStoreConfig::new(database_uri).max_connections(max_connections))
+            .await
+    }
+
+    /// Connects to the database with the given configuration.
+    pub async fn connect_with(config: MetadataStoreConfig) -> Result<Self, InitError> {
+        let query = Self::query_for(&config.database_uri)?;
+        let mut options = match &config.tls_ca_cert_path {
+            Some(path) => tls_connect_options(&config.database_uri, path)?,
+            None => config.database_uri.parse()?,
+        };
+        if config.read_only {
+            if let Some(sqlite) = options.as_sqlite_mut() {
+                // The driver switches the journal mode to WAL by default, which requires a write.
+                // SQLite regards an unknown mode as a query of the current mode.
+                *sqlite = sqlite
+                    .clone()
+                    .read_only(true)
+                    .pragma("journal_mode", "QUERY");
+            }
+        }
+        let mut pool_options = if config.max_connections == 1 {
+            single_connection_pool_options()
         } else {
-            return Err(InitError::UnsupportedDatabase);
+            AnyPoolOptions::new().max_connections(config.max_connections)
+        };
+        if let Some(timeout) = config.connect_timeout {
+            pool_options = pool_options.connect_timeout(timeout);
+        }
+        let pool = pool_options.connect_with(options).await?;
+
+        let mut this = Self::new(pool, query, config.schema_version, config.read_only).await?;
+        this.max_uri_length = config.max_uri_length;
+        this.max_retries = config.max_retries;
+        this.retry_base_delay = config.retry_base_delay;
+        if let Some(capacity) = config.type_cache_capacity {
+            this = this.with_type_cache(capacity);
+        }
+        Ok(this)
+    }
+
+    /// Connects to the database specified by the `MLMD_DATABASE_URI` environment variable.
+    ///
+    /// Use [`MetadataStore::connect_from_env_var`] to read the URI from another variable.
+    pub async fn connect_from_env() -> Result<Self, InitError> {
+        Self::connect_from_env_var(DATABASE_URI_ENV_VAR).await
+    }
+
+    /// Connects to the database specified by the given environment variable.
+    pub async fn connect_from_env_var(key: &str) -> Result<Self, InitError> {
+        let database_uri = std::env::var(key).map_err(|source| InitError::EnvVar {
+            key: key.to_owned(),
+            source,
+        })?;
+        Self::connect(&database_uri).await
+    }
+
+    /// Connects to the database specified by the given URI using TLS.
+    ///
+    /// The server certificate is verified against the CA certificate located at `ca_cert_path`
+    /// (i.e., the connection is made with `ssl-mode=VERIFY_CA`).
+    ///
+    /// Note that TLS is a MySQL-only option. If `database_uri` points to a SQLite database,
+    /// the certificate is ignored and this method behaves the same as [`MetadataStore::connect`].
+    ///
+    /// Client certificates are not supported as the underlying driver doesn't provide a way to set them.
+    pub async fn connect_with_tls(
+        database_uri: &str,
+        ca_cert_path: impl AsRef<Path>,
+    ) -> Result<Self, InitError> {
+        Self::connect_with(MetadataStoreConfig::new(database_uri).tls_ca_cert_path(ca_cert_path))
+            .await
+    }
+
+    /// Connects to the existing database specified by the given URI without modifying it.
+    ///
+    /// Unlike [`MetadataStore::connect`], this method never creates tables nor writes the schema version,
+    /// so it can be used with read-only credentials.
+    /// If the database hasn't been initialized, [`InitError::UninitializedDatabase`] is returned.
+    ///
+    /// SQLite databases are opened in read-only mode, so write requests via the returned store fail.
+    /// For MySQL, use read-only credentials to enforce read-only access.
+    /// Note that the MySQL tables created by the older releases of this crate lack some columns,
+    /// so such a database needs to be connected once via [`MetadataStore::connect`] (or [`MetadataStore::migrate`]d) beforehand.
+    pub async fn connect_read_only(database_uri: &str) -> Result<Self, InitError> {
+        Self::connect_with(MetadataStoreConfig::new(database_uri).read_only()).await
+    }
+
+    /// Connects to the database specified by the given URI expecting the given schema version.
+    ///
+    /// By default, [`MetadataStore::connect`] fails with [`InitError::UnsupportedSchemaVersion`]
+    /// if the database has a schema version other than 8.
+    /// This method allows to opt in to use a database of another (e.g., a nearby) version at your own risk.
+    ///
+    /// Note that a new database can only be initialized with the schema version 8.
+    /// If the database hasn't been initialized and another version is specified,
+    /// this method fails with [`InitError::UnsupportedSchemaVersion`] without creating tables.
+    pub async fn connect_with_schema_version(
+        database_uri: &str,
+        schema_version: i32,
+    ) -> Result<Self, InitError> {
+        Self::connect_with(MetadataStoreConfig::new(database_uri).schema_version(schema_version))
+            .await
+    }
+
+    /// Returns the schema version of the database.
+    pub async fn schema_version(&self) -> Result<i32, GetError> {
+        let sql = self.query.select_schema_version();
+        query::trace_sql(sql, 0);
+        let version = sqlx::query_scalar(sql)
+            .fetch_one(&mut *self.connection().await?)
+            .await?;
+        Ok(version)
+    }
+
+    /// Checks whether the connection to the database is alive.
+    ///
+    /// This executes a trivial query (`SELECT 1`) which doesn't touch any tables,
+    /// so it's cheap enough to be used for health checks.
+    pub async fn ping(&self) -> Result<(), GetError> {
+        let sql = self.query.ping();
+        query::trace_sql(sql, 0);
+        sqlx::query(sql)
+            .execute(&mut *self.connection().await?)
+            .await?;
+        Ok(())
+    }
+
+    /// Migrates the database schema to the version supported by this crate (i.e., 8).
+    ///
+    /// Databases of the schema versions 6 and 7 can be migrated.
+    /// Such a database can be opened via [`MetadataStore::connect_with_schema_version`] before calling this method.
+    /// If the database already has the supported version, this method only adds the columns
+    /// which are missing in the tables created by the older releases of this crate (if any).
+    ///
+    /// The migration is executed in a transaction.
+    /// Note that MySQL implicitly commits DDL statements, so a failed migration may leave a partially migrated schema there.
+    /// As each step is idempotent, it's safe to call this method again in that case.
+    pub async fn migrate(&mut self) -> Result<(), InitError> {
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
+
+        let sql = self.query.select_schema_version();
+        query::trace_sql(sql, 0);
+        let version: i32 = sqlx::query_scalar(sql).fetch_one(&mut connection).await?;
+        if version == SCHEMA_VERSION {
+            self.repair_schema(&mut connection).await?;
+            connection.commit().await?;
+            return Ok(());
+        }
+        if !(MIN_MIGRATABLE_SCHEMA_VERSION..SCHEMA_VERSION).contains(&version) {
+            return Err(InitError::UnsupportedSchemaVersion {
+                actual: version,
+                expected: SCHEMA_VERSION,
+            });
+        }
+
+        for step in self.query.migration_steps() {
+            if step.version <= version {
+                continue;
+            }
+            for (probe, statement) in step.statements {
+                query::trace_sql(probe, 0);
+                let applied: i64 = sqlx::query_scalar(probe).fetch_one(&mut connection).await?;
+                if applied == 0 {
+                    query::trace_sql(statement, 0);
+                    sqlx::query(statement).execute(&mut connection).await?;
+                }
+            }
+        }
+
+        let sql = self.query.update_schema_version();
+        query::trace_sql(sql, 1);
+        sqlx::query(sql)
+            .bind(SCHEMA_VERSION)
+            .execute(&mut connection)
+            .await?;
+        connection.commit().await?;
+        Ok(())
+    }
+
+    fn query_for(database_uri: &str) -> Result<Query, InitError> {
+        let invalid = |reason| InitError::InvalidUri {
+            uri: database_uri.to_owned(),
+            reason,
         };
+        let (scheme, rest) = database_uri
+            .split_once(':')
+            .ok_or_else(|| invalid("missing scheme"))?;
+        match scheme {
+            "sqlite" => {
+                // Accepts both `sqlite://path` and `sqlite:path` (e.g., `sqlite::memory:`).
+                let path = rest.strip_prefix("//").unwrap_or(rest);
+                let path = path.split('?').next().unwrap_or_default();
+                if path.is_empty() {
+                    return Err(invalid("empty database path"));
+                }
+                Ok(Query::sqlite())
+            }
+            "mysql" => {
+                let authority = rest
+                    .strip_prefix("//")
+                    .ok_or_else(|| invalid("missing `//` after the scheme"))?;
+                let host = authority
+                    .split(&['/', '?'][..])
+                    .next()
+                    .unwrap_or_default()
+                    .rsplit('@')
+                    .next()
+                    .unwrap_or_default();
+                if host.is_empty() {
+                    return Err(invalid("empty host"));
+                }
+                Ok(Query::mysql())
+            }
+            _ => Err(InitError::UnsupportedDatabase),
+        }
+    }
 
-        let connection = AnyConnection::connect(database_uri).await?;
-        let mut this = Self { connection, query };
-        this.initialize_database().await?;
+    async fn new(
+        pool: AnyPool,
+        query: Query,
+        schema_version: i32,
+        read_only: bool,
+    ) -> Result<Self, InitError> {
+        let this = Self {
+            pool,
+            query,
+            transaction: None,
+            max_uri_length: None,
+            max_retries: 0,
+            retry_base_delay: Duration::ZERO,
+            type_cache: None,
+        };
+        if read_only {
+            this.check_schema_version(schema_version).await?;
+        } else {
+            this.initialize_database(schema_version).await?;
+        }
         Ok(this)
     }
 
+    /// Sets the maximum length (in bytes) of artifact URIs.
+    ///
+    /// Creating or updating an artifact with a longer URI fails with an `UriTooLong` error.
+    /// The default is [`None`] (i.e., unlimited).
+    pub fn set_max_uri_length(&mut self, max: Option<usize>) {
+        self.max_uri_length = max;
+    }
+
+    /// Enables an in-memory LRU cache of the property definitions of at most `capacity` types.
+    ///
+    /// Creating or updating an item requires the property definitions of its type.
+    /// With this cache, they are fetched from the database only once per type
+    /// (until the type is evicted from the cache or updated via this store).
+    ///
+    /// Note that the updates on types made by other clients (or processes) are not reflected to the cache.
+    pub fn with_type_cache(mut self, capacity: usize) -> Self {
+        self.type_cache = Some(Arc::new(std::sync::Mutex::new(TypeCache::new(capacity))));
+        self
+    }
+
+    /// Starts a transaction.
+    ///
+    /// The requests issued via the returned [`Transaction`] are committed at once by [`Transaction::commit`].
+    /// If the transaction is dropped without being committed, the requests are rolled back.
+    pub async fn transaction(&mut self) -> Result<Transaction<'_>, PutError> {
+        let transaction = self.pool.begin().await?;
+        let store = Self {
+            pool: self.pool.clone(),
+            query: self.query.clone(),
+            transaction: Some(Mutex::new(transaction)),
+            max_uri_length: self.max_uri_length,
+            max_retries: self.max_retries,
+            retry_base_delay: self.retry_base_delay,
+            type_cache: self.type_cache.clone(),
+        };
+        Ok(Transaction::new(store))
+    }
+
     /// Makes a request builder to put an artifact type.
-    pub fn put_artifact_type(&mut self, type_name: &str) -> requests::PutArtifactTypeRequest {
+    pub fn put_artifact_type(&mut self, type_name: &str) -> requests::PutArtifactTypeRequest<'_> {
         requests::PutArtifactTypeRequest::new(self, type_name)
     }
 
     /// Makes a request builder to get artifact types.
-    pub fn get_artifact_types(&mut self) -> requests::GetArtifactTypesRequest {
+    pub fn get_artifact_types(&self) -> requests::GetArtifactTypesRequest<'_> {
         requests::GetArtifactTypesRequest::new(self)
     }
 
+    /// Gets the artifact type that has the given name (and no version).
+    ///
+    /// If there is no such type, [`None`] is returned.
+    pub async fn get_artifact_type(
+        &self,
+        type_name: &str,
+    ) -> Result<Option<ArtifactType>, GetError> {
+        let Some(type_id) = self
+            .get_type_id_by_name(TypeKind::Artifact, type_name)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let types = self.get_artifact_types().id(type_id).execute().await?;
+        Ok(types.into_iter().next())
+    }
+
     /// Makes a request builder to put an execution type.
-    pub fn put_execution_type(&mut self, type_name: &str) -> requests::PutExecutionTypeRequest {
+    pub fn put_execution_type(&mut self, type_name: &str) -> requests::PutExecutionTypeRequest<'_> {
         requests::PutExecutionTypeRequest::new(self, type_name)
     }
 
     /// Makes a request builder to get execution types.
-    pub fn get_execution_types(&mut self) -> requests::GetExecutionTypesRequest {
+    pub fn get_execution_types(&self) -> requests::GetExecutionTypesRequest<'_> {
         requests::GetExecutionTypesRequest::new(self)
     }
 
+    /// Gets the execution type that has the given name (and no version).
+    ///
+    /// If there is no such type, [`None`] is returned.
+    pub async fn get_execution_type(
+        &self,
+        type_name: &str,
+    ) -> Result<Option<ExecutionType>, GetError> {
+        let Some(type_id) = self
+            .get_type_id_by_name(TypeKind::Execution, type_name)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let types = self.get_execution_types().id(type_id).execute().await?;
+        Ok(types.into_iter().next())
+    }
+
     /// Makes a request builder to put a context type.
-    pub fn put_context_type(&mut self, type_name: &str) -> requests::PutContextTypeRequest {
+    pub fn put_context_type(&mut self, type_name: &str) -> requests::PutContextTypeRequest<'_> {
         requests::PutContextTypeRequest::new(self, type_name)
     }
 
     /// Makes a request builder to get context types.
-    pub fn get_context_types(&mut self) -> requests::GetContextTypesRequest {
+    pub fn get_context_types(&self) -> requests::GetContextTypesRequest<'_> {
         requests::GetContextTypesRequest::new(self)
     }
 
+    /// Gets the context type that has the given name (and no version).
+    ///
+    /// If there is no such type, [`None`] is returned.
+    pub async fn get_context_type(&self, type_name: &str) -> Result<Option<ContextType>, GetError> {
+        let Some(type_id) = self
+            .get_type_id_by_name(TypeKind::Context, type_name)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let types = self.get_context_types().id(type_id).execute().await?;
+        Ok(types.into_iter().next())
+    }
+
+    /// Makes a request builder to create a new parent-child relationship between types.
+    ///
+    /// Both types must be of the same kind, and a type cannot be the parent of itself.
+    pub fn put_parent_type(
+        &mut self,
+        child_type_id: TypeId,
+        parent_type_id: TypeId,
+    ) -> requests::PutParentTypeRequest<'_> {
+        requests::PutParentTypeRequest::new(self, child_type_id, parent_type_id)
+    }
+
     /// Makes a request builder to create a new artifact.
-    pub fn post_artifact(&mut self, type_id: TypeId) -> requests::PostArtifactRequest {
+    pub fn post_artifact(&mut self, type_id: TypeId) -> requests::PostArtifactRequest<'_> {
         requests::PostArtifactRequest::new(self, type_id)
     }
 
     /// Makes a request builder to update an artifact.
-    pub fn put_artifact(&mut self, artifact_id: ArtifactId) -> requests::PutArtifactRequest {
+    pub fn put_artifact(&mut self, artifact_id: ArtifactId) -> requests::PutArtifactRequest<'_> {
         requests::PutArtifactRequest::new(self, artifact_id)
     }
 
+    /// Updates multiple artifacts atomically.
+    ///
+    /// Each artifact is updated in the same way as [`MetadataStore::put_artifact`].
+    /// If any of the updates fails, none of them are applied and the first error is returned.
+    pub async fn put_artifacts(
+        &mut self,
+        artifacts: Vec<(ArtifactId, ArtifactOptions)>,
+    ) -> Result<(), PutError> {
+        let transaction = self.transaction().await?;
+        for (artifact_id, options) in artifacts {
+            transaction
+                .store()
+                .execute_put_item(Id::Artifact(artifact_id), ItemOptions::Artifact(options))
+                .await?;
+        }
+        transaction.commit().await
+    }
+
     /// Makes a request builder to get artifacts.
-    pub fn get_artifacts(&mut self) -> requests::GetArtifactsRequest {
+    pub fn get_artifacts(&self) -> requests::GetArtifactsRequest<'_> {
         requests::GetArtifactsRequest::new(self)
     }
 
+    /// Gets the artifact that has the given identifier.
+    ///
+    /// If there is no such artifact, [`None`] is returned.
+    pub async fn get_artifact(
+        &self,
+        artifact_id: ArtifactId,
+    ) -> Result<Option<Artifact>, GetError> {
+        let options = GetArtifactsOptions {
+            artifact_ids: vec![artifact_id].into_iter().collect(),
+            ..Default::default()
+        };
+        let items = self
+            .execute_get_items(GetItemsOptions::Artifact(options))
+            .await?;
+        Ok(items.into_iter().next())
+    }
+
+    /// Gets the ID of the artifact that has the given type and name, creating it if absent.
+    ///
+    /// If another client creates the same artifact concurrently, the ID of that artifact is returned.
+    pub async fn get_or_create_artifact(
+        &mut self,
+        type_id: TypeId,
+        artifact_name: &str,
+    ) -> Result<ArtifactId, PostError> {
+        let options = ArtifactOptions::new().name(artifact_name);
+        self.get_or_create_item(type_id, ItemOptions::Artifact(options))
+            .await
+            .map(ArtifactId::new)
+    }
+
     /// Makes a request builder to create a new execution.
-    pub fn post_execution(&mut self, type_id: TypeId) -> requests::PostExecutionRequest {
+    pub fn post_execution(&mut self, type_id: TypeId) -> requests::PostExecutionRequest<'_> {
         requests::PostExecutionRequest::new(self, type_id)
     }
 
     /// Makes a request builder to update an execution.
-    pub fn put_execution(&mut self, execution_id: ExecutionId) -> requests::PutExecutionRequest {
+    pub fn put_execution(
+        &mut self,
+        execution_id: ExecutionId,
+    ) -> requests::PutExecutionRequest<'_> {
         requests::PutExecutionRequest::new(self, execution_id)
     }
 
     /// Makes a request builder to get executions.
-    pub fn get_executions(&mut self) -> requests::GetExecutionsRequest {
+    pub fn get_executions(&self) -> requests::GetExecutionsRequest<'_> {
         requests::GetExecutionsRequest::new(self)
     }
 
+    /// Gets the execution that has the given identifier.
+    ///
+    /// If there is no such execution, [`None`] is returned.
+    pub async fn get_execution(
+        &self,
+        execution_id: ExecutionId,
+    ) -> Result<Option<Execution>, GetError> {
+        let options = GetExecutionsOptions {
+            execution_ids: vec![execution_id].into_iter().collect(),
+            ..Default::default()
+        };
+        let items = self
+            .execute_get_items(GetItemsOptions::Execution(options))
+            .await?;
+        Ok(items.into_iter().next())
+    }
+
     /// Makes a request builder to create a new context.
     pub fn post_context(
         &mut self,
         type_id: TypeId,
         context_name: &str,
-    ) -> requests::PostContextRequest {
+    ) -> requests::PostContextRequest<'_> {
         requests::PostContextRequest::new(self, type_id, context_name)
     }
 
+    /// Gets the ID of the context that has the given type and name, creating it if absent.
+    ///
+    /// If another client creates the same context concurrently, the ID of that context is returned.
+    pub async fn get_or_create_context(
+        &mut self,
+        type_id: TypeId,
+        context_name: &str,
+    ) -> Result<ContextId, PostError> {
+        let options = ContextOptions {
+            name: Some(context_name.to_owned()),
+            ..Default::default()
+        };
+        self.get_or_create_item(type_id, ItemOptions::Context(options))
+            .await
+            .map(ContextId::new)
+    }
+
     /// Makes a request builder to update a context.
-    pub fn put_context(&mut self, context_id: ContextId) -> requests::PutContextRequest {
+    pub fn put_context(&mut self, context_id: ContextId) -> requests::PutContextRequest<'_> {
         requests::PutContextRequest::new(self, context_id)
     }
 
     /// Makes a request builder to get contexts.
-    pub fn get_contexts(&mut self) -> requests::GetContextsRequest {
+    pub fn get_contexts(&self) -> requests::GetContextsRequest<'_> {
         requests::GetContextsRequest::new(self)
     }
 
+    /// Gets the context that has the given identifier.
+    ///
+    /// If there is no such context, [`None`] is returned.
+    pub async fn get_context(&self, context_id: ContextId) -> Result<Option<Context>, GetError> {
+        let options = GetContextsOptions {
+            context_ids: vec![context_id].into_iter().collect(),
+            ..Default::default()
+        };
+        let items = self
+            .execute_get_items(GetItemsOptions::Context(options))
+            .await?;
+        Ok(items.into_iter().next())
+    }
+
     /// Makes a request builder to create a new attribution.
     pub fn put_attribution(
         &mut self,
         context_id: ContextId,
         artifact_id: ArtifactId,
-    ) -> requests::PutAttributionRequest {
+    ) -> requests::PutAttributionRequest<'_> {
         requests::PutAttributionRequest::new(self, context_id, artifact_id)
     }
 
@@ -139,52 +647,1032 @@ impl MetadataStore {
         &mut self,
         context_id: ContextId,
         execution_id: ExecutionId,
-    ) -> requests::PutAssociationRequest {
+    ) -> requests::PutAssociationRequest<'_> {
         requests::PutAssociationRequest::new(self, context_id, execution_id)
     }
 
-    /// Makes a request builder to create a new event.
-    pub fn put_event(
-        &mut self,
-        execution_id: ExecutionId,
-        artifact_id: ArtifactId,
-    ) -> requests::PutEventRequest {
-        requests::PutEventRequest::new(self, execution_id, artifact_id)
+    /// Gets the IDs of the contexts to which the given artifact is attributed.
+    ///
+    /// This is lighter than `get_contexts().artifact(artifact_id)` as it doesn't fetch the contexts themselves.
+    pub async fn get_context_ids_by_artifact(
+        &self,
+        artifact_id: ArtifactId,
+    ) -> Result<Vec<ContextId>, GetError> {
+        let sql = self.query.get_context_ids_by_artifact();
+        query::trace_sql(sql, 1);
+        let ids: Vec<i32> = sqlx::query_scalar(sql)
+            .bind(artifact_id.get())
+            .fetch_all(&mut *self.connection().await?)
+            .await?;
+        Ok(ids.into_iter().map(ContextId::new).collect())
+    }
+
+    /// Gets the IDs of the contexts with which the given execution is associated.
+    ///
+    /// This is lighter than `get_contexts().execution(execution_id)` as it doesn't fetch the contexts themselves.
+    pub async fn get_context_ids_by_execution(
+        &self,
+        execution_id: ExecutionId,
+    ) -> Result<Vec<ContextId>, GetError> {
+        let sql = self.query.get_context_ids_by_execution();
+        query::trace_sql(sql, 1);
+        let ids: Vec<i32> = sqlx::query_scalar(sql)
+            .bind(execution_id.get())
+            .fetch_all(&mut *self.connection().await?)
+            .await?;
+        Ok(ids.into_iter().map(ContextId::new).collect())
+    }
+
+    /// Makes a request builder to create a new parent-child relationship between contexts.
+    ///
+    /// A context cannot be the parent of itself.
+    pub fn put_parent_context(
+        &mut self,
+        parent_context_id: ContextId,
+        child_context_id: ContextId,
+    ) -> requests::PutParentContextRequest<'_> {
+        requests::PutParentContextRequest::new(self, parent_context_id, child_context_id)
+    }
+
+    /// Gets the parent contexts of the given context.
+    ///
+    /// This is a shorthand for `get_contexts().child(context_id).execute()`.
+    pub async fn get_parent_contexts_by_context(
+        &self,
+        context_id: ContextId,
+    ) -> Result<Vec<Context>, GetError> {
+        self.get_contexts().child(context_id).execute().await
+    }
+
+    /// Gets the child contexts of the given context.
+    ///
+    /// This is a shorthand for `get_contexts().parent(context_id).execute()`.
+    pub async fn get_child_contexts_by_context(
+        &self,
+        context_id: ContextId,
+    ) -> Result<Vec<Context>, GetError> {
+        self.get_contexts().parent(context_id).execute().await
+    }
+
+    /// Makes a request builder to create a new event.
+    pub fn put_event(
+        &mut self,
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+    ) -> requests::PutEventRequest<'_> {
+        requests::PutEventRequest::new(self, execution_id, artifact_id)
+    }
+
+    /// Replaces the path of an existing event.
+    ///
+    /// An event is identified by its execution, artifact and type.
+    /// Note that [`MetadataStore::put_event`] fails with [`PutError::EventAlreadyExists`] if the same event already exists,
+    /// so use this method to change the path of an event.
+    pub async fn update_event_path(
+        &mut self,
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+        ty: EventType,
+        path: impl IntoIterator<Item = EventStep>,
+    ) -> Result<(), PutError> {
+        let path = path.into_iter().collect::<Vec<_>>();
+        self.with_retry(|| self.update_event_path_once(execution_id, artifact_id, ty, &path))
+            .await
+    }
+
+    async fn update_event_path_once(
+        &self,
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+        ty: EventType,
+        path: &[EventStep],
+    ) -> Result<(), PutError> {
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
+
+        let sql = self.query.get_event_ids();
+        query::trace_sql(sql, 3);
+        let event_ids: Vec<i32> = sqlx::query_scalar(sql)
+            .bind(artifact_id.get())
+            .bind(execution_id.get())
+            .bind(ty as i32)
+            .fetch_all(&mut *connection)
+            .await?;
+        if event_ids.is_empty() {
+            return Err(PutError::EventNotFound {
+                execution_id,
+                artifact_id,
+                ty,
+            });
+        }
+
+        for event_id in event_ids {
+            let sql = self.query.delete_event_path();
+            query::trace_sql(sql, 1);
+            sqlx::query(sql)
+                .bind(event_id)
+                .execute(&mut *connection)
+                .await?;
+            for step in path {
+                let sql = self.query.insert_event_path(step);
+                query::trace_sql(sql, 2);
+                let query = match step {
+                    EventStep::Index(v) => sqlx::query(sql).bind(event_id).bind(*v),
+                    EventStep::Key(v) => sqlx::query(sql).bind(event_id).bind(v),
+                };
+                query.execute(&mut *connection).await?;
+            }
+        }
+
+        connection.commit().await?;
+        Ok(())
+    }
+
+    /// Makes a request builder to get events.
+    pub fn get_events(&self) -> requests::GetEventsRequest<'_> {
+        requests::GetEventsRequest::new(self)
+    }
+
+    /// Returns the earliest and latest creation times of the artifacts.
+    ///
+    /// If `type_name` is specified, only the artifacts of the type are considered.
+    /// If there are no such artifacts, [`None`] is returned.
+    pub async fn artifact_time_bounds(
+        &self,
+        type_name: Option<&str>,
+    ) -> Result<Option<(Duration, Duration)>, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.artifact_time_bounds(type_name.is_some());
+        query::trace_sql(sql, usize::from(type_name.is_some()));
+        let mut query = sqlx::query_as::<_, (Option<i64>, Option<i64>)>(sql);
+        if let Some(type_name) = type_name {
+            query = query.bind(type_name);
+        }
+        match query.fetch_one(&mut *connection).await? {
+            (Some(min), Some(max)) => Ok(Some((
+                Duration::from_millis(min as u64),
+                Duration::from_millis(max as u64),
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the values of the given properties of the artifacts of the given type as a table.
+    ///
+    /// Each row consists of an artifact ID and the property values aligned with `property_names`.
+    /// A property is looked up in the custom properties if it isn't one of the type properties,
+    /// and [`None`] is set for the artifacts that don't have it.
+    pub async fn project_artifacts(
+        &self,
+        type_name: &str,
+        property_names: &[&str],
+    ) -> Result<Vec<(ArtifactId, Vec<Option<PropertyValue>>)>, GetError> {
+        let artifacts = self
+            .get_artifacts()
+            .ty(type_name)
+            .only_properties(property_names)
+            .execute()
+            .await?;
+        Ok(artifacts
+            .into_iter()
+            .map(|mut artifact| {
+                let values = property_names
+                    .iter()
+                    .map(|&name| {
+                        artifact
+                            .properties
+                            .remove(name)
+                            .or_else(|| artifact.custom_properties.remove(name))
+                    })
+                    .collect();
+                (artifact.id, values)
+            })
+            .collect())
+    }
+
+    /// Returns the artifacts that are inputs or outputs of executions of the given type.
+    ///
+    /// If `event_types` is specified, only the artifacts connected by the events of those types are returned.
+    /// Each artifact is included only once, even if it is touched by multiple executions.
+    pub async fn artifacts_touched_by_execution_type(
+        &self,
+        type_name: &str,
+        event_types: Option<Vec<EventType>>,
+    ) -> Result<Vec<Artifact>, GetError> {
+        if event_types.as_ref().is_some_and(|x| x.is_empty()) {
+            return Ok(Vec::new());
+        }
+        let event_types = event_types.unwrap_or_default();
+        let sql = self
+            .query
+            .get_artifact_ids_by_execution_type(event_types.len());
+        query::trace_sql(&sql, 1 + event_types.len());
+        let mut query = sqlx::query_scalar::<_, i32>(&sql).bind(type_name);
+        for ty in &event_types {
+            query = query.bind(*ty as i32);
+        }
+        let artifact_ids = query.fetch_all(&mut *self.connection().await?).await?;
+        if artifact_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let options = GetArtifactsOptions {
+            artifact_ids: artifact_ids.into_iter().map(ArtifactId::new).collect(),
+            ..Default::default()
+        };
+        self.execute_get_items(GetItemsOptions::Artifact(options))
+            .await
+    }
+
+    /// Returns the execution that most recently output the given artifact.
+    ///
+    /// The execution is determined by the newest output event (i.e., [`EventType::is_output`] returns `true`)
+    /// of the artifact (if multiple events have the same timestamp, the one added last is chosen).
+    /// If there is no such event, [`None`] is returned.
+    pub async fn latest_producing_execution(
+        &self,
+        artifact_id: ArtifactId,
+    ) -> Result<Option<Execution>, GetError> {
+        let sql = self
+            .query
+            .get_latest_producing_execution_id(OUTPUT_EVENT_TYPES.len());
+        query::trace_sql(&sql, 1 + OUTPUT_EVENT_TYPES.len());
+        let mut query = sqlx::query_scalar(&sql).bind(artifact_id.get());
+        for ty in OUTPUT_EVENT_TYPES {
+            query = query.bind(ty as i32);
+        }
+        let execution_id: Option<i32> =
+            query.fetch_optional(&mut *self.connection().await?).await?;
+        if let Some(execution_id) = execution_id {
+            self.get_execution(ExecutionId::new(execution_id)).await
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the input and output artifacts of each of the given executions.
+    ///
+    /// The first set of each entry contains the artifacts connected by input events
+    /// (i.e., [`EventType::is_input`] returns `true`) and the second one contains those connected by output events
+    /// (i.e., [`EventType::is_output`] returns `true`).
+    /// Events of [`EventType::Unknown`] are ignored.
+    ///
+    /// Every given execution has an entry in the result even if it has no events.
+    pub async fn execution_io(
+        &self,
+        execution_ids: impl Iterator<Item = ExecutionId>,
+    ) -> Result<BTreeMap<ExecutionId, (BTreeSet<ArtifactId>, BTreeSet<ArtifactId>)>, GetError> {
+        let mut io = execution_ids
+            .map(|id| (id, (BTreeSet::new(), BTreeSet::new())))
+            .collect::<BTreeMap<_, _>>();
+        if io.is_empty() {
+            return Ok(io);
+        }
+
+        let events = self
+            .get_events()
+            .executions(io.keys().copied())
+            .execute()
+            .await?;
+        for event in events {
+            let (inputs, outputs) = io.get_mut(&event.execution_id).expect("bug");
+            if event.ty.is_input() {
+                inputs.insert(event.artifact_id);
+            } else if event.ty.is_output() {
+                outputs.insert(event.artifact_id);
+            }
+        }
+        Ok(io)
+    }
+
+    /// Validates the input and output artifacts of the given execution against the types declared by its execution type.
+    ///
+    /// The artifacts connected by input (resp. output) events are arranged by their event paths and
+    /// checked against [`ExecutionType::input_type`] (resp. [`ExecutionType::output_type`]),
+    /// which is the JSON representation of ml-metadata's `ArtifactStructType` (e.g., `{"simple": {"name": "Model"}}`).
+    /// An undeclared type is regarded as `{"any": {}}`.
+    ///
+    /// Returns the list of the found violations (empty if the execution conforms to its type),
+    /// or [`None`] if the execution doesn't exist.
+    #[cfg(feature = "serde")]
+    pub async fn validate_execution_io(
+        &self,
+        execution_id: ExecutionId,
+    ) -> Result<Option<Vec<ExecutionIoViolation>>, GetError> {
+        let Some(execution) = self.get_execution(execution_id).await? else {
+            return Ok(None);
+        };
+        let Some(execution_type) = self
+            .get_execution_types()
+            .id(execution.type_id)
+            .execute()
+            .await?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+
+        let events = self.get_events().execution(execution_id).execute().await?;
+        let artifact_ids = events
+            .iter()
+            .map(|e| e.artifact_id)
+            .collect::<BTreeSet<_>>();
+        let artifacts = if artifact_ids.is_empty() {
+            Vec::new()
+        } else {
+            self.get_artifacts()
+                .ids(artifact_ids.into_iter())
+                .execute()
+                .await?
+        };
+        let artifact_types = self
+            .get_artifact_types_by_ids(artifacts.iter().map(|a| a.type_id))
+            .await?;
+        let type_names = artifacts
+            .iter()
+            .map(|a| {
+                let name = artifact_types.get(&a.type_id).map(|t| t.name.as_str());
+                (a.id, name.unwrap_or_default())
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut inputs = artifact_struct::ArtifactTree::default();
+        let mut outputs = artifact_struct::ArtifactTree::default();
+        for event in &events {
+            let tree = if event.ty.is_input() {
+                &mut inputs
+            } else if event.ty.is_output() {
+                &mut outputs
+            } else {
+                continue;
+            };
+            let type_name = type_names.get(&event.artifact_id).copied();
+            tree.insert(
+                &event.path,
+                event.artifact_id,
+                type_name.unwrap_or_default(),
+            );
+        }
+
+        let mut violations = Vec::new();
+        for (is_output, tree, ty) in [
+            (false, inputs, execution_type.input_type),
+            (true, outputs, execution_type.output_type),
+        ] {
+            let Some(ty) = ty else {
+                continue;
+            };
+            violations.extend(tree.validate(&ty).into_iter().map(|(path, reason)| {
+                ExecutionIoViolation {
+                    is_output,
+                    path,
+                    reason,
+                }
+            }));
+        }
+        Ok(Some(violations))
+    }
+
+    /// Returns the lineage graph reachable from the given item by following at most `max_hops` events.
+    ///
+    /// Events are followed in both directions (i.e., from an artifact to the executions that consumed or produced it,
+    /// and from an execution to its input and output artifacts), and each item is visited at most once.
+    /// If `id` is a context, the traversal starts from the artifacts and executions attributed to or associated with it.
+    ///
+    /// Only the events visited by the traversal are included in the result,
+    /// so events between two items found at the last hop are omitted.
+    pub async fn get_lineage(&self, id: Id, max_hops: usize) -> Result<Lineage, GetError> {
+        let mut artifact_ids = BTreeSet::new();
+        let mut execution_ids = BTreeSet::new();
+        match id {
+            Id::Artifact(id) => {
+                artifact_ids.insert(id);
+            }
+            Id::Execution(id) => {
+                execution_ids.insert(id);
+            }
+            Id::Context(id) => {
+                let artifacts = self.get_artifacts().context(id).execute().await?;
+                artifact_ids.extend(artifacts.into_iter().map(|a| a.id));
+                let executions = self.get_executions().context(id).execute().await?;
+                execution_ids.extend(executions.into_iter().map(|e| e.id));
+            }
+        }
+
+        let mut events = BTreeMap::new();
+        let mut artifact_frontier = artifact_ids.clone();
+        let mut execution_frontier = execution_ids.clone();
+        for _ in 0..max_hops {
+            if artifact_frontier.is_empty() && execution_frontier.is_empty() {
+                break;
+            }
+
+            let mut found = Vec::new();
+            if !artifact_frontier.is_empty() {
+                found.extend(
+                    self.get_events()
+                        .artifacts(std::mem::take(&mut artifact_frontier).into_iter())
+                        .execute()
+                        .await?,
+                );
+            }
+            if !execution_frontier.is_empty() {
+                found.extend(
+                    self.get_events()
+                        .executions(std::mem::take(&mut execution_frontier).into_iter())
+                        .execute()
+                        .await?,
+                );
+            }
+
+            for event in found {
+                if artifact_ids.insert(event.artifact_id) {
+                    artifact_frontier.insert(event.artifact_id);
+                }
+                if execution_ids.insert(event.execution_id) {
+                    execution_frontier.insert(event.execution_id);
+                }
+                events.insert((event.artifact_id, event.execution_id, event.ty), event);
+            }
+        }
+
+        let mut lineage = Lineage {
+            events: events.into_values().collect(),
+            ..Default::default()
+        };
+        if !artifact_ids.is_empty() {
+            lineage.artifacts = self
+                .get_artifacts()
+                .ids(artifact_ids.into_iter())
+                .execute()
+                .await?;
+        }
+        if !execution_ids.is_empty() {
+            lineage.executions = self
+                .get_executions()
+                .ids(execution_ids.into_iter())
+                .execute()
+                .await?;
+        }
+        Ok(lineage)
+    }
+
+    /// Returns the artifacts attributed to the given context, the executions associated with it and
+    /// the events between them.
+    ///
+    /// Events connecting an artifact or an execution to an item outside of the context are not included.
+    pub async fn get_context_contents(
+        &self,
+        context_id: ContextId,
+    ) -> Result<(Vec<Artifact>, Vec<Execution>, Vec<Event>), GetError> {
+        let artifacts = self.get_artifacts().context(context_id).execute().await?;
+        let executions = self.get_executions().context(context_id).execute().await?;
+        if artifacts.is_empty() || executions.is_empty() {
+            return Ok((artifacts, executions, Vec::new()));
+        }
+
+        let events = self
+            .get_events()
+            .artifacts(artifacts.iter().map(|a| a.id))
+            .executions(executions.iter().map(|e| e.id))
+            .execute()
+            .await?;
+        Ok((artifacts, executions, events))
+    }
+
+    /// Returns the artifact types having the given IDs.
+    ///
+    /// The types are fetched at once and IDs that don't exist are just omitted from the result.
+    pub async fn get_artifact_types_by_ids(
+        &self,
+        type_ids: impl Iterator<Item = TypeId>,
+    ) -> Result<HashMap<TypeId, ArtifactType>, GetError> {
+        let type_ids = type_ids.collect::<BTreeSet<_>>();
+        if type_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let types = self
+            .get_artifact_types()
+            .ids(type_ids.into_iter())
+            .execute()
+            .await?;
+        Ok(types.into_iter().map(|ty| (ty.id, ty)).collect())
+    }
+
+    /// Returns the execution types having the given IDs.
+    ///
+    /// The types are fetched at once and IDs that don't exist are just omitted from the result.
+    pub async fn get_execution_types_by_ids(
+        &self,
+        type_ids: impl Iterator<Item = TypeId>,
+    ) -> Result<HashMap<TypeId, ExecutionType>, GetError> {
+        let type_ids = type_ids.collect::<BTreeSet<_>>();
+        if type_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let types = self
+            .get_execution_types()
+            .ids(type_ids.into_iter())
+            .execute()
+            .await?;
+        Ok(types.into_iter().map(|ty| (ty.id, ty)).collect())
+    }
+
+    /// Returns the context types having the given IDs.
+    ///
+    /// The types are fetched at once and IDs that don't exist are just omitted from the result.
+    pub async fn get_context_types_by_ids(
+        &self,
+        type_ids: impl Iterator<Item = TypeId>,
+    ) -> Result<HashMap<TypeId, ContextType>, GetError> {
+        let type_ids = type_ids.collect::<BTreeSet<_>>();
+        if type_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let types = self
+            .get_context_types()
+            .ids(type_ids.into_iter())
+            .execute()
+            .await?;
+        Ok(types.into_iter().map(|ty| (ty.id, ty)).collect())
+    }
+
+    /// Returns the names of the types of the given kind in ascending order.
+    ///
+    /// Unlike the `get_*_types` methods, this doesn't fetch the property definitions of the types.
+    pub async fn get_type_names(&self, type_kind: TypeKind) -> Result<Vec<String>, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.get_type_names();
+        query::trace_sql(sql, 1);
+        let names = sqlx::query_as::<_, (String,)>(sql)
+            .bind(type_kind as i32)
+            .fetch_all(&mut *connection)
+            .await?;
+        Ok(names.into_iter().map(|(name,)| name).collect())
+    }
+
+    /// Returns the names of the custom properties that the items of the given type have.
+    ///
+    /// As custom properties aren't declared by types, this is useful to know which columns
+    /// are needed to display the items of a type.
+    pub async fn get_custom_property_names(
+        &self,
+        type_kind: TypeKind,
+        type_id: TypeId,
+    ) -> Result<BTreeSet<String>, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.get_custom_property_names(type_kind);
+        query::trace_sql(&sql, 1);
+        let mut names = BTreeSet::new();
+        let mut rows = sqlx::query_as::<_, (String,)>(&sql)
+            .bind(type_id.get())
+            .fetch(&mut *connection);
+        while let Some((name,)) = rows.try_next().await? {
+            names.insert(name);
+        }
+        Ok(names)
+    }
+
+    /// Returns the number of items (artifacts, executions or contexts) for each type.
+    ///
+    /// Types that have no items are not included in the result.
+    pub async fn count_items_by_type(
+        &self,
+        type_kind: TypeKind,
+    ) -> Result<BTreeMap<TypeId, usize>, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.count_items_by_type(type_kind);
+        query::trace_sql(&sql, 0);
+        let mut counts = BTreeMap::new();
+        let mut rows = sqlx::query_as::<_, (i32, i64)>(&sql).fetch(&mut *connection);
+        while let Some((type_id, count)) = rows.try_next().await? {
+            counts.insert(TypeId::new(type_id), count as usize);
+        }
+        Ok(counts)
+    }
+
+    /// Returns the number of events for each event type.
+    ///
+    /// Event types that have no events are not included in the result.
+    pub async fn event_type_counts(&self) -> Result<BTreeMap<EventType, usize>, GetError> {
+        let mut connection = self.connection().await?;
+        let mut counts = BTreeMap::new();
+        let sql = self.query.count_events_by_type();
+        query::trace_sql(sql, 0);
+        let mut rows = sqlx::query_as::<_, (i32, i64)>(sql).fetch(&mut *connection);
+        while let Some((ty, count)) = rows.try_next().await? {
+            counts.insert(EventType::from_i32(ty)?, count as usize);
+        }
+        Ok(counts)
+    }
+
+    /// Returns the number of artifacts for each artifact state.
+    ///
+    /// If `type_name` is specified, only the artifacts of the type are counted.
+    /// States that have no artifacts are not included in the result.
+    pub async fn count_artifacts_by_state(
+        &self,
+        type_name: Option<&str>,
+    ) -> Result<BTreeMap<ArtifactState, usize>, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.count_artifacts_by_state(type_name.is_some());
+        query::trace_sql(&sql, usize::from(type_name.is_some()));
+        let mut counts = BTreeMap::new();
+        let mut query = sqlx::query_as::<_, (i32, i64)>(&sql);
+        if let Some(name) = type_name {
+            query = query.bind(name);
+        }
+        let mut rows = query.fetch(&mut *connection);
+        while let Some((state, count)) = rows.try_next().await? {
+            counts.insert(ArtifactState::from_i32(state)?, count as usize);
+        }
+        Ok(counts)
+    }
+
+    /// Returns the number of executions for each last known state.
+    ///
+    /// If `type_name` is specified, only the executions of the type are counted.
+    /// States that have no executions are not included in the result.
+    pub async fn count_executions_by_state(
+        &self,
+        type_name: Option<&str>,
+    ) -> Result<BTreeMap<ExecutionState, usize>, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.count_executions_by_state(type_name.is_some());
+        query::trace_sql(&sql, usize::from(type_name.is_some()));
+        let mut counts = BTreeMap::new();
+        let mut query = sqlx::query_as::<_, (i32, i64)>(&sql);
+        if let Some(name) = type_name {
+            query = query.bind(name);
+        }
+        let mut rows = query.fetch(&mut *connection);
+        while let Some((state, count)) = rows.try_next().await? {
+            counts.insert(ExecutionState::from_i32(state)?, count as usize);
+        }
+        Ok(counts)
+    }
+
+    /// Returns the events that share the same artifact, execution and event type with other events.
+    ///
+    /// Each entry holds the artifact ID, the execution ID, the event type and the number of such events.
+    ///
+    /// Such duplicates can only exist in MySQL databases created by older versions of this crate,
+    /// where the `Event` table had no unique constraint.
+    /// [`MetadataStore::remove_duplicate_events`] removes them and then adds the missing constraint,
+    /// so that no duplicates can be inserted afterwards.
+    pub async fn find_duplicate_events(
+        &self,
+    ) -> Result<Vec<(ArtifactId, ExecutionId, EventType, usize)>, GetError> {
+        let mut connection = self.connection().await?;
+        let mut duplicates = Vec::new();
+        let sql = self.query.find_duplicate_events();
+        query::trace_sql(sql, 0);
+        let mut rows = sqlx::query_as::<_, (i32, i32, i32, i64)>(sql).fetch(&mut *connection);
+        while let Some((artifact_id, execution_id, ty, count)) = rows.try_next().await? {
+            duplicates.push((
+                ArtifactId::new(artifact_id),
+                ExecutionId::new(execution_id),
+                EventType::from_i32(ty)?,
+                count as usize,
+            ));
+        }
+        Ok(duplicates)
+    }
+
+    /// Removes the duplicate events found by [`MetadataStore::find_duplicate_events`].
+    ///
+    /// Of each set of duplicates, the earliest inserted event is kept.
+    /// After that, the unique constraint on the artifact ID, the execution ID and the event type
+    /// is added to the `Event` table if it's missing.
+    /// Returns the number of the removed events.
+    pub async fn remove_duplicate_events(&mut self) -> Result<usize, PutError> {
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
+
+        let sql = self.query.get_duplicate_event_ids();
+        query::trace_sql(sql, 0);
+        let event_ids: Vec<i32> = sqlx::query_scalar(sql).fetch_all(&mut *connection).await?;
+        if !event_ids.is_empty() {
+            let (delete_event_paths, delete_events) = self.query.delete_events(event_ids.len());
+            for sql in [&delete_event_paths, &delete_events] {
+                query::trace_sql(sql, event_ids.len());
+                let mut query = sqlx::query(sql);
+                for id in &event_ids {
+                    query = query.bind(*id);
+                }
+                query.execute(&mut *connection).await?;
+            }
+        }
+        connection.commit().await?;
+
+        // MySQL implicitly commits DDL statements, so this is executed after the deletion is committed.
+        let (probe, statement) = self.query.add_event_unique_constraint();
+        query::trace_sql(probe, 0);
+        let applied: i64 = sqlx::query_scalar(probe).fetch_one(&mut *guard).await?;
+        if applied == 0 {
+            query::trace_sql(statement, 0);
+            sqlx::query(statement).execute(&mut *guard).await?;
+        }
+        Ok(event_ids.len())
+    }
+
+    /// Returns a stream of all the types (with their properties) in the database.
+    ///
+    /// The types are yielded in the order of artifact, execution and context types,
+    /// and are fetched from the database in bounded batches rather than all at once.
+    pub fn stream_all_types(&self) -> impl Stream<Item = Result<AnyType, GetError>> + '_ {
+        const KINDS: [TypeKind; 3] = [TypeKind::Artifact, TypeKind::Execution, TypeKind::Context];
+        futures::stream::try_unfold((0, 0), move |(mut i, mut last_id)| async move {
+            while let Some(&kind) = KINDS.get(i) {
+                let sql = self.query.get_type_ids_after(TYPE_BATCH_SIZE);
+                query::trace_sql(&sql, 2);
+                let ids: Vec<i32> = sqlx::query_scalar(&sql)
+                    .bind(kind as i32)
+                    .bind(last_id)
+                    .fetch_all(&mut *self.connection().await?)
+                    .await?;
+                let Some(&next_last_id) = ids.last() else {
+                    i += 1;
+                    last_id = 0;
+                    continue;
+                };
+                let ids = ids.into_iter().map(TypeId::new);
+                let types = match kind {
+                    TypeKind::Artifact => (self.get_artifact_types().ids(ids).execute().await?)
+                        .into_iter()
+                        .map(AnyType::Artifact)
+                        .collect::<Vec<_>>(),
+                    TypeKind::Execution => (self.get_execution_types().ids(ids).execute().await?)
+                        .into_iter()
+                        .map(AnyType::Execution)
+                        .collect(),
+                    TypeKind::Context => (self.get_context_types().ids(ids).execute().await?)
+                        .into_iter()
+                        .map(AnyType::Context)
+                        .collect(),
+                };
+                return Ok(Some((types, (i, next_last_id))));
+            }
+            Ok::<_, GetError>(None)
+        })
+        .map_ok(|types| futures::stream::iter(types.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Fetches all the types, items and events in the database at once
+    /// and returns an in-memory snapshot of them.
+    ///
+    /// Note that the snapshot is built by issuing multiple queries,
+    /// so it may be inconsistent if the database is updated concurrently.
+    pub async fn snapshot(&self) -> Result<Snapshot, GetError> {
+        Ok(Snapshot {
+            artifact_types: self.get_artifact_types().execute().await?,
+            execution_types: self.get_execution_types().execute().await?,
+            context_types: self.get_context_types().execute().await?,
+            artifacts: self.get_artifacts().execute().await?,
+            executions: self.get_executions().execute().await?,
+            contexts: self.get_contexts().execute().await?,
+            events: self.get_events().execute().await?,
+        })
+    }
+
+    /// Exports all the types, items, events, attributions, associations and parent relations in the database as JSON.
+    ///
+    /// The returned JSON is an object having the fields `artifactTypes`, `executionTypes`, `contextTypes`,
+    /// `artifacts`, `executions`, `contexts`, `events`, `attributions`, `associations`,
+    /// `parentTypes` and `parentContexts`
+    /// (named after the responses of `MetadataStoreService` of ml-metadata).
+    /// Each field is an array of the corresponding ml-metadata messages
+    /// encoded in the canonical protobuf JSON format.
+    ///
+    /// As with [`MetadataStore::snapshot`], the result may be inconsistent if the database is updated concurrently.
+    #[cfg(feature = "serde")]
+    pub async fn export_json(&self) -> Result<String, GetError> {
+        let snapshot = self.snapshot().await?;
+        let mut connection = self.connection().await?;
+        let sql = self.query.get_attributions();
+        query::trace_sql(sql, 0);
+        let attributions = sqlx::query_as::<_, (i32, i32)>(sql)
+            .fetch_all(&mut *connection)
+            .await?;
+        let sql = self.query.get_associations();
+        query::trace_sql(sql, 0);
+        let associations = sqlx::query_as::<_, (i32, i32)>(sql)
+            .fetch_all(&mut *connection)
+            .await?;
+        let sql = self.query.get_parent_types();
+        query::trace_sql(sql, 0);
+        let parent_types = sqlx::query_as::<_, (i32, i32)>(sql)
+            .fetch_all(&mut *connection)
+            .await?;
+        let sql = self.query.get_parent_contexts();
+        query::trace_sql(sql, 0);
+        let parent_contexts = sqlx::query_as::<_, (i32, i32)>(sql)
+            .fetch_all(&mut *connection)
+            .await?;
+        let dump = proto_json::Dump::new(
+            &snapshot,
+            &attributions,
+            &associations,
+            &parent_types,
+            &parent_contexts,
+        );
+        Ok(serde_json::to_string(&dump).expect("bug"))
+    }
+
+    /// Imports a JSON dump created by [`MetadataStore::export_json`].
+    ///
+    /// Types are put by name (adding or omitting properties as needed), and then
+    /// artifacts, executions, contexts, events, attributions, associations and parent relations are created
+    /// with their original timestamps.
+    /// As new IDs are assigned to the imported items, the references between them are rewritten accordingly.
+    ///
+    /// All the requests are executed in a single transaction,
+    /// so nothing is imported if an error occurs.
+    #[cfg(feature = "serde")]
+    pub async fn import_json(&mut self, json: &str) -> Result<ImportSummary, ImportError> {
+        let dump: proto_json::Dump = serde_json::from_str(json)?;
+        let mut transaction = self.transaction().await?;
+        let summary = dump.import(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(summary)
+    }
+
+    fn check_uri_length(&self, options: &ItemOptions) -> Option<(usize, usize)> {
+        let len = options.uri()?.len();
+        let max = self.max_uri_length?;
+        (len > max).then_some((len, max))
+    }
+
+    async fn with_retry<F, Fut, T, E>(&self, mut f: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: AsDbError,
+    {
+        let mut retries = 0;
+        loop {
+            match f().await {
+                // A transaction of the user can't be retried partially.
+                Err(e)
+                    if retries < self.max_retries
+                        && self.transaction.is_none()
+                        && e.as_db_error().is_some_and(is_retryable) =>
+                {
+                    tokio::time::sleep(
+                        self.retry_base_delay
+                            .saturating_mul(2u32.saturating_pow(retries)),
+                    )
+                    .await;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn connection(&self) -> Result<ConnectionGuard<'_>, sqlx::Error> {
+        if let Some(transaction) = &self.transaction {
+            Ok(ConnectionGuard::Transaction(transaction.lock().await))
+        } else {
+            Ok(ConnectionGuard::Pool(Box::new(self.pool.acquire().await?)))
+        }
+    }
+
+    async fn get_or_create_item(
+        &self,
+        type_id: TypeId,
+        options: ItemOptions,
+    ) -> Result<i32, PostError> {
+        let type_kind = options.type_kind();
+        let item_name = options.name().expect("bug").to_owned();
+        let get_item_id = || async {
+            let mut connection = self.connection().await?;
+            let sql = self.query.get_item_id_by_name(type_kind);
+            query::trace_sql(&sql, 2);
+            sqlx::query_scalar::<_, i32>(&sql)
+                .bind(type_id.get())
+                .bind(&item_name)
+                .fetch_optional(&mut *connection)
+                .await
+        };
+
+        if let Some(item_id) = get_item_id().await? {
+            return Ok(item_id);
+        }
+        match self.execute_post_item(type_id, options.clone()).await {
+            Err(PostError::NameAlreadyExists { .. }) => {
+                // Another client has created the item after the above lookup.
+                let item_id = get_item_id()
+                    .await?
+                    .ok_or_else(|| PostError::NameAlreadyExists {
+                        type_kind,
+                        item_name: item_name.clone(),
+                    })?;
+                Ok(item_id)
+            }
+            result => result,
+        }
+    }
+
+    async fn get_type_id_by_name(
+        &self,
+        type_kind: TypeKind,
+        type_name: &str,
+    ) -> Result<Option<TypeId>, GetError> {
+        let mut connection = self.connection().await?;
+        let (sql, args) = self.query.get_type_by_name(type_kind, type_name, None);
+        query::trace_sql(sql, args.len());
+        let ty = sqlx::query_as_with::<_, query::Type, _>(sql, args.into_inner())
+            .fetch_optional(&mut *connection)
+            .await?;
+        Ok(ty.map(|ty| TypeId::new(ty.id)))
+    }
+
+    async fn type_not_found_error(
+        &self,
+        type_kind: TypeKind,
+        type_id: TypeId,
+    ) -> Result<PostError, GetError> {
+        let mut connection = self.connection().await?;
+        let sql = self.query.get_type_kind();
+        query::trace_sql(sql, 1);
+        let actual: Option<i32> = sqlx::query_scalar(sql)
+            .bind(type_id.get())
+            .fetch_optional(&mut *connection)
+            .await?;
+        match actual.map(TypeKind::from_i32).transpose()? {
+            Some(actual) if actual != type_kind => Ok(PostError::TypeKindMismatch {
+                type_id,
+                expected: type_kind,
+                actual,
+            }),
+            _ => Ok(PostError::TypeNotFound { type_kind, type_id }),
+        }
     }
 
-    /// Makes a request builder to get events.
-    pub fn get_events(&mut self) -> requests::GetEventsRequest {
-        requests::GetEventsRequest::new(self)
+    pub(crate) async fn execute_post_item(
+        &self,
+        type_id: TypeId,
+        options: ItemOptions,
+    ) -> Result<i32, PostError> {
+        self.with_retry(|| self.execute_post_item_once(type_id, options.clone()))
+            .await
     }
 
-    pub(crate) async fn execute_post_item(
-        &mut self,
+    async fn execute_post_item_once(
+        &self,
         type_id: TypeId,
         options: ItemOptions,
     ) -> Result<i32, PostError> {
+        if let Some(name) = options.name().filter(|name| !is_valid_name(name)) {
+            return Err(PostError::InvalidName {
+                name: name.to_owned(),
+            });
+        }
+        if let Some(name) = invalid_property_name(&options) {
+            return Err(PostError::InvalidPropertyName { name });
+        }
+        if let Some((len, max)) = self.check_uri_length(&options) {
+            return Err(PostError::UriTooLong { len, max });
+        }
+
         let type_kind = options.type_kind();
-        let property_types = self
-            .get_type_properties(type_kind, type_id)
-            .await?
-            .ok_or(PostError::TypeNotFound { type_kind, type_id })?;
+        let property_types = match self.get_type_properties(type_kind, type_id).await? {
+            Some(property_types) => property_types,
+            None => return Err(self.type_not_found_error(type_kind, type_id).await?),
+        };
         for (name, value) in options.properties() {
-            if property_types.get(name).copied() != Some(value.ty()) {
-                return Err(PostError::UndefinedProperty {
-                    type_kind,
-                    type_id,
-                    property_name: name.clone(),
-                });
+            match property_types.get(name).copied() {
+                None => {
+                    return Err(PostError::UndefinedProperty {
+                        type_kind,
+                        type_id,
+                        property_name: name.clone(),
+                    });
+                }
+                Some(expected) if expected != value.ty() => {
+                    return Err(PostError::PropertyTypeMismatch {
+                        property_name: name.clone(),
+                        expected,
+                        actual: value.ty(),
+                    });
+                }
+                Some(_) => {}
             }
         }
 
-        let mut connection = self.connection.begin().await?;
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
 
         if let Some(item_name) = options.name() {
             let (sql, args) = self
                 .query
                 .check_item_name(type_kind, type_id, None, item_name);
-            let count: i32 = sqlx::query_scalar_with(&sql, args)
-                .fetch_one(&mut connection)
+            query::trace_sql(&sql, args.len());
+            let count: i32 = sqlx::query_scalar_with(&sql, args.into_inner())
+                .fetch_one(&mut *connection)
                 .await?;
             if count > 0 {
                 return Err(PostError::NameAlreadyExists {
@@ -195,12 +1683,24 @@ impl MetadataStore {
         }
 
         let (sql, args) = self.query.insert_item(type_id, &options);
-        sqlx::query_with(&sql, args)
-            .execute(&mut connection)
-            .await?;
+        query::trace_sql(&sql, args.len());
+        let result = sqlx::query_with(&sql, args.into_inner())
+            .execute(&mut *connection)
+            .await;
+        if let (Err(e), Some(item_name)) = (&result, options.name()) {
+            // Another client may have inserted an item with the same name after the above check.
+            if is_unique_violation(e) {
+                return Err(PostError::NameAlreadyExists {
+                    type_kind,
+                    item_name: item_name.to_owned(),
+                });
+            }
+        }
+        result?;
 
         let sql = self.query.get_last_item_id(type_kind);
-        let item_id: i32 = sqlx::query_scalar(&sql).fetch_one(&mut connection).await?;
+        query::trace_sql(&sql, 0);
+        let item_id: i32 = sqlx::query_scalar(&sql).fetch_one(&mut *connection).await?;
 
         let properties = options
             .properties()
@@ -219,8 +1719,9 @@ impl MetadataStore {
                 value,
                 is_custom,
             );
-            sqlx::query_with(&sql, args)
-                .execute(&mut connection)
+            query::trace_sql(&sql, args.len());
+            sqlx::query_with(&sql, args.into_inner())
+                .execute(&mut *connection)
                 .await?;
         }
 
@@ -229,29 +1730,75 @@ impl MetadataStore {
     }
 
     async fn get_type_properties(
-        &mut self,
+        &self,
         type_kind: TypeKind,
         type_id: TypeId,
     ) -> Result<Option<PropertyTypes>, GetError> {
-        Ok(self
+        // The cache isn't used in a transaction as it may see types that will be rolled back.
+        let cache = self
+            .type_cache
+            .as_ref()
+            .filter(|_| self.transaction.is_none());
+        if let Some(properties) =
+            cache.and_then(|cache| cache.lock().expect("poisoned").get(type_kind, type_id))
+        {
+            return Ok(Some(properties));
+        }
+
+        let properties = self
             .execute_get_types(
                 type_kind,
-                |_, _, properties| properties,
+                |_, properties| Ok(properties),
                 GetTypesOptions::by_id(type_id),
             )
             .await?
             .into_iter()
-            .next())
+            .next();
+        if let (Some(cache), Some(properties)) = (cache, &properties) {
+            cache
+                .lock()
+                .expect("poisoned")
+                .insert(type_kind, type_id, properties.clone());
+        }
+        Ok(properties)
+    }
+
+    fn invalidate_type_cache(&self, type_kind: TypeKind, type_id: TypeId) {
+        if let Some(cache) = &self.type_cache {
+            cache.lock().expect("poisoned").remove(type_kind, type_id);
+        }
     }
 
     pub(crate) async fn execute_put_item(
-        &mut self,
+        &self,
+        item_id: Id,
+        options: ItemOptions,
+    ) -> Result<(), PutError> {
+        self.with_retry(|| self.execute_put_item_once(item_id, options.clone()))
+            .await
+    }
+
+    async fn execute_put_item_once(
+        &self,
         item_id: Id,
         options: ItemOptions,
     ) -> Result<(), PutError> {
+        if let Some(name) = options.name().filter(|name| !is_valid_name(name)) {
+            return Err(PutError::InvalidName {
+                name: name.to_owned(),
+            });
+        }
+        if let Some(name) = invalid_property_name(&options) {
+            return Err(PutError::InvalidPropertyName { name });
+        }
+        if let Some((len, max)) = self.check_uri_length(&options) {
+            return Err(PutError::UriTooLong { item_id, len, max });
+        }
+
         let (sql, args) = self.query.get_type_id(item_id);
-        let type_id = sqlx::query_scalar_with(&sql, args)
-            .fetch_optional(&mut self.connection)
+        query::trace_sql(&sql, args.len());
+        let type_id = sqlx::query_scalar_with(&sql, args.into_inner())
+            .fetch_optional(&mut *self.connection().await?)
             .await?
             .map(TypeId::new)
             .ok_or(PutError::NotFound { item_id })?;
@@ -261,23 +1808,36 @@ impl MetadataStore {
             .await?
             .ok_or(PutError::TypeNotFound { type_id, item_id })?;
         for (name, value) in options.properties() {
-            if property_types.get(name).copied() != Some(value.ty()) {
-                return Err(PutError::UndefinedProperty {
-                    item_id,
-                    property_name: name.clone(),
-                    property_type: value.ty(),
-                });
+            match property_types.get(name).copied() {
+                None => {
+                    return Err(PutError::UndefinedProperty {
+                        item_id,
+                        property_name: name.clone(),
+                        property_type: value.ty(),
+                    });
+                }
+                Some(expected) if expected != value.ty() => {
+                    return Err(PutError::PropertyTypeMismatch {
+                        item_id,
+                        property_name: name.clone(),
+                        expected,
+                        actual: value.ty(),
+                    });
+                }
+                Some(_) => {}
             }
         }
 
-        let mut connection = self.connection.begin().await?;
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
 
         if let Some(item_name) = options.name() {
             let (sql, args) =
                 self.query
                     .check_item_name(item_id.kind(), type_id, Some(item_id), item_name);
-            let count: i32 = sqlx::query_scalar_with(&sql, args)
-                .fetch_one(&mut connection)
+            query::trace_sql(&sql, args.len());
+            let count: i32 = sqlx::query_scalar_with(&sql, args.into_inner())
+                .fetch_one(&mut *connection)
                 .await?;
             if count > 0 {
                 return Err(PutError::NameAlreadyExists {
@@ -287,11 +1847,57 @@ impl MetadataStore {
             }
         }
 
+        if options.strict_custom_properties() && !options.custom_properties().is_empty() {
+            let (sql, args) = self.query.get_item_properties(
+                item_id.kind(),
+                std::iter::once(item_id.get()),
+                None,
+            );
+            query::trace_sql(&sql, args.len());
+            let rows = sqlx::query_as_with::<_, query::Property, _>(&sql, args.into_inner())
+                .fetch_all(&mut *connection)
+                .await?;
+            for row in rows.into_iter().filter(|row| row.is_custom_property) {
+                let (name, stored) = row.into_name_and_vaue()?;
+                let Some(value) = options.custom_properties().get(&name) else {
+                    continue;
+                };
+                if storage_type(stored.ty()) != storage_type(value.ty()) {
+                    return Err(PutError::CustomPropertyTypeMismatch {
+                        item_id,
+                        property_name: name,
+                        expected: stored.ty(),
+                        actual: value.ty(),
+                    });
+                }
+            }
+        }
+
         let (sql, args) = self.query.update_item(item_id, &options);
-        sqlx::query_with(&sql, args)
-            .execute(&mut connection)
+        query::trace_sql(&sql, args.len());
+        sqlx::query_with(&sql, args.into_inner())
+            .execute(&mut *connection)
             .await?;
 
+        if options.replace_custom_properties() {
+            let sql = self.query.delete_custom_properties(item_id);
+            query::trace_sql(&sql, 1);
+            sqlx::query(&sql)
+                .bind(item_id.get())
+                .execute(&mut *connection)
+                .await?;
+        }
+        for (name, is_custom) in options.removed_properties() {
+            let sql = self.query.delete_item_property(item_id);
+            query::trace_sql(&sql, 3);
+            sqlx::query(&sql)
+                .bind(item_id.get())
+                .bind(name)
+                .bind(is_custom)
+                .execute(&mut *connection)
+                .await?;
+        }
+
         let properties = options
             .properties()
             .iter()
@@ -306,8 +1912,9 @@ impl MetadataStore {
             let (sql, args) = self
                 .query
                 .upsert_item_property(item_id, name, value, is_custom);
-            sqlx::query_with(&sql, args)
-                .execute(&mut connection)
+            query::trace_sql(&sql, args.len());
+            sqlx::query_with(&sql, args.into_inner())
+                .execute(&mut *connection)
                 .await?;
         }
 
@@ -316,14 +1923,16 @@ impl MetadataStore {
     }
 
     pub(crate) async fn execute_get_items<T>(
-        &mut self,
+        &self,
         options: GetItemsOptions,
     ) -> Result<Vec<T>, GetError>
     where
         T: for<'a> sqlx::FromRow<'a, sqlx::any::AnyRow> + InsertProperty,
     {
+        let mut connection = self.connection().await?;
         let (sql, args) = self.query.get_items(&options, false);
-        let mut rows = sqlx::query_with(&sql, args).fetch(&mut self.connection);
+        query::trace_sql(&sql, args.len());
+        let mut rows = sqlx::query_with(&sql, args.into_inner()).fetch(&mut *connection);
         let mut items = BTreeMap::new();
         let mut order = Vec::new();
         while let Some(row) = rows.try_next().await? {
@@ -336,16 +1945,22 @@ impl MetadataStore {
             return Ok(Vec::new());
         }
 
-        let (sql, args) = self
-            .query
-            .get_item_properties(options.type_kind(), items.keys().copied());
-        let mut rows =
-            sqlx::query_as_with::<_, query::Property, _>(&sql, args).fetch(&mut self.connection);
-        while let Some(row) = rows.try_next().await? {
-            let item = items.get_mut(&row.id).expect("bug");
-            let is_custom_property = row.is_custom_property;
-            let (name, value) = row.into_name_and_vaue()?;
-            item.insert_property(is_custom_property, name, value);
+        let only_properties = options.only_properties();
+        if only_properties.is_none_or(|names| !names.is_empty()) {
+            let (sql, args) = self.query.get_item_properties(
+                options.type_kind(),
+                items.keys().copied(),
+                only_properties,
+            );
+            query::trace_sql(&sql, args.len());
+            let mut rows = sqlx::query_as_with::<_, query::Property, _>(&sql, args.into_inner())
+                .fetch(&mut *connection);
+            while let Some(row) = rows.try_next().await? {
+                let item = items.get_mut(&row.id).expect("bug");
+                let is_custom_property = row.is_custom_property;
+                let (name, value) = row.into_name_and_vaue()?;
+                item.insert_property(is_custom_property, name, value);
+            }
         }
 
         let mut result = Vec::new();
@@ -357,26 +1972,67 @@ impl MetadataStore {
         Ok(result)
     }
 
+    pub(crate) async fn execute_delete_artifacts(
+        &self,
+        options: GetArtifactsOptions,
+    ) -> Result<usize, PutError> {
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
+
+        let (sql, args) = self.query.get_artifacts(&options, false);
+        let mut ids = Vec::new();
+        query::trace_sql(&sql, args.len());
+        let mut rows = sqlx::query_with(&sql, args.into_inner()).fetch(&mut *connection);
+        while let Some(row) = rows.try_next().await? {
+            ids.push(row.try_get::<i32, _>("id")?);
+        }
+        std::mem::drop(rows);
+
+        for &id in &ids {
+            for sql in self.query.delete_artifact() {
+                query::trace_sql(sql, 1);
+                sqlx::query(sql).bind(id).execute(&mut *connection).await?;
+            }
+        }
+
+        connection.commit().await?;
+        Ok(ids.len())
+    }
+
     pub(crate) async fn execute_count_items(
-        &mut self,
+        &self,
         options: GetItemsOptions,
     ) -> Result<usize, GetError> {
+        let mut connection = self.connection().await?;
         let (sql, args) = self.query.get_items(&options, true);
-        let count: i32 = sqlx::query_scalar_with(&sql, args)
-            .fetch_one(&mut self.connection)
+        query::trace_sql(&sql, args.len());
+        let count: i32 = sqlx::query_scalar_with(&sql, args.into_inner())
+            .fetch_one(&mut *connection)
             .await?;
         Ok(count as usize)
     }
 
     pub(crate) async fn execute_put_relation(
-        &mut self,
+        &self,
         context_id: ContextId,
         item_id: Id,
-    ) -> Result<(), PutError> {
+    ) -> Result<requests::RelationOutcome, PutError> {
+        self.with_retry(|| self.execute_put_relation_once(context_id, item_id))
+            .await
+    }
+
+    async fn execute_put_relation_once(
+        &self,
+        context_id: ContextId,
+        item_id: Id,
+    ) -> Result<requests::RelationOutcome, PutError> {
+        let mut connection = self.connection().await?;
         let is_attribution = matches!(item_id, Id::Artifact(_));
-        let count: i32 = sqlx::query_scalar(self.query.check_context_id())
+        let sql = self.query.check_context_id();
+        query::trace_sql(sql, 1);
+        let count: i32 = sqlx::query_scalar(sql)
             .bind(context_id.get())
-            .fetch_one(&mut self.connection)
+            .fetch_one(&mut *connection)
             .await?;
         if count == 0 {
             return Err(PutError::NotFound {
@@ -384,40 +2040,141 @@ impl MetadataStore {
             });
         }
 
-        let count: i32 = sqlx::query_scalar(if is_attribution {
+        let sql = if is_attribution {
             self.query.check_artifact_id()
         } else {
             self.query.check_execution_id()
-        })
-        .bind(item_id.get())
-        .fetch_one(&mut self.connection)
-        .await?;
+        };
+        query::trace_sql(sql, 1);
+        let count: i32 = sqlx::query_scalar(sql)
+            .bind(item_id.get())
+            .fetch_one(&mut *connection)
+            .await?;
         if count == 0 {
             return Err(PutError::NotFound { item_id });
         }
 
-        sqlx::query(if is_attribution {
+        let sql = if is_attribution {
             self.query.insert_or_ignore_attribution()
         } else {
             self.query.insert_or_ignore_association()
-        })
-        .bind(context_id.get())
-        .bind(item_id.get())
-        .execute(&mut self.connection)
-        .await?;
+        };
+        query::trace_sql(sql, 2);
+        let result = sqlx::query(sql)
+            .bind(context_id.get())
+            .bind(item_id.get())
+            .execute(&mut *connection)
+            .await?;
 
-        Ok(())
+        Ok(requests::RelationOutcome::from_rows_affected(
+            result.rows_affected(),
+        ))
+    }
+
+    pub(crate) async fn execute_put_parent_type(
+        &self,
+        child_type_id: TypeId,
+        parent_type_id: TypeId,
+    ) -> Result<requests::RelationOutcome, PutError> {
+        if child_type_id == parent_type_id {
+            return Err(PutError::SelfParentType {
+                type_id: child_type_id,
+            });
+        }
+
+        let mut connection = self.connection().await?;
+        let mut kinds = Vec::new();
+        for type_id in [child_type_id, parent_type_id] {
+            let sql = self.query.get_type_kind();
+            query::trace_sql(sql, 1);
+            let kind: i32 = sqlx::query_scalar(sql)
+                .bind(type_id.get())
+                .fetch_optional(&mut *connection)
+                .await?
+                .ok_or(PutError::TypeIdNotFound { type_id })?;
+            kinds.push(TypeKind::from_i32(kind)?);
+        }
+        if kinds[0] != kinds[1] {
+            return Err(PutError::TypeKindMismatch {
+                type_id: parent_type_id,
+                expected: kinds[0],
+                actual: kinds[1],
+            });
+        }
+
+        let sql = self.query.insert_or_ignore_parent_type();
+        query::trace_sql(sql, 2);
+        let result = sqlx::query(sql)
+            .bind(child_type_id.get())
+            .bind(parent_type_id.get())
+            .execute(&mut *connection)
+            .await?;
+
+        Ok(requests::RelationOutcome::from_rows_affected(
+            result.rows_affected(),
+        ))
+    }
+
+    pub(crate) async fn execute_put_parent_context(
+        &self,
+        parent_context_id: ContextId,
+        child_context_id: ContextId,
+    ) -> Result<requests::RelationOutcome, PutError> {
+        if parent_context_id == child_context_id {
+            return Err(PutError::SelfParentContext {
+                context_id: parent_context_id,
+            });
+        }
+
+        let mut connection = self.connection().await?;
+        for context_id in [parent_context_id, child_context_id] {
+            let sql = self.query.check_context_id();
+            query::trace_sql(sql, 1);
+            let count: i32 = sqlx::query_scalar(sql)
+                .bind(context_id.get())
+                .fetch_one(&mut *connection)
+                .await?;
+            if count == 0 {
+                return Err(PutError::NotFound {
+                    item_id: Id::Context(context_id),
+                });
+            }
+        }
+
+        let sql = self.query.insert_or_ignore_parent_context();
+        query::trace_sql(sql, 2);
+        let result = sqlx::query(sql)
+            .bind(child_context_id.get())
+            .bind(parent_context_id.get())
+            .execute(&mut *connection)
+            .await?;
+
+        Ok(requests::RelationOutcome::from_rows_affected(
+            result.rows_affected(),
+        ))
     }
 
     pub(crate) async fn execute_put_event(
-        &mut self,
+        &self,
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+        options: PutEventOptions,
+    ) -> Result<(), PutError> {
+        self.with_retry(|| self.execute_put_event_once(execution_id, artifact_id, options.clone()))
+            .await
+    }
+
+    async fn execute_put_event_once(
+        &self,
         execution_id: ExecutionId,
         artifact_id: ArtifactId,
         options: PutEventOptions,
     ) -> Result<(), PutError> {
-        let count: i32 = sqlx::query_scalar(self.query.check_execution_id())
+        let sql = self.query.check_execution_id();
+        query::trace_sql(sql, 1);
+        let count: i32 = sqlx::query_scalar(sql)
             .bind(execution_id.get())
-            .fetch_one(&mut self.connection)
+            .fetch_one(&mut *self.connection().await?)
             .await?;
         if count == 0 {
             return Err(PutError::NotFound {
@@ -425,9 +2182,11 @@ impl MetadataStore {
             });
         }
 
-        let count: i32 = sqlx::query_scalar(self.query.check_artifact_id())
+        let sql = self.query.check_artifact_id();
+        query::trace_sql(sql, 1);
+        let count: i32 = sqlx::query_scalar(sql)
             .bind(artifact_id.get())
-            .fetch_one(&mut self.connection)
+            .fetch_one(&mut *self.connection().await?)
             .await?;
         if count == 0 {
             return Err(PutError::NotFound {
@@ -435,26 +2194,45 @@ impl MetadataStore {
             });
         }
 
-        let mut connection = self.connection.begin().await?;
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
 
-        sqlx::query(self.query.insert_event())
+        let sql = self.query.insert_event();
+        query::trace_sql(sql, 4);
+        let result = sqlx::query(sql)
             .bind(artifact_id.get())
             .bind(execution_id.get())
             .bind(options.event_type as i32)
-            .bind(UNIX_EPOCH.elapsed().unwrap_or_default().as_millis() as i64)
-            .execute(&mut connection)
-            .await?;
-        let event_id: i32 = sqlx::query_scalar(self.query.get_last_event_id())
-            .fetch_one(&mut connection)
-            .await?;
+            .bind(
+                options
+                    .time
+                    .unwrap_or_else(|| UNIX_EPOCH.elapsed().unwrap_or_default())
+                    .as_millis() as i64,
+            )
+            .execute(&mut *connection)
+            .await;
+        if let Err(e) = &result {
+            if is_unique_violation(e) {
+                return Err(PutError::EventAlreadyExists {
+                    execution_id,
+                    artifact_id,
+                    ty: options.event_type,
+                });
+            }
+        }
+        result?;
+        let sql = self.query.get_last_event_id();
+        query::trace_sql(sql, 0);
+        let event_id: i32 = sqlx::query_scalar(sql).fetch_one(&mut *connection).await?;
 
         for step in &options.path {
             let sql = self.query.insert_event_path(step);
+            query::trace_sql(sql, 2);
             let query = match step {
                 EventStep::Index(v) => sqlx::query(sql).bind(event_id).bind(*v),
                 EventStep::Key(v) => sqlx::query(sql).bind(event_id).bind(v),
             };
-            query.execute(&mut connection).await?;
+            query.execute(&mut *connection).await?;
         }
 
         connection.commit().await?;
@@ -462,23 +2240,22 @@ impl MetadataStore {
     }
 
     pub(crate) async fn execute_get_events(
-        &mut self,
+        &self,
         options: GetEventsOptions,
     ) -> Result<Vec<Event>, GetError> {
-        let sql = self.query.get_events(&options, false);
-        let mut query = sqlx::query_as::<_, query::Event>(&sql);
-        for id in &options.artifact_ids {
-            query = query.bind(id.get());
-        }
-        for id in &options.execution_ids {
-            query = query.bind(id.get());
-        }
-
+        let mut connection = self.connection().await?;
+        let (sql, args) = self.query.get_events(&options, false);
+        query::trace_sql(&sql, args.len());
         let mut events = BTreeMap::new();
-        let mut rows = query.fetch(&mut self.connection);
+        let mut rows = sqlx::query_as_with::<_, query::Event, _>(&sql, args.into_inner())
+            .fetch(&mut *connection);
         let mut order = Vec::new();
+        let mut event_ids_with_path = Vec::new();
         while let Some(row) = rows.try_next().await? {
             order.push(row.id);
+            if row.has_path != 0 {
+                event_ids_with_path.push(row.id);
+            }
             events.insert(
                 row.id,
                 Event {
@@ -493,30 +2270,44 @@ impl MetadataStore {
             );
         }
         std::mem::drop(rows);
-        if events.is_empty() {
-            return Ok(Vec::new());
+        if event_ids_with_path.is_empty() {
+            return Ok(order
+                .into_iter()
+                .filter_map(|id| events.remove(&id))
+                .collect());
         }
 
-        let sql = self.query.get_event_paths(events.len());
+        let sql = self.query.get_event_paths(event_ids_with_path.len());
+        query::trace_sql(&sql, event_ids_with_path.len());
         let mut query = sqlx::query_as::<_, query::EventPath>(&sql);
-        for id in events.keys().cloned() {
+        for id in event_ids_with_path {
             query = query.bind(id);
         }
 
-        let mut rows = query.fetch(&mut self.connection);
+        let mut rows = query.fetch(&mut *connection);
         while let Some(row) = rows.try_next().await? {
             let event = events.get_mut(&row.event_id).expect("bug");
             event.path.push(if row.is_index_step {
                 let v = row.step_index.ok_or_else(|| {
                     sqlx::Error::Decode(
-                        anyhow::anyhow!("EventPath.step_index must have a value").into(),
+                        anyhow::anyhow!(
+                            "EventPath.step_index of the event {} must have a value \
+                             because it is an index step",
+                            row.event_id
+                        )
+                        .into(),
                     )
                 })?;
                 EventStep::Index(v)
             } else {
                 let v = row.step_key.ok_or_else(|| {
                     sqlx::Error::Decode(
-                        anyhow::anyhow!("EventPath.step_key must have a value").into(),
+                        anyhow::anyhow!(
+                            "EventPath.step_key of the event {} must have a value \
+                             because it is a key step",
+                            row.event_id
+                        )
+                        .into(),
                     )
                 })?;
                 EventStep::Key(v)
@@ -533,41 +2324,109 @@ impl MetadataStore {
     }
 
     pub(crate) async fn execute_count_events(
-        &mut self,
+        &self,
         options: GetEventsOptions,
     ) -> Result<usize, GetError> {
-        let sql = self.query.get_events(&options, true);
-        let mut query = sqlx::query_scalar(&sql);
-        for id in &options.artifact_ids {
-            query = query.bind(id.get());
+        let mut connection = self.connection().await?;
+        let (sql, args) = self.query.get_events(&options, true);
+        query::trace_sql(&sql, args.len());
+        let count: i64 = sqlx::query_scalar_with(&sql, args.into_inner())
+            .fetch_one(&mut *connection)
+            .await?;
+        Ok(count as usize)
+    }
+
+    async fn check_schema_version(&self, schema_version: i32) -> Result<(), InitError> {
+        let mut connection = self.pool.acquire().await?;
+        let sql = self.query.select_schema_version();
+        query::trace_sql(sql, 0);
+        let version = sqlx::query_scalar(sql)
+            .fetch_optional(&mut connection)
+            .await;
+        match version {
+            Ok(Some(actual)) if actual == schema_version => Ok(()),
+            Ok(Some(actual)) => Err(InitError::UnsupportedSchemaVersion {
+                actual,
+                expected: schema_version,
+            }),
+            Ok(None) => Err(InitError::UninitializedDatabase),
+            Err(e) if is_no_such_table(&e) => Err(InitError::UninitializedDatabase),
+            Err(e) => Err(e.into()),
         }
-        for id in &options.execution_ids {
-            query = query.bind(id.get());
+    }
+
+    async fn initialize_database(&self, schema_version: i32) -> Result<(), InitError> {
+        let mut connection = self.pool.acquire().await?;
+
+        // Serializes the initialization among processes (MySQL only) to avoid racing `CREATE TABLE`s.
+        if let Some(sql) = self.query.acquire_initialization_lock() {
+            query::trace_sql(sql, 0);
+            let acquired: Option<i64> = sqlx::query_scalar(sql).fetch_one(&mut connection).await?;
+            if acquired != Some(1) {
+                return Err(sqlx::Error::Protocol(
+                    "failed to acquire the database initialization lock".to_owned(),
+                )
+                .into());
+            }
         }
 
-        let count: i64 = query.fetch_one(&mut self.connection).await?;
-        Ok(count as usize)
+        let result = self
+            .initialize_database_locked(&mut connection, schema_version)
+            .await;
+
+        if let Some(sql) = self.query.release_initialization_lock() {
+            query::trace_sql(sql, 0);
+            sqlx::query(sql).execute(&mut connection).await?;
+        }
+        result
     }
 
-    async fn initialize_database(&mut self) -> Result<(), InitError> {
-        let version = sqlx::query_scalar(self.query.select_schema_version())
-            .fetch_optional(&mut self.connection)
+    async fn initialize_database_locked(
+        &self,
+        connection: &mut AnyConnection,
+        schema_version: i32,
+    ) -> Result<(), InitError> {
+        let sql = self.query.select_schema_version();
+        query::trace_sql(sql, 0);
+        let version = sqlx::query_scalar(sql)
+            .fetch_optional(&mut *connection)
             .await;
 
         match version {
-            Ok(Some(SCHEMA_VERSION)) => Ok(()),
+            Ok(Some(actual)) if actual == SCHEMA_VERSION && actual == schema_version => {
+                self.repair_schema(connection).await
+            }
+            Ok(Some(actual)) if actual == schema_version => Ok(()),
             Ok(Some(actual)) => Err(InitError::UnsupportedSchemaVersion {
                 actual,
-                expected: SCHEMA_VERSION,
+                expected: schema_version,
             }),
+            _ if schema_version != SCHEMA_VERSION => {
+                // The tables are always created with the schema of the supported version.
+                Err(InitError::UnsupportedSchemaVersion {
+                    actual: schema_version,
+                    expected: SCHEMA_VERSION,
+                })
+            }
             _ => {
-                let mut connection = self.connection.begin().await?;
+                let mut connection = connection.begin().await?;
 
-                for query in self.query.create_tables() {
-                    sqlx::query(query).execute(&mut connection).await?;
+                for sql in self.query.create_tables() {
+                    query::trace_sql(sql, 0);
+                    sqlx::query(sql).execute(&mut connection).await?;
+                }
+                for (probe, statement) in self.query.create_indices() {
+                    query::trace_sql(probe, 0);
+                    let exists: i64 = sqlx::query_scalar(probe).fetch_one(&mut connection).await?;
+                    if exists == 0 {
+                        query::trace_sql(statement, 0);
+                        sqlx::query(statement).execute(&mut connection).await?;
+                    }
                 }
 
-                sqlx::query(self.query.insert_schema_version())
+                let sql = self.query.insert_schema_version();
+                query::trace_sql(sql, 1);
+                sqlx::query(sql)
                     .bind(SCHEMA_VERSION)
                     .execute(&mut connection)
                     .await?;
@@ -578,25 +2437,52 @@ impl MetadataStore {
         }
     }
 
+    /// Adds the columns which are missing in the tables created by the older releases of this crate.
+    async fn repair_schema(&self, connection: &mut AnyConnection) -> Result<(), InitError> {
+        for (probe, statement) in self.query.schema_repairs() {
+            query::trace_sql(probe, 0);
+            let applied: i64 = sqlx::query_scalar(probe)
+                .fetch_one(&mut *connection)
+                .await?;
+            if applied == 0 {
+                query::trace_sql(statement, 0);
+                sqlx::query(statement).execute(&mut *connection).await?;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) async fn execute_put_type(
-        &mut self,
+        &self,
         type_kind: TypeKind,
         type_name: &str,
         mut options: PutTypeOptions,
     ) -> Result<TypeId, PutError> {
-        let mut connection = self.connection.begin().await?;
-        let ty = sqlx::query_as::<_, query::Type>(self.query.get_type_by_name())
-            .bind(type_kind as i32)
-            .bind(type_name)
-            .fetch_optional(&mut connection)
+        if !is_valid_name(type_name) {
+            return Err(PutError::InvalidName {
+                name: type_name.to_owned(),
+            });
+        }
+        if let Some(name) = options.properties.keys().find(|name| !is_valid_name(name)) {
+            return Err(PutError::InvalidPropertyName { name: name.clone() });
+        }
+
+        let mut guard = self.connection().await?;
+        let mut connection = guard.begin().await?;
+        let (sql, args) =
+            self.query
+                .get_type_by_name(type_kind, type_name, options.version.as_deref());
+        query::trace_sql(sql, args.len());
+        let ty = sqlx::query_as_with::<_, query::Type, _>(sql, args.into_inner())
+            .fetch_optional(&mut *connection)
             .await?;
         let ty = if let Some(ty) = ty {
-            let properties = sqlx::query_as::<_, query::TypeProperty>(
-                self.query.get_type_properties_by_type_id(),
-            )
-            .bind(ty.id)
-            .fetch_all(&mut connection)
-            .await?;
+            let sql = self.query.get_type_properties_by_type_id();
+            query::trace_sql(sql, 1);
+            let properties = sqlx::query_as::<_, query::TypeProperty>(sql)
+                .bind(ty.id)
+                .fetch_all(&mut *connection)
+                .await?;
 
             for property in properties {
                 match options.properties.remove(&property.name) {
@@ -617,69 +2503,240 @@ impl MetadataStore {
                 });
             }
 
+            if let Some(description) = &options.description {
+                let sql = self.query.update_type_description();
+                query::trace_sql(sql, 2);
+                sqlx::query(sql)
+                    .bind(description)
+                    .bind(ty.id)
+                    .execute(&mut *connection)
+                    .await?;
+            }
+            if let Some(input_type) = &options.input_type {
+                let sql = self.query.update_type_input_type();
+                query::trace_sql(sql, 2);
+                sqlx::query(sql)
+                    .bind(input_type)
+                    .bind(ty.id)
+                    .execute(&mut *connection)
+                    .await?;
+            }
+            if let Some(output_type) = &options.output_type {
+                let sql = self.query.update_type_output_type();
+                query::trace_sql(sql, 2);
+                sqlx::query(sql)
+                    .bind(output_type)
+                    .bind(ty.id)
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
             ty
         } else {
-            sqlx::query(self.query.insert_type())
+            let sql = self.query.insert_type();
+            query::trace_sql(sql, 6);
+            sqlx::query(sql)
                 .bind(type_kind as i32)
                 .bind(type_name)
-                .execute(&mut connection)
+                .bind(options.version.clone())
+                .bind(options.description.clone())
+                .bind(options.input_type.clone())
+                .bind(options.output_type.clone())
+                .execute(&mut *connection)
                 .await?;
 
-            sqlx::query_as::<_, query::Type>(self.query.get_type_by_name())
-                .bind(type_kind as i32)
-                .bind(type_name)
-                .fetch_one(&mut connection)
+            let (sql, args) =
+                self.query
+                    .get_type_by_name(type_kind, type_name, options.version.as_deref());
+            query::trace_sql(sql, args.len());
+            sqlx::query_as_with::<_, query::Type, _>(sql, args.into_inner())
+                .fetch_one(&mut *connection)
                 .await?
         };
         for (name, value) in &options.properties {
-            sqlx::query(self.query.insert_type_property())
+            let sql = self.query.insert_type_property();
+            query::trace_sql(sql, 3);
+            sqlx::query(sql)
                 .bind(ty.id)
                 .bind(name)
                 .bind(*value as i32)
-                .execute(&mut connection)
+                .execute(&mut *connection)
                 .await?;
         }
         connection.commit().await?;
+        self.invalidate_type_cache(type_kind, TypeId::new(ty.id));
 
         Ok(TypeId::new(ty.id))
     }
 
     pub(crate) async fn execute_get_types<F, T>(
-        &mut self,
+        &self,
         type_kind: TypeKind,
         f: F,
         options: GetTypesOptions,
     ) -> Result<Vec<T>, GetError>
     where
-        F: Fn(TypeId, String, PropertyTypes) -> T,
+        F: Fn(query::Type, PropertyTypes) -> Result<T, GetError>,
     {
+        let mut connection = self.connection().await?;
         let sql = self.query.get_types(&options);
-        let mut query = sqlx::query_as::<_, query::Type>(&sql).bind(type_kind as i32);
+        let mut args = QueryArgs::default();
+        args.add(type_kind as i32);
         if let Some(v) = &options.name {
-            query = query.bind(v);
+            args.add(v.as_str());
+        }
+        if let Some(v) = &options.name_pattern {
+            args.add(v.as_str());
         }
         for id in &options.ids {
-            query = query.bind(id.get());
+            args.add(id.get());
         }
+        if let Some(id) = options.child_type_id {
+            args.add(id.get());
+        }
+        query::trace_sql(&sql, args.len());
 
         let mut types = BTreeMap::new();
-        let mut rows = query.fetch(&mut self.connection);
+        let mut rows = sqlx::query_as_with::<_, query::Type, _>(&sql, args.into_inner())
+            .fetch(&mut *connection);
         while let Some(row) = rows.try_next().await? {
-            types.insert(row.id, (row.name, BTreeMap::new()));
+            types.insert(row.id, (row, BTreeMap::new()));
         }
         std::mem::drop(rows);
 
-        let mut rows = sqlx::query_as::<_, query::TypeProperty>(self.query.get_type_properties())
-            .fetch(&mut self.connection);
+        let sql = if options.ids.is_empty() {
+            self.query.get_type_properties().to_owned()
+        } else {
+            self.query
+                .get_type_properties_by_type_ids(options.ids.len())
+        };
+        query::trace_sql(&sql, options.ids.len());
+        let mut query = sqlx::query_as::<_, query::TypeProperty>(&sql);
+        for id in &options.ids {
+            query = query.bind(id.get());
+        }
+        let mut rows = query.fetch(&mut *connection);
         while let Some(row) = rows.try_next().await? {
             if let Some(ty) = types.get_mut(&row.type_id) {
                 ty.1.insert(row.name, PropertyType::from_i32(row.data_type)?);
             }
         }
 
-        Ok(types
+        types
             .into_iter()
-            .map(|(id, (name, properties))| f(TypeId::new(id), name, properties))
-            .collect())
+            .map(|(_, (ty, properties))| f(ty, properties))
+            .collect()
+    }
+}
+
+fn single_connection_pool_options() -> AnyPoolOptions {
+    AnyPoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+}
+
+fn tls_connect_options(
+    database_uri: &str,
+    ca_cert_path: impl AsRef<Path>,
+) -> Result<AnyConnectOptions, sqlx::Error> {
+    let mut options: AnyConnectOptions = database_uri.parse()?;
+    if let Some(mysql) = options.as_mysql_mut() {
+        *mysql = mysql
+            .clone()
+            .ssl_mode(MySqlSslMode::VerifyCa)
+            .ssl_ca(ca_cert_path);
+    }
+    Ok(options)
+}
+
+// Bool values are stored as integers.
+fn storage_type(ty: PropertyType) -> PropertyType {
+    match ty {
+        PropertyType::Bool => PropertyType::Int,
+        ty => ty,
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= MAX_NAME_LENGTH
+}
+
+fn invalid_property_name(options: &ItemOptions) -> Option<String> {
+    options
+        .properties()
+        .keys()
+        .chain(options.custom_properties().keys())
+        .find(|name| !is_valid_name(name))
+        .cloned()
+}
+
+fn is_retryable(e: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(e) = e {
+        if let Some(e) = e.try_downcast_ref::<MySqlDatabaseError>() {
+            // ER_LOCK_WAIT_TIMEOUT and ER_LOCK_DEADLOCK.
+            matches!(e.number(), 1205 | 1213)
+        } else {
+            // SQLITE_BUSY and SQLITE_LOCKED (including their extended codes).
+            e.code()
+                .and_then(|code| code.parse::<i32>().ok())
+                .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+        }
+    } else {
+        false
+    }
+}
+
+fn is_unique_violation(e: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(e) = e {
+        if let Some(e) = e.try_downcast_ref::<MySqlDatabaseError>() {
+            // ER_DUP_ENTRY.
+            e.number() == 1062
+        } else {
+            // SQLITE_CONSTRAINT_UNIQUE and SQLITE_CONSTRAINT_PRIMARYKEY.
+            matches!(e.code().as_deref(), Some("2067" | "1555"))
+        }
+    } else {
+        false
+    }
+}
+
+fn is_no_such_table(e: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(e) = e {
+        if let Some(e) = e.try_downcast_ref::<MySqlDatabaseError>() {
+            // ER_NO_SUCH_TABLE.
+            e.number() == 1146
+        } else {
+            // SQLite reports a missing table as SQLITE_ERROR, so the message is checked instead.
+            e.message().starts_with("no such table")
+        }
+    } else {
+        false
+    }
+}
+
+#[derive(Debug)]
+enum ConnectionGuard<'a> {
+    Pool(Box<PoolConnection<Any>>),
+    Transaction(MutexGuard<'a, sqlx::Transaction<'static, Any>>),
+}
+
+impl std::ops::Deref for ConnectionGuard<'_> {
+    type Target = AnyConnection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Pool(x) => x,
+            Self::Transaction(x) => x,
+        }
+    }
+}
+
+impl std::ops::DerefMut for ConnectionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Pool(x) => x,
+            Self::Transaction(x) => x,
+        }
     }
 }
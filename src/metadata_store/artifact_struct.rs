@@ -0,0 +1,184 @@
+//! Validation of artifacts against the JSON representation of ml-metadata's `ArtifactStructType`.
+//!
+//! See [the original definition][proto] for the structure.
+//!
+//! [proto]: https://github.com/google/ml-metadata/blob/v1.7.0/ml_metadata/proto/metadata_store.proto#L393-L463
+use crate::metadata::{ArtifactId, EventStep};
+use serde_json::Value;
+
+/// Artifacts of an execution arranged by their event paths.
+#[derive(Debug, Default)]
+pub struct ArtifactTree {
+    // Artifacts whose paths end at this node (with the names of their types).
+    artifacts: Vec<(ArtifactId, String)>,
+    children: Vec<(EventStep, ArtifactTree)>,
+}
+
+impl ArtifactTree {
+    pub fn insert(&mut self, path: &[EventStep], artifact_id: ArtifactId, type_name: &str) {
+        let Some((step, rest)) = path.split_first() else {
+            self.artifacts.push((artifact_id, type_name.to_owned()));
+            return;
+        };
+        let i = match self.children.iter().position(|(s, _)| s == step) {
+            Some(i) => i,
+            None => {
+                self.children.push((step.clone(), Self::default()));
+                self.children.len() - 1
+            }
+        };
+        self.children[i].1.insert(rest, artifact_id, type_name);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.artifacts.is_empty() && self.children.is_empty()
+    }
+
+    fn child(&self, step: &EventStep) -> Option<&Self> {
+        self.children
+            .iter()
+            .find(|(s, _)| s == step)
+            .map(|(_, c)| c)
+    }
+
+    /// Returns the paths and the reasons of the violations of `ty` by this tree.
+    pub fn validate(&self, ty: &Value) -> Vec<(Vec<EventStep>, String)> {
+        let mut violations = Vec::new();
+        self.validate_at(ty, &mut Vec::new(), &mut violations);
+        violations
+    }
+
+    fn validate_at(
+        &self,
+        ty: &Value,
+        path: &mut Vec<EventStep>,
+        violations: &mut Vec<(Vec<EventStep>, String)>,
+    ) {
+        let mut violate = |reason: String| violations.push((path.clone(), reason));
+        let Some((kind, body)) = ty.as_object().and_then(|x| x.iter().next()) else {
+            violate(format!("malformed artifact structure type: {ty}"));
+            return;
+        };
+        match kind.as_str() {
+            "any" => {}
+            "none" => {
+                if !self.is_empty() {
+                    violate("no artifacts are expected".to_owned());
+                }
+            }
+            "simple" => {
+                let name = body
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                match &self.artifacts[..] {
+                    [] if self.children.is_empty() => {
+                        violate(format!("an artifact of the type {name:?} is missing"));
+                    }
+                    [(id, actual)] if self.children.is_empty() => {
+                        if actual != name {
+                            violate(format!(
+                                "{id} has the type {actual:?} but {name:?} is expected"
+                            ));
+                        }
+                    }
+                    _ => violate(format!(
+                        "a single artifact of the type {name:?} is expected"
+                    )),
+                }
+            }
+            "unionType" => {
+                let candidates = array(body, "candidates");
+                if !candidates.iter().any(|c| self.validate(c).is_empty()) {
+                    violate("no candidate of the union type is satisfied".to_owned());
+                }
+            }
+            "intersection" => {
+                for constraint in array(body, "constraints") {
+                    self.validate_at(constraint, path, violations);
+                }
+            }
+            "list" => {
+                let element = body.get("element").unwrap_or(&Value::Null);
+                // An artifact without an index step is regarded as an element by itself.
+                for (id, type_name) in &self.artifacts {
+                    let mut leaf = Self::default();
+                    leaf.artifacts.push((*id, type_name.clone()));
+                    leaf.validate_at(element, path, violations);
+                }
+                for (step, child) in &self.children {
+                    if let EventStep::Key(key) = step {
+                        violations.push((path.clone(), format!("unexpected key {key:?}")));
+                        continue;
+                    }
+                    path.push(step.clone());
+                    child.validate_at(element, path, violations);
+                    path.pop();
+                }
+            }
+            "tuple" => {
+                let elements = array(body, "elements");
+                if !self.artifacts.is_empty() {
+                    violate("artifacts without an index step are not expected".to_owned());
+                }
+                for (step, _) in &self.children {
+                    if !matches!(step, EventStep::Index(i) if (*i as usize) < elements.len()) {
+                        violations.push((path.clone(), format!("unexpected step {step:?}")));
+                    }
+                }
+                let empty = Self::default();
+                for (i, element) in elements.iter().enumerate() {
+                    let step = EventStep::Index(i as i32);
+                    let child = self.child(&step).unwrap_or(&empty);
+                    path.push(step);
+                    child.validate_at(element, path, violations);
+                    path.pop();
+                }
+            }
+            "dict" => {
+                let empty_properties = serde_json::Map::new();
+                let properties = body
+                    .get("properties")
+                    .and_then(|v| v.as_object())
+                    .unwrap_or(&empty_properties);
+                let extra = body.get("extraPropertiesType");
+                if !self.artifacts.is_empty() {
+                    violate("artifacts without a key step are not expected".to_owned());
+                }
+                for (step, child) in &self.children {
+                    let EventStep::Key(key) = step else {
+                        violations.push((path.clone(), format!("unexpected step {step:?}")));
+                        continue;
+                    };
+                    if properties.contains_key(key) {
+                        continue;
+                    }
+                    path.push(step.clone());
+                    if let Some(extra) = extra {
+                        child.validate_at(extra, path, violations);
+                    } else {
+                        violations.push((path.clone(), format!("unexpected key {key:?}")));
+                    }
+                    path.pop();
+                }
+                // A missing key is regarded as having no artifacts.
+                let empty = Self::default();
+                for (key, ty) in properties {
+                    let step = EventStep::Key(key.clone());
+                    let child = self.child(&step).unwrap_or(&empty);
+                    path.push(step);
+                    child.validate_at(ty, path, violations);
+                    path.pop();
+                }
+            }
+            _ => violate(format!("unknown artifact structure type {kind:?}")),
+        }
+    }
+}
+
+fn array<'a>(body: &'a Value, key: &str) -> &'a [Value] {
+    body.get(key)
+        .and_then(|v| v.as_array())
+        .map(|v| &v[..])
+        .unwrap_or_default()
+}
@@ -0,0 +1,125 @@
+use super::SCHEMA_VERSION;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Configuration to connect to a metadata store.
+///
+/// A store is created from a configuration by [`MetadataStore::connect_with`](crate::MetadataStore::connect_with).
+///
+/// # Examples
+///
+/// ```no_run
+/// use mlmd::{MetadataStore, MetadataStoreConfig};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let config = MetadataStoreConfig::new("mysql://localhost/mlmd")
+///     .max_connections(4)
+///     .tls_ca_cert_path("/path/to/ca.pem");
+/// let store = MetadataStore::connect_with(config).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetadataStoreConfig {
+    pub(crate) database_uri: String,
+    pub(crate) max_connections: u32,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) tls_ca_cert_path: Option<PathBuf>,
+    pub(crate) schema_version: i32,
+    pub(crate) max_uri_length: Option<usize>,
+    pub(crate) max_retries: u32,
+    pub(crate) retry_base_delay: Duration,
+    pub(crate) read_only: bool,
+    pub(crate) type_cache_capacity: Option<usize>,
+}
+
+impl MetadataStoreConfig {
+    /// Makes a configuration to connect to the database specified by the given URI.
+    pub fn new(database_uri: &str) -> Self {
+        Self {
+            database_uri: database_uri.to_owned(),
+            max_connections: 1,
+            connect_timeout: None,
+            tls_ca_cert_path: None,
+            schema_version: SCHEMA_VERSION,
+            max_uri_length: None,
+            max_retries: 0,
+            retry_base_delay: Duration::from_millis(10),
+            read_only: false,
+            type_cache_capacity: None,
+        }
+    }
+
+    /// Sets the maximum number of connections in the pool.
+    ///
+    /// The default is `1` (see [`MetadataStore::connect_pool`](crate::MetadataStore::connect_pool)).
+    pub fn max_connections(mut self, n: u32) -> Self {
+        self.max_connections = n;
+        self
+    }
+
+    /// Sets the maximum time to wait for establishing (or acquiring) a connection.
+    ///
+    /// If not specified, the default timeout of the underlying driver is used.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the path of the CA certificate to verify the server certificate.
+    ///
+    /// See [`MetadataStore::connect_with_tls`](crate::MetadataStore::connect_with_tls) for the details.
+    pub fn tls_ca_cert_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.tls_ca_cert_path = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the expected schema version of the database.
+    ///
+    /// See [`MetadataStore::connect_with_schema_version`](crate::MetadataStore::connect_with_schema_version) for the details.
+    pub fn schema_version(mut self, version: i32) -> Self {
+        self.schema_version = version;
+        self
+    }
+
+    /// Connects to the existing database without creating tables or writing the schema version.
+    ///
+    /// See [`MetadataStore::connect_read_only`](crate::MetadataStore::connect_read_only) for the details.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Sets the maximum length (in bytes) of artifact URIs.
+    ///
+    /// See [`MetadataStore::set_max_uri_length`](crate::MetadataStore::set_max_uri_length) for the details.
+    pub fn max_uri_length(mut self, max: usize) -> Self {
+        self.max_uri_length = Some(max);
+        self
+    }
+
+    /// Enables retrying write requests that failed due to transient database errors.
+    ///
+    /// A request that fails due to a deadlock or a lock wait timeout (MySQL), or
+    /// a busy or locked database (SQLite) is retried at most `max_retries` times.
+    /// The `n`-th retry is made after waiting for `base_delay * 2^(n-1)`.
+    /// Other errors such as constraint violations are never retried.
+    ///
+    /// Requests issued via a [`Transaction`](crate::Transaction) are not retried
+    /// as the whole transaction would need to be re-executed.
+    ///
+    /// The default is `0` (i.e., no retry).
+    pub fn retry(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Enables an in-memory LRU cache of the property definitions of at most `capacity` types.
+    ///
+    /// See [`MetadataStore::with_type_cache`](crate::MetadataStore::with_type_cache) for the details.
+    pub fn type_cache(mut self, capacity: usize) -> Self {
+        self.type_cache_capacity = Some(capacity);
+        self
+    }
+}
@@ -1,6 +1,6 @@
 use crate::metadata::{
     ArtifactId, ArtifactState, ContextId, EventStep, EventType, ExecutionId, ExecutionState,
-    PropertyTypes, PropertyValues, TypeId, TypeKind,
+    PropertyTypes, PropertyValue, PropertyValues, TypeId, TypeKind,
 };
 use crate::query::QueryValue;
 use crate::requests::{
@@ -13,20 +13,27 @@ use std::time::Duration;
 #[derive(Debug, Default, Clone)]
 pub struct GetTypesOptions {
     pub name: Option<String>,
+    pub name_pattern: Option<String>,
     pub ids: BTreeSet<TypeId>,
+    pub child_type_id: Option<TypeId>,
 }
 
 impl GetTypesOptions {
     pub fn by_id(id: TypeId) -> Self {
         Self {
-            name: None,
             ids: vec![id].into_iter().collect(),
+            ..Default::default()
         }
     }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct PutTypeOptions {
+    pub version: Option<String>,
+    pub description: Option<String>,
+    // JSON strings.
+    pub input_type: Option<String>,
+    pub output_type: Option<String>,
     pub can_add_fields: bool,
     pub can_omit_fields: bool,
     pub properties: PropertyTypes,
@@ -48,6 +55,13 @@ impl ItemOptions {
         }
     }
 
+    pub fn uri(&self) -> Option<&str> {
+        match self {
+            Self::Artifact(x) => x.uri.as_deref(),
+            Self::Execution(_) | Self::Context(_) => None,
+        }
+    }
+
     pub fn properties(&self) -> &PropertyValues {
         match self {
             Self::Artifact(x) => &x.properties,
@@ -64,6 +78,45 @@ impl ItemOptions {
         }
     }
 
+    pub fn create_time_since_epoch(&self) -> Option<Duration> {
+        match self {
+            Self::Artifact(x) => x.create_time_since_epoch,
+            Self::Execution(x) => x.create_time_since_epoch,
+            Self::Context(x) => x.create_time_since_epoch,
+        }
+    }
+
+    pub fn last_update_time_since_epoch(&self) -> Option<Duration> {
+        match self {
+            Self::Artifact(x) => x.last_update_time_since_epoch,
+            Self::Execution(x) => x.last_update_time_since_epoch,
+            Self::Context(x) => x.last_update_time_since_epoch,
+        }
+    }
+
+    pub fn replace_custom_properties(&self) -> bool {
+        match self {
+            Self::Artifact(x) => x.replace_custom_properties,
+            Self::Execution(x) => x.replace_custom_properties,
+            Self::Context(x) => x.replace_custom_properties,
+        }
+    }
+
+    pub fn removed_properties(&self) -> &BTreeSet<(String, bool)> {
+        match self {
+            Self::Artifact(x) => &x.removed_properties,
+            Self::Execution(x) => &x.removed_properties,
+            Self::Context(x) => &x.removed_properties,
+        }
+    }
+
+    pub fn strict_custom_properties(&self) -> bool {
+        match self {
+            Self::Artifact(x) => x.strict_custom_properties,
+            Self::Execution(_) | Self::Context(_) => false,
+        }
+    }
+
     pub fn type_kind(&self) -> TypeKind {
         match self {
             Self::Artifact(_) => TypeKind::Artifact,
@@ -72,7 +125,7 @@ impl ItemOptions {
         }
     }
 
-    pub fn extra_fields(&self) -> Vec<(&'static str, QueryValue)> {
+    pub fn extra_fields(&self) -> Vec<(&'static str, QueryValue<'_>)> {
         let mut fields = Vec::new();
         match self {
             Self::Artifact(x) => {
@@ -109,43 +162,153 @@ impl GetItemsOptions {
             Self::Context(_) => TypeKind::Context,
         }
     }
+
+    pub fn only_properties(&self) -> Option<&[String]> {
+        match self {
+            Self::Artifact(x) => x.only_properties.as_deref(),
+            Self::Execution(_) | Self::Context(_) => None,
+        }
+    }
 }
 
+/// Fields to be updated of an artifact.
+///
+/// This is used by [`MetadataStore::put_artifacts`](crate::MetadataStore::put_artifacts)
+/// to update multiple artifacts at once.
 #[derive(Debug, Clone, Default)]
 pub struct ArtifactOptions {
     pub(crate) name: Option<String>,
     pub(crate) uri: Option<String>,
     pub(crate) properties: PropertyValues,
     pub(crate) custom_properties: PropertyValues,
+    pub(crate) replace_custom_properties: bool,
+    pub(crate) removed_properties: BTreeSet<(String, bool)>,
+    pub(crate) strict_custom_properties: bool,
+    pub(crate) create_time_since_epoch: Option<Duration>,
+    pub(crate) last_update_time_since_epoch: Option<Duration>,
     pub(crate) state: Option<ArtifactState>,
 }
 
+impl ArtifactOptions {
+    /// Makes an empty set of fields (i.e., nothing is updated).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the artifact.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Sets the URI of the artifact.
+    pub fn uri(mut self, uri: &str) -> Self {
+        self.uri = Some(uri.to_owned());
+        self
+    }
+
+    /// Adds a property to the artifact.
+    pub fn property<T>(mut self, key: &str, value: T) -> Self
+    where
+        T: Into<PropertyValue>,
+    {
+        self.properties.insert(key.to_owned(), value.into());
+        self
+    }
+
+    /// Adds a custom property to the artifact.
+    pub fn custom_property<T>(mut self, key: &str, value: T) -> Self
+    where
+        T: Into<PropertyValue>,
+    {
+        self.custom_properties.insert(key.to_owned(), value.into());
+        self
+    }
+
+    /// Sets the state of the artifact.
+    pub fn state(mut self, state: ArtifactState) -> Self {
+        self.state = Some(state);
+        self
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct GetArtifactsOptions {
     pub(crate) type_name: Option<String>,
+    pub(crate) type_names: Vec<String>,
     pub(crate) artifact_name: Option<String>,
     pub(crate) artifact_name_pattern: Option<String>,
+    pub(crate) ignore_case: bool,
     pub(crate) artifact_ids: BTreeSet<ArtifactId>,
     pub(crate) uri: Option<String>,
+    pub(crate) uri_pattern: Option<String>,
+    pub(crate) uris: Vec<String>,
+    pub(crate) without_uri: bool,
+    pub(crate) match_any: bool,
+    pub(crate) state: Option<ArtifactState>,
     pub(crate) context_id: Option<ContextId>,
+    pub(crate) execution_ids: BTreeSet<ExecutionId>,
+    pub(crate) output_events_only: bool,
+    pub(crate) property_eq: Vec<(String, PropertyValue)>,
+    pub(crate) property_ranges: Vec<(String, PropertyRange)>,
+    pub(crate) property_presence: Vec<(String, bool)>,
+    pub(crate) only_properties: Option<Vec<String>>,
     pub(crate) limit: Option<usize>,
     pub(crate) offset: Option<usize>,
     pub(crate) order_by: Option<ArtifactOrderByField>,
+    pub(crate) order_by_property: Option<(String, bool)>,
+    pub(crate) order_by_last_event: bool,
     pub(crate) desc: bool,
     pub(crate) create_time: Option<Range<Bound<Duration>>>,
     pub(crate) update_time: Option<Range<Bound<Duration>>>,
 }
 
+#[derive(Debug, Clone)]
+pub enum PropertyRange {
+    Int(Range<Bound<i32>>),
+    Double(Range<Bound<f64>>),
+}
+
+impl PropertyRange {
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "int_value",
+            Self::Double(_) => "double_value",
+        }
+    }
+
+    pub fn bounds(&self) -> (Bound<PropertyValue>, Bound<PropertyValue>) {
+        fn map<T: Copy + Into<PropertyValue>>(x: &Bound<T>) -> Bound<PropertyValue> {
+            match x {
+                Bound::Excluded(x) => Bound::Excluded((*x).into()),
+                Bound::Included(x) => Bound::Included((*x).into()),
+                Bound::Unbounded => Bound::Unbounded,
+            }
+        }
+        match self {
+            Self::Int(x) => (map(&x.start), map(&x.end)),
+            Self::Double(x) => (map(&x.start), map(&x.end)),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct GetExecutionsOptions {
     pub(crate) type_name: Option<String>,
+    pub(crate) type_names: Vec<String>,
     pub(crate) execution_name: Option<String>,
     pub(crate) execution_name_pattern: Option<String>,
+    pub(crate) ignore_case: bool,
     pub(crate) execution_ids: BTreeSet<ExecutionId>,
+    pub(crate) state: Option<ExecutionState>,
     pub(crate) context_id: Option<ContextId>,
+    pub(crate) artifact_ids: BTreeSet<ArtifactId>,
+    pub(crate) event_type: Option<EventType>,
+    pub(crate) property_presence: Vec<(String, bool)>,
     pub(crate) limit: Option<usize>,
     pub(crate) offset: Option<usize>,
     pub(crate) order_by: Option<ExecutionOrderByField>,
+    pub(crate) order_by_property: Option<(String, bool)>,
     pub(crate) desc: bool,
     pub(crate) create_time: Option<Range<Bound<Duration>>>,
     pub(crate) update_time: Option<Range<Bound<Duration>>>,
@@ -156,20 +319,30 @@ pub struct ExecutionOptions {
     pub(crate) name: Option<String>,
     pub(crate) properties: PropertyValues,
     pub(crate) custom_properties: PropertyValues,
+    pub(crate) replace_custom_properties: bool,
+    pub(crate) removed_properties: BTreeSet<(String, bool)>,
+    pub(crate) create_time_since_epoch: Option<Duration>,
+    pub(crate) last_update_time_since_epoch: Option<Duration>,
     pub(crate) last_known_state: Option<ExecutionState>,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct GetContextsOptions {
     pub(crate) type_name: Option<String>,
+    pub(crate) type_names: Vec<String>,
     pub(crate) context_name: Option<String>,
     pub(crate) context_name_pattern: Option<String>,
+    pub(crate) ignore_case: bool,
     pub(crate) context_ids: BTreeSet<ContextId>,
     pub(crate) artifact_ids: BTreeSet<ArtifactId>,
     pub(crate) execution_ids: BTreeSet<ExecutionId>,
+    pub(crate) parent_context_id: Option<ContextId>,
+    pub(crate) child_context_id: Option<ContextId>,
+    pub(crate) property_presence: Vec<(String, bool)>,
     pub(crate) limit: Option<usize>,
     pub(crate) offset: Option<usize>,
     pub(crate) order_by: Option<ContextOrderByField>,
+    pub(crate) order_by_property: Option<(String, bool)>,
     pub(crate) desc: bool,
     pub(crate) create_time: Option<Range<Bound<Duration>>>,
     pub(crate) update_time: Option<Range<Bound<Duration>>>,
@@ -180,12 +353,17 @@ pub struct ContextOptions {
     pub(crate) name: Option<String>,
     pub(crate) properties: PropertyValues,
     pub(crate) custom_properties: PropertyValues,
+    pub(crate) replace_custom_properties: bool,
+    pub(crate) removed_properties: BTreeSet<(String, bool)>,
+    pub(crate) create_time_since_epoch: Option<Duration>,
+    pub(crate) last_update_time_since_epoch: Option<Duration>,
 }
 
 #[derive(Debug, Clone)]
 pub struct PutEventOptions {
     pub(crate) event_type: EventType,
     pub(crate) path: Vec<EventStep>,
+    pub(crate) time: Option<Duration>,
 }
 
 impl Default for PutEventOptions {
@@ -193,6 +371,7 @@ impl Default for PutEventOptions {
         Self {
             event_type: EventType::Unknown,
             path: Vec::new(),
+            time: None,
         }
     }
 }
@@ -201,6 +380,7 @@ impl Default for PutEventOptions {
 pub struct GetEventsOptions {
     pub(crate) artifact_ids: BTreeSet<ArtifactId>,
     pub(crate) execution_ids: BTreeSet<ExecutionId>,
+    pub(crate) pairs: Vec<(ExecutionId, ArtifactId)>,
     pub(crate) limit: Option<usize>,
     pub(crate) offset: Option<usize>,
     pub(crate) order_by: Option<EventOrderByField>,
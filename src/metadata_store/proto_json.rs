@@ -0,0 +1,727 @@
+//! JSON representation of the messages of ml-metadata.
+//!
+//! The field names and value encodings follow [the canonical protobuf JSON mapping][mapping]
+//! (e.g., `int64` values are encoded as strings and enums as their names).
+//!
+//! [mapping]: https://protobuf.dev/programming-guides/proto3/#json
+use super::Transaction;
+use crate::errors::ImportError;
+use crate::metadata::{self, ArtifactId, ContextId, ExecutionId, TypeId};
+use crate::snapshot::Snapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Summary of [`MetadataStore::import_json`](crate::MetadataStore::import_json).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Number of the imported types (of all kinds).
+    pub types: usize,
+
+    /// Number of the imported artifacts.
+    pub artifacts: usize,
+
+    /// Number of the imported executions.
+    pub executions: usize,
+
+    /// Number of the imported contexts.
+    pub contexts: usize,
+
+    /// Number of the imported events.
+    pub events: usize,
+
+    /// Number of the imported attributions.
+    pub attributions: usize,
+
+    /// Number of the imported associations.
+    pub associations: usize,
+
+    /// Number of the imported parent-child relationships between types.
+    pub parent_types: usize,
+
+    /// Number of the imported parent-child relationships between contexts.
+    pub parent_contexts: usize,
+}
+
+/// All the contents of a metadata store.
+///
+/// The field names are taken from the responses of `MetadataStoreService`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Dump {
+    pub artifact_types: Vec<ArtifactType>,
+    pub execution_types: Vec<ExecutionType>,
+    pub context_types: Vec<ContextType>,
+    pub artifacts: Vec<Artifact>,
+    pub executions: Vec<Execution>,
+    pub contexts: Vec<Context>,
+    pub events: Vec<Event>,
+    pub attributions: Vec<Attribution>,
+    pub associations: Vec<Association>,
+    pub parent_types: Vec<ParentType>,
+    pub parent_contexts: Vec<ParentContext>,
+}
+
+impl Dump {
+    pub fn new(
+        snapshot: &Snapshot,
+        attributions: &[(i32, i32)],
+        associations: &[(i32, i32)],
+        parent_types: &[(i32, i32)],
+        parent_contexts: &[(i32, i32)],
+    ) -> Self {
+        Self {
+            artifact_types: snapshot.artifact_types.iter().map(From::from).collect(),
+            execution_types: snapshot.execution_types.iter().map(From::from).collect(),
+            context_types: snapshot.context_types.iter().map(From::from).collect(),
+            artifacts: snapshot.artifacts.iter().map(From::from).collect(),
+            executions: snapshot.executions.iter().map(From::from).collect(),
+            contexts: snapshot.contexts.iter().map(From::from).collect(),
+            events: snapshot.events.iter().map(From::from).collect(),
+            attributions: attributions
+                .iter()
+                .map(|&(context_id, artifact_id)| Attribution {
+                    artifact_id,
+                    context_id,
+                })
+                .collect(),
+            associations: associations
+                .iter()
+                .map(|&(context_id, execution_id)| Association {
+                    execution_id,
+                    context_id,
+                })
+                .collect(),
+            parent_types: parent_types
+                .iter()
+                .map(|&(child_id, parent_id)| ParentType {
+                    child_id,
+                    parent_id,
+                })
+                .collect(),
+            parent_contexts: parent_contexts
+                .iter()
+                .map(|&(child_id, parent_id)| ParentContext {
+                    child_id,
+                    parent_id,
+                })
+                .collect(),
+        }
+    }
+
+    pub async fn import(
+        self,
+        transaction: &mut Transaction<'_>,
+    ) -> Result<ImportSummary, ImportError> {
+        // The IDs in the dump are mapped to the ones assigned by the destination store.
+        let mut artifact_type_ids = HashMap::new();
+        let mut execution_type_ids = HashMap::new();
+        let mut context_type_ids = HashMap::new();
+        let mut artifact_ids = HashMap::new();
+        let mut execution_ids = HashMap::new();
+        let mut context_ids = HashMap::new();
+
+        for x in &self.artifact_types {
+            let mut request = transaction
+                .put_artifact_type(&x.name)
+                .properties(parse_property_types(&x.properties)?)
+                .can_add_fields()
+                .can_omit_fields();
+            if let Some(v) = &x.version {
+                request = request.version(v);
+            }
+            if let Some(v) = &x.description {
+                request = request.description(v);
+            }
+            artifact_type_ids.insert(x.id, request.execute().await?);
+        }
+        for x in &self.execution_types {
+            let mut request = transaction
+                .put_execution_type(&x.name)
+                .properties(parse_property_types(&x.properties)?)
+                .can_add_fields()
+                .can_omit_fields();
+            if let Some(v) = &x.version {
+                request = request.version(v);
+            }
+            if let Some(v) = &x.description {
+                request = request.description(v);
+            }
+            if let Some(v) = &x.input_type {
+                request = request.input_type(v.clone());
+            }
+            if let Some(v) = &x.output_type {
+                request = request.output_type(v.clone());
+            }
+            execution_type_ids.insert(x.id, request.execute().await?);
+        }
+        for x in &self.context_types {
+            let mut request = transaction
+                .put_context_type(&x.name)
+                .properties(parse_property_types(&x.properties)?)
+                .can_add_fields()
+                .can_omit_fields();
+            if let Some(v) = &x.version {
+                request = request.version(v);
+            }
+            if let Some(v) = &x.description {
+                request = request.description(v);
+            }
+            context_type_ids.insert(x.id, request.execute().await?);
+        }
+
+        for x in &self.artifacts {
+            let mut request = transaction
+                .post_artifact(lookup(&artifact_type_ids, x.type_id, |id| {
+                    ImportError::UndefinedTypeId {
+                        type_id: TypeId::new(id),
+                    }
+                })?)
+                .properties(parse_property_values(&x.properties))
+                .custom_properties(parse_property_values(&x.custom_properties))
+                .state(parse_name(
+                    &x.state,
+                    metadata::ArtifactState::from_str_name,
+                )?)
+                .create_time_since_epoch(millis(x.create_time_since_epoch))
+                .last_update_time_since_epoch(millis(x.last_update_time_since_epoch));
+            if let Some(v) = &x.name {
+                request = request.name(v);
+            }
+            if let Some(v) = &x.uri {
+                request = request.uri(v);
+            }
+            artifact_ids.insert(x.id, request.execute().await?);
+        }
+        for x in &self.executions {
+            let mut request = transaction
+                .post_execution(lookup(&execution_type_ids, x.type_id, |id| {
+                    ImportError::UndefinedTypeId {
+                        type_id: TypeId::new(id),
+                    }
+                })?)
+                .properties(parse_property_values(&x.properties))
+                .custom_properties(parse_property_values(&x.custom_properties))
+                .state(parse_name(
+                    &x.last_known_state,
+                    metadata::ExecutionState::from_str_name,
+                )?)
+                .create_time_since_epoch(millis(x.create_time_since_epoch))
+                .last_update_time_since_epoch(millis(x.last_update_time_since_epoch));
+            if let Some(v) = &x.name {
+                request = request.name(v);
+            }
+            execution_ids.insert(x.id, request.execute().await?);
+        }
+        for x in &self.contexts {
+            let type_id = lookup(&context_type_ids, x.type_id, |id| {
+                ImportError::UndefinedTypeId {
+                    type_id: TypeId::new(id),
+                }
+            })?;
+            let context_id = transaction
+                .post_context(type_id, &x.name)
+                .properties(parse_property_values(&x.properties))
+                .custom_properties(parse_property_values(&x.custom_properties))
+                .create_time_since_epoch(millis(x.create_time_since_epoch))
+                .last_update_time_since_epoch(millis(x.last_update_time_since_epoch))
+                .execute()
+                .await?;
+            context_ids.insert(x.id, context_id);
+        }
+
+        let artifact_id = |id| {
+            lookup(&artifact_ids, id, |id| ImportError::UndefinedItemId {
+                item_id: metadata::Id::Artifact(ArtifactId::new(id)),
+            })
+        };
+        let execution_id = |id| {
+            lookup(&execution_ids, id, |id| ImportError::UndefinedItemId {
+                item_id: metadata::Id::Execution(ExecutionId::new(id)),
+            })
+        };
+        let context_id = |id| {
+            lookup(&context_ids, id, |id| ImportError::UndefinedItemId {
+                item_id: metadata::Id::Context(ContextId::new(id)),
+            })
+        };
+        for x in &self.events {
+            let path = x.path.steps.iter().map(|step| match step {
+                EventStep::Index(i) => metadata::EventStep::Index(*i),
+                EventStep::Key(k) => metadata::EventStep::Key(k.clone()),
+            });
+            transaction
+                .put_event(execution_id(x.execution_id)?, artifact_id(x.artifact_id)?)
+                .ty(parse_name(&x.ty, metadata::EventType::from_str_name)?)
+                .path(path)
+                .time(millis(x.milliseconds_since_epoch))
+                .execute()
+                .await?;
+        }
+        for x in &self.attributions {
+            transaction
+                .put_attribution(context_id(x.context_id)?, artifact_id(x.artifact_id)?)
+                .execute()
+                .await?;
+        }
+        for x in &self.associations {
+            transaction
+                .put_association(context_id(x.context_id)?, execution_id(x.execution_id)?)
+                .execute()
+                .await?;
+        }
+        for x in &self.parent_contexts {
+            transaction
+                .put_parent_context(context_id(x.parent_id)?, context_id(x.child_id)?)
+                .execute()
+                .await?;
+        }
+
+        // A parent type can be of any kind, so look up all the type ID maps.
+        let type_id = |id| {
+            artifact_type_ids
+                .get(&id)
+                .or_else(|| execution_type_ids.get(&id))
+                .or_else(|| context_type_ids.get(&id))
+                .copied()
+                .ok_or(ImportError::UndefinedTypeId {
+                    type_id: TypeId::new(id),
+                })
+        };
+        for x in &self.parent_types {
+            transaction
+                .put_parent_type(type_id(x.child_id)?, type_id(x.parent_id)?)
+                .execute()
+                .await?;
+        }
+
+        Ok(ImportSummary {
+            types: self.artifact_types.len()
+                + self.execution_types.len()
+                + self.context_types.len(),
+            artifacts: self.artifacts.len(),
+            executions: self.executions.len(),
+            contexts: self.contexts.len(),
+            events: self.events.len(),
+            attributions: self.attributions.len(),
+            associations: self.associations.len(),
+            parent_types: self.parent_types.len(),
+            parent_contexts: self.parent_contexts.len(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactType {
+    #[serde(with = "int64")]
+    pub id: i32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl From<&metadata::ArtifactType> for ArtifactType {
+    fn from(x: &metadata::ArtifactType) -> Self {
+        Self {
+            id: x.id.get(),
+            name: x.name.clone(),
+            version: x.version.clone(),
+            description: x.description.clone(),
+            properties: property_types(&x.properties),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionType {
+    #[serde(with = "int64")]
+    pub id: i32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_type: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_type: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl From<&metadata::ExecutionType> for ExecutionType {
+    fn from(x: &metadata::ExecutionType) -> Self {
+        Self {
+            id: x.id.get(),
+            name: x.name.clone(),
+            version: x.version.clone(),
+            description: x.description.clone(),
+            input_type: x.input_type.clone(),
+            output_type: x.output_type.clone(),
+            properties: property_types(&x.properties),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextType {
+    #[serde(with = "int64")]
+    pub id: i32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl From<&metadata::ContextType> for ContextType {
+    fn from(x: &metadata::ContextType) -> Self {
+        Self {
+            id: x.id.get(),
+            name: x.name.clone(),
+            version: x.version.clone(),
+            description: x.description.clone(),
+            properties: property_types(&x.properties),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    #[serde(with = "int64")]
+    pub id: i32,
+    #[serde(with = "int64")]
+    pub type_id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_properties: BTreeMap<String, Value>,
+    #[serde(default = "unknown")]
+    pub state: String,
+    #[serde(with = "int64")]
+    pub create_time_since_epoch: i64,
+    #[serde(with = "int64")]
+    pub last_update_time_since_epoch: i64,
+}
+
+impl From<&metadata::Artifact> for Artifact {
+    fn from(x: &metadata::Artifact) -> Self {
+        Self {
+            id: x.id.get(),
+            type_id: x.type_id.get(),
+            name: x.name.clone(),
+            uri: x.uri.clone(),
+            properties: property_values(&x.properties),
+            custom_properties: property_values(&x.custom_properties),
+            state: x.state.as_str_name().to_owned(),
+            create_time_since_epoch: x.create_time_since_epoch.as_millis() as i64,
+            last_update_time_since_epoch: x.last_update_time_since_epoch.as_millis() as i64,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Execution {
+    #[serde(with = "int64")]
+    pub id: i32,
+    #[serde(with = "int64")]
+    pub type_id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_properties: BTreeMap<String, Value>,
+    #[serde(default = "unknown")]
+    pub last_known_state: String,
+    #[serde(with = "int64")]
+    pub create_time_since_epoch: i64,
+    #[serde(with = "int64")]
+    pub last_update_time_since_epoch: i64,
+}
+
+impl From<&metadata::Execution> for Execution {
+    fn from(x: &metadata::Execution) -> Self {
+        Self {
+            id: x.id.get(),
+            type_id: x.type_id.get(),
+            name: x.name.clone(),
+            properties: property_values(&x.properties),
+            custom_properties: property_values(&x.custom_properties),
+            last_known_state: x.last_known_state.as_str_name().to_owned(),
+            create_time_since_epoch: x.create_time_since_epoch.as_millis() as i64,
+            last_update_time_since_epoch: x.last_update_time_since_epoch.as_millis() as i64,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Context {
+    #[serde(with = "int64")]
+    pub id: i32,
+    #[serde(with = "int64")]
+    pub type_id: i32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_properties: BTreeMap<String, Value>,
+    #[serde(with = "int64")]
+    pub create_time_since_epoch: i64,
+    #[serde(with = "int64")]
+    pub last_update_time_since_epoch: i64,
+}
+
+impl From<&metadata::Context> for Context {
+    fn from(x: &metadata::Context) -> Self {
+        Self {
+            id: x.id.get(),
+            type_id: x.type_id.get(),
+            name: x.name.clone(),
+            properties: property_values(&x.properties),
+            custom_properties: property_values(&x.custom_properties),
+            create_time_since_epoch: x.create_time_since_epoch.as_millis() as i64,
+            last_update_time_since_epoch: x.last_update_time_since_epoch.as_millis() as i64,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    #[serde(with = "int64")]
+    pub artifact_id: i32,
+    #[serde(with = "int64")]
+    pub execution_id: i32,
+    #[serde(default, skip_serializing_if = "EventPath::is_empty")]
+    pub path: EventPath,
+    #[serde(rename = "type", default = "unknown")]
+    pub ty: String,
+    #[serde(with = "int64")]
+    pub milliseconds_since_epoch: i64,
+}
+
+impl From<&metadata::Event> for Event {
+    fn from(x: &metadata::Event) -> Self {
+        Self {
+            artifact_id: x.artifact_id.get(),
+            execution_id: x.execution_id.get(),
+            path: EventPath {
+                steps: x
+                    .path
+                    .iter()
+                    .map(|step| match step {
+                        metadata::EventStep::Index(i) => EventStep::Index(*i),
+                        metadata::EventStep::Key(k) => EventStep::Key(k.clone()),
+                    })
+                    .collect(),
+            },
+            ty: x.ty.as_str_name().to_owned(),
+            milliseconds_since_epoch: x.create_time_since_epoch.as_millis() as i64,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EventPath {
+    #[serde(default)]
+    pub steps: Vec<EventStep>,
+}
+
+impl EventPath {
+    fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventStep {
+    Index(#[serde(with = "int64")] i32),
+    Key(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attribution {
+    #[serde(with = "int64")]
+    pub artifact_id: i32,
+    #[serde(with = "int64")]
+    pub context_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Association {
+    #[serde(with = "int64")]
+    pub execution_id: i32,
+    #[serde(with = "int64")]
+    pub context_id: i32,
+}
+
+/// Parent-child relationship between types.
+///
+/// ml-metadata doesn't define a message for this, so it's shaped after `ParentContext`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentType {
+    #[serde(with = "int64")]
+    pub child_id: i32,
+    #[serde(with = "int64")]
+    pub parent_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentContext {
+    #[serde(with = "int64")]
+    pub child_id: i32,
+    #[serde(with = "int64")]
+    pub parent_id: i32,
+}
+
+/// Property value.
+///
+/// ml-metadata doesn't define a bytes value, so `bytesValue` (encoded in base64) is specific to this crate.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Value {
+    #[serde(rename = "intValue", with = "int64")]
+    Int(i32),
+    #[serde(rename = "doubleValue", with = "crate::metadata::non_finite_f64")]
+    Double(f64),
+    #[serde(rename = "stringValue")]
+    String(String),
+    #[serde(rename = "bytesValue", with = "base64_bytes")]
+    Bytes(Vec<u8>),
+    #[serde(rename = "boolValue")]
+    Bool(bool),
+}
+
+impl From<&metadata::PropertyValue> for Value {
+    fn from(x: &metadata::PropertyValue) -> Self {
+        match x {
+            metadata::PropertyValue::Int(v) => Self::Int(*v),
+            metadata::PropertyValue::Double(v) => Self::Double(*v),
+            metadata::PropertyValue::String(v) => Self::String(v.clone()),
+            metadata::PropertyValue::Bytes(v) => Self::Bytes(v.clone()),
+            metadata::PropertyValue::Bool(v) => Self::Bool(*v),
+        }
+    }
+}
+
+fn parse_property_types(
+    x: &BTreeMap<String, String>,
+) -> Result<metadata::PropertyTypes, ImportError> {
+    x.iter()
+        .map(|(k, v)| {
+            Ok((
+                k.clone(),
+                parse_name(v, metadata::PropertyType::from_str_name)?,
+            ))
+        })
+        .collect()
+}
+
+fn parse_property_values(x: &BTreeMap<String, Value>) -> metadata::PropertyValues {
+    x.iter()
+        .map(|(k, v)| {
+            let v = match v {
+                Value::Int(v) => metadata::PropertyValue::Int(*v),
+                Value::Double(v) => metadata::PropertyValue::Double(*v),
+                Value::String(v) => metadata::PropertyValue::String(v.clone()),
+                Value::Bytes(v) => metadata::PropertyValue::Bytes(v.clone()),
+                Value::Bool(v) => metadata::PropertyValue::Bool(*v),
+            };
+            (k.clone(), v)
+        })
+        .collect()
+}
+
+fn parse_name<T>(name: &str, f: fn(&str) -> Option<T>) -> Result<T, ImportError> {
+    f(name).ok_or_else(|| ImportError::UnknownEnumValue {
+        value: name.to_owned(),
+    })
+}
+
+fn lookup<T: Copy>(
+    ids: &HashMap<i32, T>,
+    id: i32,
+    error: impl FnOnce(i32) -> ImportError,
+) -> Result<T, ImportError> {
+    ids.get(&id).copied().ok_or_else(|| error(id))
+}
+
+fn millis(v: i64) -> Duration {
+    Duration::from_millis(v.max(0) as u64)
+}
+
+fn property_types(x: &metadata::PropertyTypes) -> BTreeMap<String, String> {
+    x.iter()
+        .map(|(k, v)| (k.clone(), v.as_str_name().to_owned()))
+        .collect()
+}
+
+fn property_values(x: &metadata::PropertyValues) -> BTreeMap<String, Value> {
+    x.iter().map(|(k, v)| (k.clone(), Value::from(v))).collect()
+}
+
+fn unknown() -> String {
+    "UNKNOWN".to_owned()
+}
+
+mod int64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: ToString, S: Serializer>(v: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&v.to_string())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: std::str::FromStr + TryFrom<i64>,
+        D: Deserializer<'de>,
+    {
+        // Parsers of protobuf JSON accept both strings and numbers as `int64` values.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Int64 {
+            Number(i64),
+            String(String),
+        }
+
+        match Int64::deserialize(deserializer)? {
+            Int64::Number(v) => T::try_from(v).ok(),
+            Int64::String(s) => s.parse().ok(),
+        }
+        .ok_or_else(|| serde::de::Error::custom("integer out of range or malformed"))
+    }
+}
+
+mod base64_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(v))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        base64::decode(s).map_err(serde::de::Error::custom)
+    }
+}
@@ -1,8 +1,15 @@
 use super::*;
+#[cfg(feature = "serde")]
+use crate::metadata::ExecutionIoViolation;
 use crate::metadata::{
     Artifact, ArtifactState, ArtifactType, Context, ContextType, Execution, ExecutionState,
     ExecutionType, PropertyValue,
 };
+use crate::requests::{
+    ArtifactOptions, ArtifactOrderByField, ContextOrderByField, EventOrderByField,
+    ExecutionOrderByField, GetArtifactsRequest, RelationOutcome,
+};
+use std::ops::Bound;
 use tempfile::NamedTempFile;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -18,6 +25,539 @@ async fn initialization_works() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_with_tls_works() -> anyhow::Result<()> {
+    // TLS options are applied to MySQL.
+    let options = tls_connect_options("mysql://localhost/mlmd", "/path/to/ca.pem")?;
+    let options = format!("{:?}", options);
+    assert!(options.contains("VerifyCa"), "{}", options);
+    assert!(options.contains("/path/to/ca.pem"), "{}", options);
+
+    // TLS options are ignored by SQLite.
+    let file = NamedTempFile::new()?;
+    MetadataStore::connect_with_tls(&sqlite_uri(file.path()), "/path/to/ca.pem").await?;
+
+    // Connects to a real MySQL server only if it's available.
+    if let (Ok(uri), Ok(ca_cert_path)) = (
+        std::env::var("MLMD_TEST_MYSQL_TLS_URI"),
+        std::env::var("MLMD_TEST_MYSQL_TLS_CA"),
+    ) {
+        MetadataStore::connect_with_tls(&uri, ca_cert_path).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ping_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    store.ping().await?;
+
+    let store = MetadataStore::connect_pool(&sqlite_uri(file.path()), 2).await?;
+    store.ping().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn initialize_with_schema_version_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let uri = sqlite_uri(file.path());
+
+    // An uninitialized database cannot be initialized with another schema version.
+    for _ in 0..2 {
+        assert!(matches!(
+            MetadataStore::connect_with_schema_version(&uri, 7).await,
+            Err(InitError::UnsupportedSchemaVersion {
+                actual: 7,
+                expected: 8
+            })
+        ));
+    }
+    assert!(matches!(
+        MetadataStore::connect_read_only(&uri).await,
+        Err(InitError::UninitializedDatabase)
+    ));
+
+    for _ in 0..2 {
+        let store = MetadataStore::connect_with_schema_version(&uri, 8).await?;
+        assert_eq!(store.schema_version().await?, 8);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn schema_version_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let uri = sqlite_uri(file.path());
+    let store = MetadataStore::connect(&uri).await?;
+    assert_eq!(store.schema_version().await?, 8);
+
+    sqlx::query("UPDATE MLMDEnv SET schema_version = 7")
+        .execute(&store.pool)
+        .await?;
+    std::mem::drop(store);
+
+    assert!(matches!(
+        MetadataStore::connect(&uri).await,
+        Err(InitError::UnsupportedSchemaVersion {
+            actual: 7,
+            expected: 8
+        })
+    ));
+    assert!(matches!(
+        MetadataStore::connect_with_schema_version(&uri, 6).await,
+        Err(InitError::UnsupportedSchemaVersion {
+            actual: 7,
+            expected: 6
+        })
+    ));
+
+    let store = MetadataStore::connect_with_schema_version(&uri, 7).await?;
+    assert_eq!(store.schema_version().await?, 7);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let uri = sqlite_uri(file.path());
+    let store = MetadataStore::connect(&uri).await?;
+
+    // Emulates a database of the schema version 6.
+    for sql in [
+        "ALTER TABLE ArtifactProperty DROP COLUMN byte_value",
+        "ALTER TABLE ExecutionProperty DROP COLUMN byte_value",
+        "ALTER TABLE ContextProperty DROP COLUMN byte_value",
+        "DROP INDEX idx_type_name",
+        "DROP INDEX idx_parentcontext_parent_context_id",
+        "DROP TABLE Type",
+        concat!(
+            "CREATE TABLE Type (",
+            "  id INTEGER PRIMARY KEY AUTOINCREMENT, name VARCHAR(255) NOT NULL, ",
+            "  type_kind TINYINT(1) NOT NULL, input_type TEXT, output_type TEXT)"
+        ),
+        "UPDATE MLMDEnv SET schema_version = 6",
+    ] {
+        sqlx::query(sql).execute(&store.pool).await?;
+    }
+    std::mem::drop(store);
+
+    assert!(matches!(
+        MetadataStore::connect(&uri).await,
+        Err(InitError::UnsupportedSchemaVersion {
+            actual: 6,
+            expected: 8
+        })
+    ));
+
+    let mut store = MetadataStore::connect_with_schema_version(&uri, 6).await?;
+    store.migrate().await?;
+    assert_eq!(store.schema_version().await?, 8);
+
+    // Migrating an up-to-date database is a no-op.
+    store.migrate().await?;
+    assert_eq!(store.schema_version().await?, 8);
+    std::mem::drop(store);
+
+    let mut store = MetadataStore::connect(&uri).await?;
+    let columns: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM pragma_table_info('Type') WHERE name IN ('version', 'description') ORDER BY name",
+    )
+    .fetch_all(&store.pool)
+    .await?;
+    assert_eq!(columns, ["description", "version"]);
+
+    let type_id = store
+        .put_artifact_type("t0")
+        .version("v1")
+        .description("foo")
+        .execute()
+        .await?;
+    let types = store.get_artifact_types().id(type_id).execute().await?;
+    assert_eq!(types[0].version.as_deref(), Some("v1"));
+    assert_eq!(types[0].description.as_deref(), Some("foo"));
+    let artifact_id = store
+        .post_artifact(type_id)
+        .custom_property("bytes", vec![1u8, 2, 3])
+        .execute()
+        .await?;
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(
+        artifact.custom_properties["bytes"],
+        PropertyValue::Bytes(vec![1, 2, 3])
+    );
+
+    // Downgrade is not supported.
+    sqlx::query("UPDATE MLMDEnv SET schema_version = 9")
+        .execute(&store.pool)
+        .await?;
+    assert!(matches!(
+        store.migrate().await,
+        Err(InitError::UnsupportedSchemaVersion {
+            actual: 9,
+            expected: 8
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn repair_mysql_property_tables_works() -> anyhow::Result<()> {
+    // Connects to a real MySQL server only if it's available.
+    let Ok(uri) = std::env::var("MLMD_TEST_MYSQL_URI") else {
+        return Ok(());
+    };
+    let store = MetadataStore::connect(&uri).await?;
+
+    // Emulates the property tables created by the older releases (schema version 8 without `byte_value`).
+    for kind in ["Artifact", "Execution", "Context"] {
+        let table = format!("{}Property", kind);
+        let id = format!("{}_id", kind.to_lowercase());
+        sqlx::query(&format!("DROP TABLE `{}`", table))
+            .execute(&store.pool)
+            .await?;
+        sqlx::query(&format!(
+            concat!(
+                " CREATE TABLE `{table}` ( ",
+                "   `{id}` INT NOT NULL, ",
+                "   `name` VARCHAR(255) NOT NULL, ",
+                "   `is_custom_property` TINYINT(1) NOT NULL, ",
+                "   `int_value` INT, ",
+                "   `double_value` DOUBLE, ",
+                "   `string_value` TEXT, ",
+                " PRIMARY KEY (`{id}`, `name`, `is_custom_property`)); "
+            ),
+            table = table,
+            id = id
+        ))
+        .execute(&store.pool)
+        .await?;
+    }
+    std::mem::drop(store);
+
+    let mut store = MetadataStore::connect(&uri).await?;
+    let artifact_type_id = store
+        .put_artifact_type("repair_artifact_type")
+        .execute()
+        .await?;
+    let artifact_id = store
+        .post_artifact(artifact_type_id)
+        .custom_property("foo", 1)
+        .custom_property("bytes", vec![1u8, 2, 3])
+        .execute()
+        .await?;
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(artifact.custom_properties["foo"], PropertyValue::Int(1));
+    assert_eq!(
+        artifact.custom_properties["bytes"],
+        PropertyValue::Bytes(vec![1, 2, 3])
+    );
+
+    let execution_type_id = store
+        .put_execution_type("repair_execution_type")
+        .execute()
+        .await?;
+    let execution_id = store
+        .post_execution(execution_type_id)
+        .custom_property("foo", "bar")
+        .execute()
+        .await?;
+    let execution = store.get_execution(execution_id).await?.expect("not found");
+    assert_eq!(
+        execution.custom_properties["foo"],
+        PropertyValue::String("bar".to_owned())
+    );
+
+    let context_type_id = store
+        .put_context_type("repair_context_type")
+        .execute()
+        .await?;
+    let context_id = store
+        .get_or_create_context(context_type_id, "repair_context")
+        .await?;
+    store
+        .put_context(context_id)
+        .custom_property("foo", 1.5)
+        .execute()
+        .await?;
+    let context = store.get_context(context_id).await?.expect("not found");
+    assert_eq!(context.custom_properties["foo"], PropertyValue::Double(1.5));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_initialization_works() -> anyhow::Result<()> {
+    async fn connect_concurrently(uri: &str) -> anyhow::Result<()> {
+        let stores =
+            futures::future::try_join_all((0..4).map(|_| MetadataStore::connect(uri))).await?;
+        for store in &stores {
+            store.get_artifact_types().execute().await?;
+        }
+        Ok(())
+    }
+
+    let file = NamedTempFile::new()?;
+    connect_concurrently(&sqlite_uri(file.path())).await?;
+
+    // Connects to a real MySQL server only if it's available.
+    if let Ok(uri) = std::env::var("MLMD_TEST_MYSQL_URI") {
+        connect_concurrently(&uri).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reinitialization_works() -> anyhow::Result<()> {
+    async fn reinitialize(uri: &str) -> anyhow::Result<()> {
+        let store = MetadataStore::connect(uri).await?;
+
+        // Makes the next connection re-run the table and index creation.
+        sqlx::query("DELETE FROM MLMDEnv")
+            .execute(&store.pool)
+            .await?;
+        let store = MetadataStore::connect(uri).await?;
+        assert_eq!(store.schema_version().await?, SCHEMA_VERSION);
+        store.get_artifact_types().execute().await?;
+        Ok(())
+    }
+
+    let file = NamedTempFile::new()?;
+    reinitialize(&sqlite_uri(file.path())).await?;
+
+    // Connects to a real MySQL server only if it's available.
+    if let Ok(uri) = std::env::var("MLMD_TEST_MYSQL_URI") {
+        reinitialize(&uri).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_pool_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect_pool(&sqlite_uri(file.path()), 4).await?;
+
+    // Concurrent requests on the shared store.
+    let (artifacts, executions, contexts) = futures::try_join!(
+        store.get_artifacts().execute(),
+        store.get_executions().execute(),
+        store.get_contexts().execute(),
+    )?;
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(executions, vec![execution0()]);
+    assert_eq!(contexts.len(), 1);
+
+    // Clones share the pool and can be moved into spawned tasks.
+    let handles = (0..4)
+        .map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move { store.get_artifacts().count().await })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        assert_eq!(handle.await??, 2);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_with_config_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let config = MetadataStoreConfig::new(&sqlite_uri(file.path()))
+        .max_connections(4)
+        .connect_timeout(Duration::from_secs(10))
+        .tls_ca_cert_path("/path/to/ca.pem")
+        .schema_version(8)
+        .max_uri_length(16);
+    let mut store = MetadataStore::connect_with(config).await?;
+
+    let (artifacts, executions) = futures::try_join!(
+        store.get_artifacts().execute(),
+        store.get_executions().execute(),
+    )?;
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(executions, vec![execution0()]);
+
+    assert!(matches!(
+        store
+            .post_artifact(TypeId::new(10))
+            .uri("path/to/too/long/uri")
+            .execute()
+            .await,
+        Err(PostError::UriTooLong { len: 20, max: 16 })
+    ));
+
+    let config = MetadataStoreConfig::new(&sqlite_uri(file.path())).schema_version(7);
+    assert!(matches!(
+        MetadataStore::connect_with(config).await,
+        Err(InitError::UnsupportedSchemaVersion {
+            actual: 8,
+            expected: 7
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retry_works() -> anyhow::Result<()> {
+    use sqlx::Connection as _;
+
+    let file = NamedTempFile::new()?;
+    let uri = sqlite_uri(file.path());
+    let config = MetadataStoreConfig::new(&uri).retry(5, Duration::from_millis(200));
+    let mut store = MetadataStore::connect_with(config).await?;
+    let type_id = store.put_context_type("t0").execute().await?;
+    store.post_context(type_id, "c0").execute().await?;
+
+    // Makes the lock conflicts surface immediately as `SQLITE_BUSY` errors.
+    sqlx::query("PRAGMA busy_timeout = 0")
+        .execute(&store.pool)
+        .await?;
+
+    // Another connection holds the write lock for a while.
+    let mut other = sqlx::AnyConnection::connect(&uri).await?;
+    sqlx::query("BEGIN IMMEDIATE").execute(&mut other).await?;
+    let handle = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sqlx::query("COMMIT").execute(&mut other).await
+    });
+    store.post_context(type_id, "c1").execute().await?;
+    handle.await??;
+
+    // Constraint violations are not retried.
+    let start = std::time::Instant::now();
+    assert!(matches!(
+        store.post_context(type_id, "c1").execute().await,
+        Err(PostError::NameAlreadyExists { .. })
+    ));
+    assert!(start.elapsed() < Duration::from_millis(200));
+
+    // Without retries, the lock conflict is reported as is.
+    let mut store = MetadataStore::connect(&uri).await?;
+    sqlx::query("PRAGMA busy_timeout = 0")
+        .execute(&store.pool)
+        .await?;
+    let mut other = sqlx::AnyConnection::connect(&uri).await?;
+    sqlx::query("BEGIN IMMEDIATE").execute(&mut other).await?;
+    assert!(matches!(
+        store.post_context(type_id, "c2").execute().await,
+        Err(PostError::Db(sqlx::Error::Database(_)))
+    ));
+    sqlx::query("COMMIT").execute(&mut other).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_read_only_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect_read_only(&sqlite_uri(file.path())).await?;
+    assert_eq!(store.get_artifacts().execute().await?.len(), 2);
+    assert!(store.put_artifact_type("foo").execute().await.is_err());
+
+    // An explicitly read-only opened file also works.
+    let uri = format!("{}?mode=ro", sqlite_uri(file.path()));
+    let store = MetadataStore::connect_read_only(&uri).await?;
+    assert_eq!(store.get_artifacts().execute().await?.len(), 2);
+
+    // Tables are never created.
+    let file = NamedTempFile::new()?;
+    assert!(matches!(
+        MetadataStore::connect_read_only(&sqlite_uri(file.path())).await,
+        Err(InitError::UninitializedDatabase)
+    ));
+    assert_eq!(std::fs::metadata(file.path())?.len(), 0);
+
+    // The schema version is still checked.
+    let file = existing_db();
+    let config = MetadataStoreConfig::new(&sqlite_uri(file.path()))
+        .read_only()
+        .schema_version(7);
+    assert!(matches!(
+        MetadataStore::connect_with(config).await,
+        Err(InitError::UnsupportedSchemaVersion {
+            actual: 8,
+            expected: 7
+        })
+    ));
+
+    // Database errors other than a missing table are reported as they are.
+    let file = NamedTempFile::new()?;
+    let pool = sqlx::AnyPool::connect(&sqlite_uri(file.path())).await?;
+    sqlx::query("CREATE TABLE MLMDEnv (foo INTEGER)")
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+    assert!(matches!(
+        MetadataStore::connect_read_only(&sqlite_uri(file.path())).await,
+        Err(InitError::Db(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_from_env_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    std::env::set_var("MLMD_TEST_DATABASE_URI", sqlite_uri(file.path()));
+    MetadataStore::connect_from_env_var("MLMD_TEST_DATABASE_URI").await?;
+
+    assert!(matches!(
+        MetadataStore::connect_from_env_var("MLMD_TEST_UNDEFINED_DATABASE_URI").await,
+        Err(InitError::EnvVar {
+            source: std::env::VarError::NotPresent,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_invalid_uri_works() -> anyhow::Result<()> {
+    for uri in [
+        "sqlite://",
+        "sqlite:",
+        "sqlite://?mode=rwc",
+        "mysql:",
+        "mysql://",
+        "foo",
+    ] {
+        assert!(
+            matches!(
+                MetadataStore::connect(uri).await,
+                Err(InitError::InvalidUri { uri: ref x, .. }) if x == uri
+            ),
+            "uri={:?}",
+            uri
+        );
+    }
+    for uri in ["postgres://localhost/mlmd", "sqlitex://foo.db"] {
+        assert!(
+            matches!(
+                MetadataStore::connect(uri).await,
+                Err(InitError::UnsupportedDatabase)
+            ),
+            "uri={:?}",
+            uri
+        );
+    }
+
+    // In-memory databases are valid.
+    MetadataStore::connect("sqlite::memory:").await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn put_artifact_type_works() -> anyhow::Result<()> {
     let file = NamedTempFile::new()?;
@@ -87,6 +627,8 @@ async fn get_artifact_type_works() -> anyhow::Result<()> {
         ArtifactType {
             id: t0_id,
             name: "t0".to_owned(),
+            version: None,
+            description: None,
             properties: vec![("p0".to_owned(), PropertyType::Int)]
                 .into_iter()
                 .collect()
@@ -97,6 +639,8 @@ async fn get_artifact_type_works() -> anyhow::Result<()> {
         ArtifactType {
             id: t1_id,
             name: "t1".to_owned(),
+            version: None,
+            description: None,
             properties: BTreeMap::new(),
         }
     );
@@ -111,817 +655,5114 @@ async fn get_artifact_type_works() -> anyhow::Result<()> {
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_artifact_types_works() -> anyhow::Result<()> {
-    let file = existing_db();
+async fn artifact_type_version_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-    let types = store.get_artifact_types().execute().await?;
-    assert_eq!(types.len(), 6);
-    assert_eq!(types[0].name, "mlmd.Dataset");
-    assert_eq!(types[1].name, "mlmd.Model");
+
+    let t0_id = store.put_artifact_type("t0").execute().await?;
+    let t1_id = store
+        .put_artifact_type("t0")
+        .version("v1")
+        .execute()
+        .await?;
+    let t2_id = store
+        .put_artifact_type("t0")
+        .version("v2")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    assert_ne!(t0_id, t1_id);
+    assert_ne!(t1_id, t2_id);
+    assert_eq!(
+        store
+            .put_artifact_type("t0")
+            .version("v1")
+            .execute()
+            .await?,
+        t1_id
+    );
+
+    let types = store.get_artifact_types().name("t0").execute().await?;
+    assert_eq!(
+        types,
+        vec![
+            ArtifactType {
+                id: t0_id,
+                name: "t0".to_owned(),
+                version: None,
+                description: None,
+                properties: BTreeMap::new(),
+            },
+            ArtifactType {
+                id: t1_id,
+                name: "t0".to_owned(),
+                version: Some("v1".to_owned()),
+                description: None,
+                properties: BTreeMap::new(),
+            },
+            ArtifactType {
+                id: t2_id,
+                name: "t0".to_owned(),
+                version: Some("v2".to_owned()),
+                description: None,
+                properties: vec![("p0".to_owned(), PropertyType::Int)]
+                    .into_iter()
+                    .collect()
+            }
+        ]
+    );
+
+    let t3_id = store.put_context_type("t0").version("v1").execute().await?;
+    let types = store.get_context_types().id(t3_id).execute().await?;
+    assert_eq!(types[0].version.as_deref(), Some("v1"));
+
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_artifacts_works() -> anyhow::Result<()> {
-    let file = existing_db();
+async fn artifact_type_description_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    // All.
-    let mut artifact0 = artifact0();
-    artifact0.type_id = TypeId::new(10);
-
-    let artifacts = store.get_artifacts().execute().await?;
-    assert_eq!(artifacts, vec![artifact0.clone(), artifact1()]);
-
-    // By type name.
-    let artifacts = store.get_artifacts().ty("DataSet").execute().await?;
-    assert_eq!(artifacts, vec![artifact0]);
-
-    // By ID.
-    let unregistered_id = ArtifactId::new(100);
-    let artifacts = store
-        .get_artifacts()
-        .ids([ArtifactId::new(2), unregistered_id].iter().copied())
+    let t0_id = store
+        .put_artifact_type("t0")
+        .description("foo")
+        .property("p0", PropertyType::Int)
         .execute()
         .await?;
-    assert_eq!(artifacts[0].id.get(), 2);
-    assert_eq!(artifacts, vec![artifact1()]);
+    let types = store.get_artifact_types().name("t0").execute().await?;
+    assert_eq!(types[0].description.as_deref(), Some("foo"));
 
-    // By URI.
-    let artifacts = store
-        .get_artifacts()
-        .uri("path/to/model/file")
+    // Updates the description.
+    store
+        .put_artifact_type("t0")
+        .description("bar")
+        .property("p0", PropertyType::Int)
         .execute()
         .await?;
-    assert_eq!(artifacts, vec![artifact1()]);
+    let types = store.get_artifact_types().name("t0").execute().await?;
+    assert_eq!(types[0].id, t0_id);
+    assert_eq!(types[0].description.as_deref(), Some("bar"));
 
-    // By Context.
-    let artifacts = store
-        .get_artifacts()
-        .context(ContextId::new(1))
+    // Keeps the description if not specified.
+    store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
         .execute()
         .await?;
-    assert_eq!(artifacts, vec![artifact1()]);
+    let types = store.get_artifact_types().name("t0").execute().await?;
+    assert_eq!(types[0].description.as_deref(), Some("bar"));
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn post_artifact_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new().unwrap();
+async fn get_artifact_types_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let types = store.get_artifact_types().execute().await?;
+    assert_eq!(types.len(), 6);
+    assert_eq!(types[0].name, "mlmd.Dataset");
+    assert_eq!(types[1].name, "mlmd.Model");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_types_by_name_pattern_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    assert!(store.get_artifacts().execute().await?.is_empty());
+    store.put_artifact_type("DataSet").execute().await?;
+    store.put_artifact_type("DataView").execute().await?;
+    store.put_artifact_type("Model").execute().await?;
+    store.put_execution_type("DataGen").execute().await?;
 
-    let type_id = store
-        .put_artifact_type("DataSet")
-        .property("day", PropertyType::Int)
-        .property("split", PropertyType::String)
+    let types = store
+        .get_artifact_types()
+        .name_pattern("Data%")
         .execute()
         .await?;
-    // Simple artifact.
-    let artifact_id = store.post_artifact(type_id).execute().await?;
-
-    let artifacts = store.get_artifacts().execute().await?;
-    assert_eq!(artifacts.len(), 1);
-    assert_eq!(artifacts[0].id, artifact_id);
-
-    // Complex artifact.
-    let mut expected = artifact0();
-    expected.id = store
-        .post_artifact(type_id)
-        .uri(expected.uri.as_ref().unwrap())
-        .properties(expected.properties.clone())
+    assert_eq!(
+        types.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["DataSet", "DataView"]
+    );
+    let types = store
+        .get_execution_types()
+        .name_pattern("Data%")
         .execute()
         .await?;
-    let artifacts = store.get_artifacts().execute().await?;
-    assert_eq!(artifacts.len(), 2);
-
-    expected.create_time_since_epoch = artifacts[1].create_time_since_epoch;
-    expected.last_update_time_since_epoch = artifacts[1].last_update_time_since_epoch;
-    assert_eq!(artifacts[1], expected);
+    assert_eq!(
+        types.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["DataGen"]
+    );
 
-    // Name confilict.
-    store.post_artifact(type_id).name("foo").execute().await?;
-    assert!(matches!(
-        store
-            .post_artifact(type_id)
-            .name("foo")
-            .execute()
-            .await
-            .err(),
-        Some(PostError::NameAlreadyExists { .. })
-    ));
+    // The last one of `name` and `name_pattern` wins.
+    let types = store
+        .get_artifact_types()
+        .name("Model")
+        .name_pattern("Data%")
+        .execute()
+        .await?;
+    assert_eq!(types.len(), 2);
+    let types = store
+        .get_artifact_types()
+        .name_pattern("Data%")
+        .name("Model")
+        .execute()
+        .await?;
+    assert_eq!(
+        types.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["Model"]
+    );
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_artifact_works() -> anyhow::Result<()> {
+async fn get_types_by_ids_works() -> anyhow::Result<()> {
     let file = existing_db();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-    assert_eq!(store.get_artifacts().execute().await?.len(), 2);
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let ids = [10, 11, 12, 13, 100].map(TypeId::new);
+    let types = store.get_artifact_types_by_ids(ids.into_iter()).await?;
+    assert_eq!(types.len(), 2);
+    for id in [ids[0], ids[1]] {
+        let expected = store.get_artifact_types().id(id).execute().await?;
+        assert_eq!(types[&id], expected[0]);
+    }
 
-    let mut artifact = artifact0();
-    artifact.type_id = TypeId::new(10);
-    artifact.name = Some("foo".to_string());
-    artifact.state = ArtifactState::Live;
-    artifact
-        .properties
-        .insert("day".to_owned(), PropertyValue::Int(234));
-    artifact
-        .custom_properties
-        .insert("bar".to_string(), PropertyValue::Int(10));
-    store
-        .put_artifact(artifact.id)
-        .name(artifact.name.as_ref().unwrap().as_str())
-        .state(artifact.state)
-        .properties(artifact.properties.clone())
-        .custom_properties(artifact.custom_properties.clone())
-        .execute()
-        .await?;
+    let types = store.get_execution_types_by_ids(ids.into_iter()).await?;
+    assert_eq!(types.keys().copied().collect::<Vec<_>>(), [ids[2]]);
+    assert_eq!(types[&ids[2]].name, "Trainer");
 
-    let artifacts = store.get_artifacts().id(artifact.id).execute().await?;
-    assert_eq!(artifacts.len(), 1);
+    let types = store.get_context_types_by_ids(ids.into_iter()).await?;
+    assert_eq!(types.keys().copied().collect::<Vec<_>>(), [ids[3]]);
+    assert_eq!(types[&ids[3]].name, "Experiment");
 
-    artifact.last_update_time_since_epoch = artifacts[0].last_update_time_since_epoch;
-    assert_eq!(artifacts[0], artifact);
+    assert!(store
+        .get_artifact_types_by_ids(std::iter::empty())
+        .await?
+        .is_empty());
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_executions_works() -> anyhow::Result<()> {
-    let file = existing_db();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-
-    // All.
-    let executions = store.get_executions().execute().await?;
-    assert_eq!(executions, vec![execution0()]);
+async fn type_cache_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
+        .await?
+        .with_type_cache(10);
 
-    // By type name.
-    let executions = store.get_executions().ty("Trainer").execute().await?;
-    assert_eq!(executions, vec![execution0()]);
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    store
+        .post_artifact(type_id)
+        .property("p0", 1)
+        .execute()
+        .await?;
 
-    let executions = store.get_executions().ty("foo").execute().await?;
-    assert_eq!(executions, vec![]);
+    // Removes the property definitions behind the cache.
+    sqlx::query("DELETE FROM TypeProperty")
+        .execute(&store.pool)
+        .await?;
 
-    // By ID.
-    let unregistered_id = ExecutionId::new(100);
-    let executions = store
-        .get_executions()
-        .ids([ExecutionId::new(1), unregistered_id].iter().copied())
+    // The cached definitions are used.
+    store
+        .post_artifact(type_id)
+        .property("p0", 2)
         .execute()
         .await?;
-    assert_eq!(executions, vec![execution0()]);
 
-    // By Context.
-    let executions = store
-        .get_executions()
-        .context(ContextId::new(1))
+    // A store without the cache sees the removal.
+    let mut uncached_store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert!(matches!(
+        uncached_store
+            .post_artifact(type_id)
+            .property("p0", 3)
+            .execute()
+            .await,
+        Err(PostError::UndefinedProperty { property_name, .. }) if property_name == "p0"
+    ));
+
+    // Updating the type invalidates the cache.
+    store
+        .put_artifact_type("t0")
+        .can_add_fields()
+        .property("p1", PropertyType::Int)
+        .execute()
+        .await?;
+    assert!(matches!(
+        store
+            .post_artifact(type_id)
+            .property("p0", 3)
+            .execute()
+            .await,
+        Err(PostError::UndefinedProperty { property_name, .. }) if property_name == "p0"
+    ));
+    store
+        .post_artifact(type_id)
+        .property("p1", 3)
         .execute()
         .await?;
-    assert_eq!(executions, vec![execution0()]);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_execution_works() -> anyhow::Result<()> {
-    let file = existing_db();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-    assert_eq!(store.get_executions().execute().await?.len(), 1);
+async fn type_cache_eviction_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let config = MetadataStoreConfig::new(&sqlite_uri(file.path())).type_cache(1);
+    let mut store = MetadataStore::connect_with(config).await?;
 
-    let mut execution = execution0();
-    execution.name = Some("foo".to_string());
-    execution.last_known_state = ExecutionState::Running;
-    execution
-        .custom_properties
-        .insert("bar".to_string(), PropertyValue::Int(10));
-    store
-        .put_execution(execution.id)
-        .name(execution.name.as_ref().unwrap())
-        .state(execution.last_known_state)
-        .custom_properties(execution.custom_properties.clone())
+    let t0 = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let t1 = store
+        .put_artifact_type("t1")
+        .property("p1", PropertyType::Int)
         .execute()
         .await?;
 
-    let executions = store.get_executions().execute().await?;
-    assert_eq!(executions.len(), 1);
+    // Caching `t1` evicts `t0` as the capacity is one.
+    store.post_artifact(t0).property("p0", 1).execute().await?;
+    store.post_artifact(t1).property("p1", 1).execute().await?;
 
-    execution.last_update_time_since_epoch = executions[0].last_update_time_since_epoch;
-    assert_eq!(executions[0], execution);
+    // Removes the property definitions behind the cache.
+    sqlx::query("DELETE FROM TypeProperty")
+        .execute(&store.pool)
+        .await?;
+
+    store.post_artifact(t1).property("p1", 2).execute().await?;
+    assert!(matches!(
+        store.post_artifact(t0).property("p0", 2).execute().await,
+        Err(PostError::UndefinedProperty { property_name, .. }) if property_name == "p0"
+    ));
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn post_execution_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new().unwrap();
+async fn put_parent_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    assert!(store.get_executions().execute().await?.is_empty());
+    let t0 = store.put_artifact_type("Model").execute().await?;
+    let t1 = store.put_artifact_type("SavedModel").execute().await?;
+    for expected in [RelationOutcome::Inserted, RelationOutcome::AlreadyExisted] {
+        // The duplicate PUTs are ignored.
+        let outcome = store.put_parent_type(t1, t0).execute().await?;
+        assert_eq!(outcome, expected);
 
-    let type_id = store
-        .put_execution_type("DataSet")
-        .property("day", PropertyType::Int)
-        .property("split", PropertyType::String)
+        let types = store.get_artifact_types().child(t1).execute().await?;
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0].id, t0);
+    }
+    assert!(store
+        .get_artifact_types()
+        .child(t0)
         .execute()
-        .await?;
+        .await?
+        .is_empty());
 
-    // Simple execution.
-    let execution_id = store.post_execution(type_id).execute().await?;
+    // Different kinds.
+    let t2 = store.put_context_type("Pipeline").execute().await?;
+    assert!(matches!(
+        store.put_parent_type(t1, t2).execute().await,
+        Err(PutError::TypeKindMismatch {
+            expected: TypeKind::Artifact,
+            actual: TypeKind::Context,
+            ..
+        })
+    ));
+    assert!(store
+        .get_context_types()
+        .child(t1)
+        .execute()
+        .await?
+        .is_empty());
 
-    let executions = store.get_executions().execute().await?;
-    assert_eq!(executions.len(), 1);
-    assert_eq!(executions[0].id, execution_id);
+    // Undefined type.
+    assert!(matches!(
+        store.put_parent_type(t1, TypeId::new(100)).execute().await,
+        Err(PutError::TypeIdNotFound { .. })
+    ));
 
-    // Name confilict.
-    store.post_execution(type_id).name("foo").execute().await?;
+    // Self parent.
     assert!(matches!(
-        store
-            .post_execution(type_id)
-            .name("foo")
-            .execute()
-            .await
-            .err(),
-        Some(PostError::NameAlreadyExists { .. })
+        store.put_parent_type(t0, t0).execute().await,
+        Err(PutError::SelfParentType { type_id }) if type_id == t0
     ));
+    assert!(store
+        .get_artifact_types()
+        .child(t0)
+        .execute()
+        .await?
+        .is_empty());
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_execution_type_works() -> anyhow::Result<()> {
+async fn type_and_name_ci_works() -> anyhow::Result<()> {
     let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    store
-        .put_execution_type("t0")
-        .property("p0", PropertyType::Int)
+    let artifact_type_id = store.put_artifact_type("DataSet").execute().await?;
+    let artifact_id = store
+        .post_artifact(artifact_type_id)
+        .name("Train")
+        .execute()
+        .await?;
+    let execution_type_id = store.put_execution_type("Trainer").execute().await?;
+    let execution_id = store
+        .post_execution(execution_type_id)
+        .name("Run1")
+        .execute()
+        .await?;
+    let context_type_id = store.put_context_type("Experiment").execute().await?;
+    let context_id = store
+        .post_context(context_type_id, "Exp1")
         .execute()
         .await?;
 
-    assert!(matches!(
-        store
-            .put_execution_type("t0")
-            .property("p0", PropertyType::Double)
-            .execute()
-            .await,
-        Err(PutError::TypeAlreadyExists { .. })
-    ));
+    // Case-sensitive.
+    let artifacts = store
+        .get_artifacts()
+        .type_and_name("dataset", "train")
+        .execute()
+        .await?;
+    assert!(artifacts.is_empty());
 
-    assert!(matches!(
-        store
-            .put_execution_type("t0")
-            .property("p0", PropertyType::Int)
-            .property("p1", PropertyType::String)
-            .execute()
-            .await,
-        Err(PutError::TypeAlreadyExists { .. })
-    ));
-    store
-        .put_execution_type("t0")
-        .can_add_fields()
-        .property("p0", PropertyType::Int)
-        .property("p1", PropertyType::String)
+    // Case-insensitive.
+    let artifacts = store
+        .get_artifacts()
+        .type_and_name_ci("dataset", "TRAIN")
         .execute()
         .await?;
+    assert_eq!(
+        artifacts.iter().map(|x| x.id).collect::<Vec<_>>(),
+        [artifact_id]
+    );
 
-    assert!(matches!(
-        store.put_execution_type("t0").execute().await,
-        Err(PutError::TypeAlreadyExists { .. })
-    ));
-    store
-        .put_execution_type("t0")
-        .can_omit_fields()
+    let executions = store
+        .get_executions()
+        .type_and_name_ci("TRAINER", "run1")
         .execute()
         .await?;
-    store.put_execution_type("t1").execute().await?;
-
-    Ok(())
-}
-
-#[tokio::test(flavor = "multi_thread")]
-async fn get_execution_type_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new()?;
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert_eq!(
+        executions.iter().map(|x| x.id).collect::<Vec<_>>(),
+        [execution_id]
+    );
 
-    let t0_id = store
-        .put_execution_type("t0")
-        .property("p0", PropertyType::Int)
+    let contexts = store
+        .get_contexts()
+        .type_and_name_ci("experiment", "exp1")
         .execute()
         .await?;
-    let t1_id = store.put_execution_type("t1").execute().await?;
-    assert_ne!(t0_id, t1_id);
-
-    assert_eq!(
-        store.get_execution_types().name("t0").execute().await?[0],
-        ExecutionType {
-            id: t0_id,
-            name: "t0".to_owned(),
-            properties: vec![("p0".to_owned(), PropertyType::Int)]
-                .into_iter()
-                .collect()
-        }
-    );
     assert_eq!(
-        store.get_execution_types().name("t1").execute().await?[0],
-        ExecutionType {
-            id: t1_id,
-            name: "t1".to_owned(),
-            properties: BTreeMap::new(),
-        }
+        contexts.iter().map(|x| x.id).collect::<Vec<_>>(),
+        [context_id]
     );
-    assert!(store
-        .get_execution_types()
-        .name("t2")
-        .execute()
-        .await?
-        .is_empty());
 
-    Ok(())
-}
+    // The last call overrides the case sensitivity.
+    let contexts = store
+        .get_contexts()
+        .type_and_name_ci("experiment", "exp1")
+        .type_and_name("experiment", "exp1")
+        .execute()
+        .await?;
+    assert!(contexts.is_empty());
 
-#[tokio::test(flavor = "multi_thread")]
-async fn get_execution_types_works() -> anyhow::Result<()> {
-    let file = existing_db();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-    let types = store.get_execution_types().execute().await?;
-    assert_eq!(types.len(), 6);
-    assert_eq!(types[0].name, "mlmd.Train");
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_contexts_works() -> anyhow::Result<()> {
+async fn get_artifacts_works() -> anyhow::Result<()> {
     let file = existing_db();
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
     // All.
-    let contexts = store.get_contexts().execute().await?;
-    assert_eq!(contexts, vec![context0()]);
-
-    // By type name.
-    let contexts = store.get_contexts().ty("Experiment").execute().await?;
-    assert_eq!(contexts, vec![context0()]);
+    let mut artifact0 = artifact0();
+    artifact0.type_id = TypeId::new(10);
 
-    let contexts = store.get_contexts().ty("foo").execute().await?;
-    assert_eq!(contexts, vec![]);
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts, vec![artifact0.clone(), artifact1()]);
 
-    let contexts = store
-        .get_contexts()
-        .type_and_name("Experiment", "exp1")
-        .execute()
-        .await?;
-    assert_eq!(contexts, vec![context0()]);
+    // By type name.
+    let artifacts = store.get_artifacts().ty("DataSet").execute().await?;
+    assert_eq!(artifacts, vec![artifact0]);
 
     // By ID.
-    let unregistered_id = ContextId::new(100);
-    let contexts = store
-        .get_contexts()
-        .ids([ContextId::new(1), unregistered_id].iter().copied())
+    let unregistered_id = ArtifactId::new(100);
+    let artifacts = store
+        .get_artifacts()
+        .ids([ArtifactId::new(2), unregistered_id].iter().copied())
         .execute()
         .await?;
-    assert_eq!(contexts, vec![context0()]);
+    assert_eq!(artifacts[0].id.get(), 2);
+    assert_eq!(artifacts, vec![artifact1()]);
 
-    // By artifact.
-    let contexts = store
-        .get_contexts()
-        .artifact(ArtifactId::new(2))
+    // By URI.
+    let artifacts = store
+        .get_artifacts()
+        .uri("path/to/model/file")
         .execute()
         .await?;
-    assert_eq!(contexts, vec![context0()]);
+    assert_eq!(artifacts, vec![artifact1()]);
 
-    // By execution.
-    let contexts = store
-        .get_contexts()
-        .execution(ExecutionId::new(1))
+    // By Context.
+    let artifacts = store
+        .get_artifacts()
+        .context(ContextId::new(1))
         .execute()
         .await?;
-    assert_eq!(contexts, vec![context0()]);
+    assert_eq!(artifacts, vec![artifact1()]);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_context_works() -> anyhow::Result<()> {
+async fn get_artifacts_by_property_works() -> anyhow::Result<()> {
     let file = existing_db();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-    assert_eq!(store.get_contexts().execute().await?.len(), 1);
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    let mut context = context0();
-    context.name = "foo".to_string();
-    context
-        .custom_properties
-        .insert("bar".to_string(), PropertyValue::Int(10));
-    store
-        .put_context(context.id)
-        .name(&context.name)
-        .custom_properties(context.custom_properties.clone())
+    let mut artifact0 = artifact0();
+    artifact0.type_id = TypeId::new(10);
+
+    let artifacts = store
+        .get_artifacts()
+        .property_eq("split", PropertyValue::String("train".to_owned()))
         .execute()
         .await?;
+    assert_eq!(artifacts, vec![artifact0.clone()]);
 
-    let contexts = store.get_contexts().execute().await?;
-    assert_eq!(contexts.len(), 1);
+    let artifacts = store
+        .get_artifacts()
+        .property_eq("day", PropertyValue::Int(1))
+        .property_eq("split", PropertyValue::String("train".to_owned()))
+        .execute()
+        .await?;
+    assert_eq!(artifacts, vec![artifact0]);
 
-    context.last_update_time_since_epoch = contexts[0].last_update_time_since_epoch;
-    assert_eq!(contexts[0], context);
+    let count = store
+        .get_artifacts()
+        .property_eq("day", PropertyValue::Int(1))
+        .property_eq("split", PropertyValue::String("test".to_owned()))
+        .count()
+        .await?;
+    assert_eq!(count, 0);
+
+    let artifacts = store
+        .get_artifacts()
+        .property_eq("version", PropertyValue::Int(1))
+        .execute()
+        .await?;
+    assert_eq!(artifacts, vec![artifact1()]);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn post_context_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new().unwrap();
+async fn get_artifacts_by_uris_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    assert!(store.get_contexts().execute().await?.is_empty());
-
-    let type_id = store.put_context_type("Context").execute().await?;
+    let t0 = store.put_artifact_type("t0").execute().await?;
+    let t1 = store.put_artifact_type("t1").execute().await?;
+    let a0 = store.post_artifact(t0).uri("/a0").execute().await?;
+    let a1 = store
+        .post_artifact(t0)
+        .uri("/a1")
+        .state(ArtifactState::Live)
+        .execute()
+        .await?;
+    let a2 = store.post_artifact(t1).uri("/a2").execute().await?;
 
-    // Simple context.
-    let context_id = store.post_context(type_id, "bar").execute().await?;
+    let request = || {
+        store
+            .get_artifacts()
+            .uris(["/a0", "/a1", "/a2", "/a3"].into_iter())
+    };
+    let artifacts = request().execute().await?;
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![a0, a1, a2]
+    );
+    assert_eq!(request().count().await?, 3);
 
-    let contexts = store.get_contexts().execute().await?;
-    assert_eq!(contexts.len(), 1);
-    assert_eq!(contexts[0].id, context_id);
+    let artifacts = request().ty("t0").execute().await?;
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![a0, a1]
+    );
+    let artifacts = request().state(ArtifactState::Live).execute().await?;
+    assert_eq!(artifacts.iter().map(|a| a.id).collect::<Vec<_>>(), vec![a1]);
 
-    // Name confilict.
-    store.post_context(type_id, "foo").execute().await?;
-    assert!(matches!(
-        store.post_context(type_id, "foo").execute().await.err(),
-        Some(PostError::NameAlreadyExists { .. })
-    ));
+    // An empty URI set doesn't restrict the result.
+    let artifacts = store
+        .get_artifacts()
+        .uris(std::iter::empty())
+        .execute()
+        .await?;
+    assert_eq!(artifacts.len(), 3);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_context_type_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new()?;
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+async fn get_artifacts_by_uri_pattern_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    store
-        .put_context_type("t0")
-        .property("p0", PropertyType::Int)
+    let mut artifact0 = artifact0();
+    artifact0.type_id = TypeId::new(10);
+
+    let artifacts = store
+        .get_artifacts()
+        .uri_pattern("path/to/%")
         .execute()
         .await?;
+    assert_eq!(artifacts, vec![artifact0.clone(), artifact1()]);
 
-    assert!(matches!(
-        store
-            .put_context_type("t0")
-            .property("p0", PropertyType::Double)
-            .execute()
-            .await,
-        Err(PutError::TypeAlreadyExists { .. })
-    ));
+    let artifacts = store
+        .get_artifacts()
+        .uri_pattern("%/model/%")
+        .execute()
+        .await?;
+    assert_eq!(artifacts, vec![artifact1()]);
 
-    assert!(matches!(
-        store
-            .put_context_type("t0")
-            .property("p0", PropertyType::Int)
-            .property("p1", PropertyType::String)
-            .execute()
-            .await,
-        Err(PutError::TypeAlreadyExists { .. })
-    ));
-    store
-        .put_context_type("t0")
-        .can_add_fields()
-        .property("p0", PropertyType::Int)
-        .property("p1", PropertyType::String)
+    // If both `uri` and `uri_pattern` are specified, the last call wins.
+    let artifacts = store
+        .get_artifacts()
+        .uri("path/to/data")
+        .uri_pattern("path/to/m_del/%")
         .execute()
         .await?;
+    assert_eq!(artifacts, vec![artifact1()]);
 
-    assert!(matches!(
-        store.put_context_type("t0").execute().await,
-        Err(PutError::TypeAlreadyExists { .. })
-    ));
-    store
-        .put_context_type("t0")
-        .can_omit_fields()
+    let artifacts = store
+        .get_artifacts()
+        .uri_pattern("path/to/m_del/%")
+        .uri("path/to/data")
         .execute()
         .await?;
-    store.put_context_type("t1").execute().await?;
+    assert_eq!(artifacts, vec![artifact0]);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_context_type_works() -> anyhow::Result<()> {
+async fn get_artifacts_match_any_works() -> anyhow::Result<()> {
     let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    let t0_id = store
-        .put_context_type("t0")
-        .property("p0", PropertyType::Int)
-        .execute()
-        .await?;
-    let t1_id = store.put_context_type("t1").execute().await?;
-    assert_ne!(t0_id, t1_id);
+    let t0 = store.put_artifact_type("t0").execute().await?;
+    let t1 = store.put_artifact_type("t1").execute().await?;
+    let a0 = store.post_artifact(t0).uri("/a0").execute().await?;
+    let a1 = store.post_artifact(t0).uri("/a1").execute().await?;
+    let a2 = store.post_artifact(t1).uri("/a2").execute().await?;
+
+    async fn ids(request: GetArtifactsRequest<'_>) -> anyhow::Result<Vec<ArtifactId>> {
+        Ok(request.execute().await?.into_iter().map(|a| a.id).collect())
+    }
+
+    // The conditions are AND-ed by default.
+    let request = store.get_artifacts().ids([a0, a2].into_iter()).uri("/a2");
+    assert_eq!(ids(request).await?, vec![a2]);
+    let request = store.get_artifacts().id(a0).uri("/a1");
+    assert_eq!(ids(request).await?, vec![]);
 
+    // The ID and URI conditions are OR-ed with `match_any`.
+    let request = store.get_artifacts().id(a0).uri("/a1").match_any();
+    assert_eq!(ids(request).await?, vec![a0, a1]);
     assert_eq!(
-        store.get_context_types().name("t0").execute().await?[0],
-        ContextType {
-            id: t0_id,
-            name: "t0".to_owned(),
-            properties: vec![("p0".to_owned(), PropertyType::Int)]
-                .into_iter()
-                .collect()
-        }
+        store
+            .get_artifacts()
+            .id(a0)
+            .uri("/a1")
+            .match_any()
+            .count()
+            .await?,
+        2
     );
+    let request = store
+        .get_artifacts()
+        .ids([a0, a1].into_iter())
+        .uri_pattern("%2")
+        .match_any();
+    assert_eq!(ids(request).await?, vec![a0, a1, a2]);
+
+    // The other conditions are still AND-ed.
+    let request = store
+        .get_artifacts()
+        .ty("t0")
+        .ids([a0, a2].into_iter())
+        .uri("/a1")
+        .match_any();
+    assert_eq!(ids(request).await?, vec![a0, a1]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_artifacts_by_executions_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let ids = |artifacts: Vec<Artifact>| artifacts.into_iter().map(|x| x.id).collect::<Vec<_>>();
+
+    let request = || store.get_artifacts().execution(event0().execution_id);
     assert_eq!(
-        store.get_context_types().name("t1").execute().await?[0],
-        ContextType {
-            id: t1_id,
-            name: "t1".to_owned(),
-            properties: BTreeMap::new(),
-        }
+        ids(request().execute().await?),
+        vec![event0().artifact_id, event1().artifact_id]
     );
-    assert!(store
-        .get_context_types()
-        .name("t2")
+    assert_eq!(request().count().await?, 2);
+
+    // Only the artifacts produced by the execution.
+    assert!(event1().ty.is_output());
+    let artifacts = request().outputs_only().execute().await?;
+    assert_eq!(ids(artifacts), vec![event1().artifact_id]);
+
+    let artifacts = store
+        .get_artifacts()
+        .executions([ExecutionId::new(100)].into_iter())
         .execute()
-        .await?
-        .is_empty(),);
+        .await?;
+    assert_eq!(ids(artifacts), vec![]);
+
+    // Ignored if no executions are specified.
+    let artifacts = store.get_artifacts().outputs_only().execute().await?;
+    assert_eq!(artifacts.len(), 2);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_context_types_works() -> anyhow::Result<()> {
-    let file = existing_db();
+async fn get_artifacts_without_uri_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
-    let types = store.get_context_types().execute().await?;
-    assert_eq!(types.len(), 1);
-    assert_eq!(types[0].name, "Experiment");
+
+    let type_id = store.put_artifact_type("t0").execute().await?;
+    let a0 = store.post_artifact(type_id).execute().await?;
+    let _a1 = store.post_artifact(type_id).uri("/foo").execute().await?;
+    let a2 = store.post_artifact(type_id).uri("").execute().await?;
+
+    let artifacts = store.get_artifacts().without_uri().execute().await?;
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![a0, a2]
+    );
+    assert!(artifacts.iter().all(|a| a.uri.is_none()));
+
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_attribution_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new().unwrap();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
-        .await
-        .unwrap();
+async fn order_by_property_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    let t0 = store.put_artifact_type("t0").execute().await?;
-    let a0 = store.post_artifact(t0).execute().await?;
-    let _a1 = store.post_artifact(t0).execute().await?;
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("accuracy", PropertyType::Double)
+        .execute()
+        .await?;
+    let mut ids = Vec::new();
+    for accuracy in [Some(0.5), Some(0.9), None, Some(0.7)] {
+        let mut request = store.post_artifact(type_id);
+        if let Some(v) = accuracy {
+            request = request.property("accuracy", v);
+        }
+        ids.push(request.execute().await?);
+    }
 
-    let t1 = store.put_context_type("t1").execute().await?;
-    let _c0 = store.post_context(t1, "foo").execute().await?;
-    let c1 = store.post_context(t1, "bar").execute().await?;
+    let artifacts = store
+        .get_artifacts()
+        .order_by_property("accuracy", true)
+        .execute()
+        .await?;
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[0], ids[3], ids[1], ids[2]]
+    );
 
-    for _ in 0..2 {
-        store.put_attribution(c1, a0).execute().await?; // The duplicate PUTs are ignored.
-        let contexts = store.get_contexts().artifact(a0).execute().await?;
-        assert_eq!(contexts.len(), 1);
-        assert_eq!(contexts[0].id, c1);
+    let artifacts = store
+        .get_artifacts()
+        .order_by_property("accuracy", false)
+        .execute()
+        .await?;
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[1], ids[3], ids[0], ids[2]]
+    );
 
-        let artifacts = store.get_artifacts().context(c1).execute().await?;
-        assert_eq!(artifacts.len(), 1);
-        assert_eq!(artifacts[0].id, a0);
-    }
+    let artifacts = store
+        .get_artifacts()
+        .order_by_property("accuracy", false)
+        .limit(2)
+        .offset(1)
+        .execute()
+        .await?;
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![ids[3], ids[0]]
+    );
+
+    // Custom properties.
+    let type_id = store.put_context_type("t1").execute().await?;
+    let c0 = store
+        .post_context(type_id, "c0")
+        .custom_property("rank", 2)
+        .execute()
+        .await?;
+    let c1 = store
+        .post_context(type_id, "c1")
+        .custom_property("rank", 1)
+        .execute()
+        .await?;
+    let contexts = store
+        .get_contexts()
+        .order_by_custom_property("rank", true)
+        .execute()
+        .await?;
+    assert_eq!(
+        contexts.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![c1, c0]
+    );
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_association_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new().unwrap();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
-        .await
-        .unwrap();
-
-    let t0 = store.put_execution_type("t0").execute().await?;
-    let e0 = store.post_execution(t0).execute().await?;
-    let _e1 = store.post_execution(t0).execute().await?;
+async fn property_presence_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    let t1 = store.put_context_type("t1").execute().await?;
-    let _c0 = store.post_context(t1, "foo").execute().await?;
-    let c1 = store.post_context(t1, "bar").execute().await?;
+    // Artifacts.
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let a0 = store
+        .post_artifact(type_id)
+        .property("p0", 1)
+        .execute()
+        .await?;
+    let a1 = store
+        .post_artifact(type_id)
+        .custom_property("c0", "foo")
+        .execute()
+        .await?;
+    let a2 = store
+        .post_artifact(type_id)
+        .property("p0", 2)
+        .custom_property("c0", "bar")
+        .execute()
+        .await?;
 
-    for _ in 0..2 {
-        store.put_association(c1, e0).execute().await?; // The duplicate PUTs are ignored.
-        let contexts = store.get_contexts().execution(e0).execute().await?;
-        assert_eq!(contexts.len(), 1);
-        assert_eq!(contexts[0].id, c1);
+    let ids = |artifacts: Vec<Artifact>| artifacts.into_iter().map(|x| x.id).collect::<Vec<_>>();
+    let artifacts = store.get_artifacts().has_property("p0").execute().await?;
+    assert_eq!(ids(artifacts), vec![a0, a2]);
+    let artifacts = store.get_artifacts().has_property("c0").execute().await?;
+    assert_eq!(ids(artifacts), vec![a1, a2]);
+    let artifacts = store
+        .get_artifacts()
+        .missing_property("p0")
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), vec![a1]);
+    let request = || {
+        store
+            .get_artifacts()
+            .has_property("p0")
+            .missing_property("c0")
+    };
+    assert_eq!(ids(request().execute().await?), vec![a0]);
+    assert_eq!(request().count().await?, 1);
+
+    // Executions.
+    let type_id = store.put_execution_type("t1").execute().await?;
+    let e0 = store
+        .post_execution(type_id)
+        .custom_property("c0", 1)
+        .execute()
+        .await?;
+    let e1 = store.post_execution(type_id).execute().await?;
+    let executions = store.get_executions().has_property("c0").execute().await?;
+    assert_eq!(
+        executions.iter().map(|x| x.id).collect::<Vec<_>>(),
+        vec![e0]
+    );
+    let executions = store
+        .get_executions()
+        .missing_property("c0")
+        .execute()
+        .await?;
+    assert_eq!(
+        executions.iter().map(|x| x.id).collect::<Vec<_>>(),
+        vec![e1]
+    );
 
-        let executions = store.get_executions().context(c1).execute().await?;
-        assert_eq!(executions.len(), 1);
-        assert_eq!(executions[0].id, e0);
-    }
+    // Contexts.
+    let type_id = store.put_context_type("t2").execute().await?;
+    let c0 = store
+        .post_context(type_id, "c0")
+        .custom_property("c0", 1.5)
+        .execute()
+        .await?;
+    let c1 = store.post_context(type_id, "c1").execute().await?;
+    let contexts = store.get_contexts().has_property("c0").execute().await?;
+    assert_eq!(contexts.iter().map(|x| x.id).collect::<Vec<_>>(), vec![c0]);
+    let contexts = store
+        .get_contexts()
+        .missing_property("c0")
+        .execute()
+        .await?;
+    assert_eq!(contexts.iter().map(|x| x.id).collect::<Vec<_>>(), vec![c1]);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn put_event_works() -> anyhow::Result<()> {
-    let file = NamedTempFile::new().unwrap();
-    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
-        .await
-        .unwrap();
-
-    let t0 = store.put_execution_type("t0").execute().await?;
-    let e0 = store.post_execution(t0).execute().await?;
-    let e1 = store.post_execution(t0).execute().await?;
+async fn delete_artifacts_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
+    let t0 = store.put_artifact_type("t0").execute().await?;
     let t1 = store.put_artifact_type("t1").execute().await?;
-    let a0 = store.post_artifact(t1).execute().await?;
-    let a1 = store.post_artifact(t1).execute().await?;
-
-    store
-        .put_event(e0, a0)
-        .ty(EventType::Input)
+    let a0 = store
+        .post_artifact(t0)
+        .custom_property("foo", 1)
         .execute()
         .await?;
+    let _a1 = store.post_artifact(t0).execute().await?;
+    let _a2 = store.post_artifact(t1).execute().await?;
+
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
     store
-        .put_event(e1, a1)
-        .ty(EventType::Output)
-        .step(EventStep::Index(30))
+        .put_event(e0, a0)
+        .step(EventStep::Index(0))
         .execute()
         .await?;
+    let context_type_id = store.put_context_type("c").execute().await?;
+    let c0 = store.post_context(context_type_id, "c0").execute().await?;
+    store.put_attribution(c0, a0).execute().await?;
 
-    let events = store.get_events().execute().await?;
-    assert_eq!(events.len(), 2);
+    std::thread::sleep(Duration::from_millis(10));
+    let threshold = UNIX_EPOCH.elapsed()?;
+    std::thread::sleep(Duration::from_millis(10));
+    let a3 = store.post_artifact(t0).execute().await?;
 
-    assert_eq!(events[0].artifact_id, a0);
-    assert_eq!(events[0].execution_id, e0);
-    assert_eq!(events[0].ty, EventType::Input);
-    assert_eq!(events[0].path, vec![]);
+    let deleted = store
+        .get_artifacts()
+        .ty("t0")
+        .create_time(..threshold)
+        .delete()
+        .await?;
+    assert_eq!(deleted, 2);
 
-    assert_eq!(events[1].artifact_id, a1);
-    assert_eq!(events[1].execution_id, e1);
-    assert_eq!(events[1].ty, EventType::Output);
-    assert_eq!(events[1].path, vec![EventStep::Index(30)]);
+    let artifacts = store.get_artifacts().ty("t0").execute().await?;
+    assert_eq!(artifacts.iter().map(|a| a.id).collect::<Vec<_>>(), vec![a3]);
+    assert_eq!(store.get_artifacts().ty("t1").count().await?, 1);
+    assert_eq!(store.get_events().count().await?, 0);
+    assert_eq!(store.get_artifacts().context(c0).count().await?, 0);
+
+    // Nothing to delete.
+    let deleted = store
+        .get_artifacts()
+        .ty("t0")
+        .create_time(..threshold)
+        .delete()
+        .await?;
+    assert_eq!(deleted, 0);
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn get_events_works() -> anyhow::Result<()> {
-    let file = existing_db();
+async fn get_items_by_state_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
     let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
 
-    let events = store.get_events().execute().await?;
-    assert_eq!(events, vec![event0(), event1()]);
-
-    let events = store
-        .get_events()
-        .artifact(ArtifactId::new(1))
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let a0 = store
+        .post_artifact(artifact_type_id)
+        .state(ArtifactState::Live)
         .execute()
         .await?;
-    assert_eq!(events, vec![event0()]);
-
-    let events = store
-        .get_events()
-        .artifact(ArtifactId::new(2))
+    let _a1 = store
+        .post_artifact(artifact_type_id)
+        .state(ArtifactState::Deleted)
         .execute()
         .await?;
-    assert_eq!(events, vec![event1()]);
+    let a2 = store
+        .post_artifact(artifact_type_id)
+        .state(ArtifactState::Live)
+        .execute()
+        .await?;
+    let _a3 = store.post_artifact(artifact_type_id).execute().await?;
 
-    let events = store
-        .get_events()
-        .execution(ExecutionId::new(1))
+    let artifacts = store
+        .get_artifacts()
+        .state(ArtifactState::Live)
         .execute()
         .await?;
-    assert_eq!(events, vec![event0(), event1()]);
+    assert_eq!(
+        artifacts.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![a0, a2]
+    );
+    let count = store
+        .get_artifacts()
+        .state(ArtifactState::Pending)
+        .count()
+        .await?;
+    assert_eq!(count, 0);
 
-    let events = store
-        .get_events()
-        .execution(ExecutionId::new(2))
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let _e0 = store
+        .post_execution(execution_type_id)
+        .state(ExecutionState::Complete)
+        .execute()
+        .await?;
+    let e1 = store
+        .post_execution(execution_type_id)
+        .state(ExecutionState::Running)
         .execute()
         .await?;
-    assert_eq!(events, vec![]);
 
-    let events = store
-        .get_events()
-        .artifact(ArtifactId::new(1))
-        .execution(ExecutionId::new(1))
+    let executions = store
+        .get_executions()
+        .state(ExecutionState::Running)
         .execute()
         .await?;
-    assert_eq!(events, vec![event0()]);
+    assert_eq!(
+        executions.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![e1]
+    );
 
     Ok(())
 }
 
-fn sqlite_uri(path: impl AsRef<std::path::Path>) -> String {
-    format!(
-        "sqlite://{}",
-        path.as_ref()
-            .to_str()
-            .ok_or_else(|| format!("invalid path: {:?}", path.as_ref()))
-            .unwrap()
-    )
+#[tokio::test(flavor = "multi_thread")]
+async fn artifact_time_bounds_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let t0 = artifact0().create_time_since_epoch;
+    let t1 = artifact1().create_time_since_epoch;
+    assert_eq!(store.artifact_time_bounds(None).await?, Some((t0, t1)));
+    assert_eq!(
+        store.artifact_time_bounds(Some("DataSet")).await?,
+        Some((t0, t0))
+    );
+    assert_eq!(store.artifact_time_bounds(Some("foo")).await?, None);
+
+    let file = NamedTempFile::new()?;
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert_eq!(store.artifact_time_bounds(None).await?, None);
+
+    Ok(())
 }
 
-fn existing_db() -> NamedTempFile {
-    let mut file = NamedTempFile::new().expect("cannot create a temporary file");
-    std::io::copy(
-        &mut std::fs::File::open("tests/test.db").expect("cannot open 'tests/test.db'"),
-        &mut file,
-    )
-    .expect("cannot copy the existing database file");
-    file
+#[tokio::test(flavor = "multi_thread")]
+async fn get_single_item_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    assert_eq!(store.get_artifact(artifact1().id).await?, Some(artifact1()));
+    assert_eq!(store.get_artifact(ArtifactId::new(100)).await?, None);
+
+    assert_eq!(
+        store.get_execution(execution0().id).await?,
+        Some(execution0())
+    );
+    assert_eq!(store.get_execution(ExecutionId::new(100)).await?, None);
+
+    assert_eq!(store.get_context(context0().id).await?, Some(context0()));
+    assert_eq!(store.get_context(ContextId::new(100)).await?, None);
+
+    Ok(())
 }
 
-fn artifact0() -> Artifact {
-    Artifact {
-        id: ArtifactId::new(1),
-        type_id: TypeId::new(1),
-        name: None,
-        uri: Some("path/to/data".to_owned()),
-        properties: vec![
-            ("day".to_owned(), PropertyValue::Int(1)),
-            (
-                "split".to_owned(),
-                PropertyValue::String("train".to_owned()),
-            ),
-        ]
-        .into_iter()
-        .collect(),
-        custom_properties: BTreeMap::new(),
-        state: ArtifactState::Unknown,
-        create_time_since_epoch: Duration::from_millis(1648979124872),
-        last_update_time_since_epoch: Duration::from_millis(1648979124872),
+#[tokio::test(flavor = "multi_thread")]
+async fn get_artifacts_time_bounds_work() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    async fn artifact_ids(
+        store: &MetadataStore,
+        range: (Bound<Duration>, Bound<Duration>),
+        update_time: bool,
+    ) -> anyhow::Result<Vec<i32>> {
+        let request = store.get_artifacts();
+        let request = if update_time {
+            request.update_time(range)
+        } else {
+            request.create_time(range)
+        };
+        Ok(request
+            .execute()
+            .await?
+            .iter()
+            .map(|a| a.id.get())
+            .collect())
     }
-}
 
-fn artifact1() -> Artifact {
-    Artifact {
-        id: ArtifactId::new(2),
-        type_id: TypeId::new(11),
-        name: None,
-        uri: Some("path/to/model/file".to_owned()),
-        properties: vec![
-            (
-                "name".to_owned(),
-                PropertyValue::String("MNIST-v1".to_owned()),
-            ),
-            ("version".to_owned(), PropertyValue::Int(1)),
-        ]
-        .into_iter()
-        .collect(),
-        custom_properties: BTreeMap::new(),
-        state: ArtifactState::Unknown,
-        create_time_since_epoch: Duration::from_millis(1648979124885),
-        last_update_time_since_epoch: Duration::from_millis(1648979124885),
+    let t0 = artifact0().create_time_since_epoch;
+    let t1 = artifact1().create_time_since_epoch;
+    assert_eq!(t0, artifact0().last_update_time_since_epoch);
+    assert_eq!(t1, artifact1().last_update_time_since_epoch);
+
+    for update_time in [false, true] {
+        // Start bound.
+        let ids = artifact_ids(&store, (Bound::Included(t1), Bound::Unbounded), update_time);
+        assert_eq!(ids.await?, vec![2]);
+        let ids = artifact_ids(&store, (Bound::Excluded(t1), Bound::Unbounded), update_time);
+        assert!(ids.await?.is_empty());
+
+        // End bound.
+        let ids = artifact_ids(&store, (Bound::Unbounded, Bound::Included(t0)), update_time);
+        assert_eq!(ids.await?, vec![1]);
+        let ids = artifact_ids(&store, (Bound::Unbounded, Bound::Excluded(t0)), update_time);
+        assert!(ids.await?.is_empty());
+
+        // Both bounds.
+        let ids = artifact_ids(
+            &store,
+            (Bound::Included(t0), Bound::Included(t1)),
+            update_time,
+        );
+        assert_eq!(ids.await?, vec![1, 2]);
+        let ids = artifact_ids(
+            &store,
+            (Bound::Included(t0), Bound::Excluded(t1)),
+            update_time,
+        );
+        assert_eq!(ids.await?, vec![1]);
+        let ids = artifact_ids(
+            &store,
+            (Bound::Excluded(t0), Bound::Included(t1)),
+            update_time,
+        );
+        assert_eq!(ids.await?, vec![2]);
+        let ids = artifact_ids(
+            &store,
+            (Bound::Excluded(t0), Bound::Excluded(t1)),
+            update_time,
+        );
+        assert!(ids.await?.is_empty());
     }
+
+    Ok(())
 }
 
-fn execution0() -> Execution {
-    Execution {
-        id: ExecutionId::new(1),
-        type_id: TypeId::new(12),
-        name: None,
-        last_known_state: ExecutionState::Unknown,
-        properties: vec![(
-            "state".to_owned(),
-            PropertyValue::String("COMPLETED".to_owned()),
-        )]
-        .into_iter()
-        .collect(),
-        custom_properties: BTreeMap::new(),
-        create_time_since_epoch: Duration::from_millis(1648979124878),
-        last_update_time_since_epoch: Duration::from_millis(1648979124891),
-    }
+#[tokio::test(flavor = "multi_thread")]
+async fn get_artifacts_dry_run_sql_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let request = store
+        .get_artifacts()
+        .ty("t0")
+        .uri("/foo")
+        .order_by(ArtifactOrderByField::CreateTime, false)
+        .limit(10);
+    let dry_run = request.dry_run_sql();
+    assert!(dry_run.sql.contains("JOIN Type as T ON A.type_id = T.id"));
+    assert!(dry_run.sql.contains("WHERE T.name = ? AND A.uri = ?"));
+    assert!(dry_run
+        .sql
+        .contains("ORDER BY A.create_time_since_epoch DESC, A.id DESC LIMIT 10"));
+    assert_eq!(dry_run.param_count, 2);
+
+    // The request is still executable.
+    assert!(request.execute().await?.is_empty());
+
+    let dry_run = store
+        .get_events()
+        .pairs([(ExecutionId::new(1), ArtifactId::new(2))].into_iter())
+        .artifact(ArtifactId::new(3))
+        .dry_run_sql();
+    assert_eq!(dry_run.param_count, 3);
+    assert_eq!(dry_run.sql.matches('?').count(), 3);
+
+    Ok(())
 }
 
-fn context0() -> Context {
-    Context {
-        id: ContextId::new(1),
-        type_id: TypeId::new(13),
-        name: "exp1".to_owned(),
-        properties: vec![(
-            "note".to_owned(),
-            PropertyValue::String("My first experiment.".to_owned()),
-        )]
-        .into_iter()
-        .collect(),
-        custom_properties: BTreeMap::new(),
-        create_time_since_epoch: Duration::from_millis(1648979124896),
-        last_update_time_since_epoch: Duration::from_millis(1648979124896),
-    }
+#[tokio::test(flavor = "multi_thread")]
+async fn post_artifact_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    assert!(store.get_artifacts().execute().await?.is_empty());
+
+    let type_id = store
+        .put_artifact_type("DataSet")
+        .property("day", PropertyType::Int)
+        .property("split", PropertyType::String)
+        .execute()
+        .await?;
+    // Simple artifact.
+    let artifact_id = store.post_artifact(type_id).execute().await?;
+
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts.len(), 1);
+    assert_eq!(artifacts[0].id, artifact_id);
+
+    // Complex artifact.
+    let mut expected = artifact0();
+    expected.id = store
+        .post_artifact(type_id)
+        .uri(expected.uri.as_ref().unwrap())
+        .properties(expected.properties.clone())
+        .execute()
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts.len(), 2);
+
+    expected.create_time_since_epoch = artifacts[1].create_time_since_epoch;
+    expected.last_update_time_since_epoch = artifacts[1].last_update_time_since_epoch;
+    assert_eq!(artifacts[1], expected);
+
+    // Name confilict.
+    store.post_artifact(type_id).name("foo").execute().await?;
+    assert!(matches!(
+        store
+            .post_artifact(type_id)
+            .name("foo")
+            .execute()
+            .await
+            .err(),
+        Some(PostError::NameAlreadyExists { .. })
+    ));
+
+    Ok(())
 }
 
-fn event0() -> Event {
-    Event {
-        artifact_id: ArtifactId::new(1),
-        execution_id: ExecutionId::new(1),
-        path: Vec::new(),
-        ty: EventType::DeclaredInput,
-        create_time_since_epoch: Duration::from_millis(1648979124882),
-    }
+#[tokio::test(flavor = "multi_thread")]
+async fn get_or_create_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("t0").execute().await?;
+    let context_type_id = store.put_context_type("t1").execute().await?;
+
+    let a0 = store.get_or_create_artifact(artifact_type_id, "a0").await?;
+    assert_eq!(
+        store.get_or_create_artifact(artifact_type_id, "a0").await?,
+        a0
+    );
+    let a1 = store.get_or_create_artifact(artifact_type_id, "a1").await?;
+    assert_ne!(a0, a1);
+    assert_eq!(store.get_artifacts().execute().await?.len(), 2);
+
+    let c0 = store.get_or_create_context(context_type_id, "c0").await?;
+    assert_eq!(
+        store.get_or_create_context(context_type_id, "c0").await?,
+        c0
+    );
+    assert_eq!(store.get_contexts().execute().await?.len(), 1);
+
+    // Concurrent calls return the same item.
+    // (Retries are enabled because SQLite can't upgrade concurrent read transactions to write ones.)
+    let config =
+        || MetadataStoreConfig::new(&sqlite_uri(file.path())).retry(5, Duration::from_millis(10));
+    let mut store0 = MetadataStore::connect_with(config()).await?;
+    let mut store1 = MetadataStore::connect_with(config()).await?;
+    let (c1, c2) = futures::try_join!(
+        store0.get_or_create_context(context_type_id, "c1"),
+        store1.get_or_create_context(context_type_id, "c1")
+    )?;
+    assert_eq!(c1, c2);
+    assert_eq!(store.get_contexts().execute().await?.len(), 2);
+
+    assert!(matches!(
+        store.get_or_create_context(context_type_id, "").await,
+        Err(PostError::InvalidName { .. })
+    ));
+    assert!(matches!(
+        store.get_or_create_artifact(context_type_id, "a2").await,
+        Err(PostError::TypeKindMismatch { .. })
+    ));
+
+    Ok(())
 }
 
-fn event1() -> Event {
-    Event {
-        artifact_id: ArtifactId::new(2),
-        execution_id: ExecutionId::new(1),
-        path: Vec::new(),
-        ty: EventType::DeclaredOutput,
-        create_time_since_epoch: Duration::from_millis(1648979124888),
-    }
+#[tokio::test(flavor = "multi_thread")]
+async fn post_item_type_kind_mismatch_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let context_type_id = store.put_context_type("t0").execute().await?;
+    assert!(matches!(
+        store.post_artifact(context_type_id).execute().await,
+        Err(PostError::TypeKindMismatch {
+            type_id,
+            expected: TypeKind::Artifact,
+            actual: TypeKind::Context,
+        }) if type_id == context_type_id
+    ));
+    assert!(matches!(
+        store.post_execution(context_type_id).execute().await,
+        Err(PostError::TypeKindMismatch {
+            expected: TypeKind::Execution,
+            actual: TypeKind::Context,
+            ..
+        })
+    ));
+
+    let unregistered_id = TypeId::new(100);
+    assert!(matches!(
+        store.post_artifact(unregistered_id).execute().await,
+        Err(PostError::TypeNotFound {
+            type_kind: TypeKind::Artifact,
+            type_id,
+        }) if type_id == unregistered_id
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn post_items_with_times_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let create_time = Duration::from_millis(1000);
+    let update_time = Duration::from_millis(2000);
+
+    let type_id = store.put_artifact_type("a").execute().await?;
+    store
+        .post_artifact(type_id)
+        .create_time_since_epoch(create_time)
+        .last_update_time_since_epoch(update_time)
+        .execute()
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts[0].create_time_since_epoch, create_time);
+    assert_eq!(artifacts[0].last_update_time_since_epoch, update_time);
+
+    let type_id = store.put_execution_type("e").execute().await?;
+    store
+        .post_execution(type_id)
+        .create_time_since_epoch(create_time)
+        .execute()
+        .await?;
+    let executions = store.get_executions().execute().await?;
+    assert_eq!(executions[0].create_time_since_epoch, create_time);
+    assert!(executions[0].last_update_time_since_epoch > update_time);
+
+    let type_id = store.put_context_type("c").execute().await?;
+    store
+        .post_context(type_id, "foo")
+        .create_time_since_epoch(create_time)
+        .last_update_time_since_epoch(update_time)
+        .execute()
+        .await?;
+    let contexts = store
+        .get_contexts()
+        .create_time(..=create_time)
+        .execute()
+        .await?;
+    assert_eq!(contexts[0].create_time_since_epoch, create_time);
+    assert_eq!(contexts[0].last_update_time_since_epoch, update_time);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bytes_property_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Bytes)
+        .execute()
+        .await?;
+    let types = store.get_artifact_types().execute().await?;
+    assert_eq!(types[0].properties["p0"], PropertyType::Bytes);
+
+    let artifact_id = store
+        .post_artifact(type_id)
+        .property("p0", vec![0, 1, 2])
+        .custom_property("c0", vec![255])
+        .execute()
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(
+        artifacts[0].properties["p0"].as_bytes(),
+        Some(&[0, 1, 2][..])
+    );
+    assert_eq!(
+        artifacts[0].custom_properties["c0"],
+        PropertyValue::Bytes(vec![255])
+    );
+
+    // Replaces a bytes value with a value of another type.
+    store
+        .put_artifact(artifact_id)
+        .custom_property("c0", 10)
+        .execute()
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts[0].custom_properties["c0"], PropertyValue::Int(10));
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn non_finite_double_property_json_works() -> anyhow::Result<()> {
+    let values = [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1.5];
+    let mut artifact = artifact0();
+    artifact.custom_properties = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (format!("p{}", i), PropertyValue::Double(*v)))
+        .collect();
+
+    let json = serde_json::to_value(&artifact)?;
+    let properties = &json["custom_properties"];
+    assert_eq!(properties["p0"]["value"], "NaN");
+    assert_eq!(properties["p1"]["value"], "Infinity");
+    assert_eq!(properties["p2"]["value"], "-Infinity");
+    assert_eq!(properties["p3"]["value"], 1.5);
+
+    let decoded: Artifact = serde_json::from_value(json)?;
+    let doubles = decoded
+        .custom_properties
+        .values()
+        .map(|v| v.as_double().expect("not a double"))
+        .collect::<Vec<_>>();
+    assert!(doubles[0].is_nan());
+    assert_eq!(doubles[1..], values[1..]);
+
+    assert!(serde_json::from_str::<PropertyValue>(r#"{"type":"Double","value":"foo"}"#).is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bool_property_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Bool)
+        .property("p1", PropertyType::Int)
+        .execute()
+        .await?;
+    store
+        .post_artifact(type_id)
+        .property("p0", true)
+        .property("p1", 1)
+        .custom_property("c0", false)
+        .execute()
+        .await?;
+    assert!(matches!(
+        store
+            .post_artifact(type_id)
+            .property("p1", true)
+            .execute()
+            .await,
+        Err(PostError::PropertyTypeMismatch {
+            expected: PropertyType::Int,
+            actual: PropertyType::Bool,
+            ..
+        })
+    ));
+
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts.len(), 1);
+    assert_eq!(artifacts[0].properties["p0"], PropertyValue::Bool(true));
+    assert_eq!(artifacts[0].properties["p1"], PropertyValue::Int(1));
+
+    // Custom properties don't have declared types.
+    assert_eq!(artifacts[0].custom_properties["c0"], PropertyValue::Int(0));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_artifact_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert_eq!(store.get_artifacts().execute().await?.len(), 2);
+
+    let mut artifact = artifact0();
+    artifact.type_id = TypeId::new(10);
+    artifact.name = Some("foo".to_string());
+    artifact.state = ArtifactState::Live;
+    artifact
+        .properties
+        .insert("day".to_owned(), PropertyValue::Int(234));
+    artifact
+        .custom_properties
+        .insert("bar".to_string(), PropertyValue::Int(10));
+    store
+        .put_artifact(artifact.id)
+        .name(artifact.name.as_ref().unwrap().as_str())
+        .state(artifact.state)
+        .properties(artifact.properties.clone())
+        .custom_properties(artifact.custom_properties.clone())
+        .execute()
+        .await?;
+
+    let artifacts = store.get_artifacts().id(artifact.id).execute().await?;
+    assert_eq!(artifacts.len(), 1);
+
+    artifact.last_update_time_since_epoch = artifacts[0].last_update_time_since_epoch;
+    assert_eq!(artifacts[0], artifact);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_executions_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // All.
+    let executions = store.get_executions().execute().await?;
+    assert_eq!(executions, vec![execution0()]);
+
+    // By type name.
+    let executions = store.get_executions().ty("Trainer").execute().await?;
+    assert_eq!(executions, vec![execution0()]);
+
+    let executions = store.get_executions().ty("foo").execute().await?;
+    assert_eq!(executions, vec![]);
+
+    // By ID.
+    let unregistered_id = ExecutionId::new(100);
+    let executions = store
+        .get_executions()
+        .ids([ExecutionId::new(1), unregistered_id].iter().copied())
+        .execute()
+        .await?;
+    assert_eq!(executions, vec![execution0()]);
+
+    // By Context.
+    let executions = store
+        .get_executions()
+        .context(ContextId::new(1))
+        .execute()
+        .await?;
+    assert_eq!(executions, vec![execution0()]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_executions_time_bounds_work() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store.put_execution_type("t0").execute().await?;
+    let mut ids = Vec::new();
+    for t in [100, 200, 300] {
+        ids.push(
+            store
+                .post_execution(type_id)
+                .create_time_since_epoch(Duration::from_millis(t))
+                .execute()
+                .await?,
+        );
+    }
+
+    let t0 = Duration::from_millis(100);
+    let t2 = Duration::from_millis(300);
+    for (range, expected) in [
+        ((Bound::Included(t0), Bound::Included(t2)), vec![0, 1, 2]),
+        ((Bound::Included(t0), Bound::Excluded(t2)), vec![0, 1]),
+        ((Bound::Excluded(t0), Bound::Included(t2)), vec![1, 2]),
+        ((Bound::Excluded(t0), Bound::Excluded(t2)), vec![1]),
+        ((Bound::Excluded(t2), Bound::Unbounded), vec![]),
+    ] {
+        let executions = store
+            .get_executions()
+            .create_time(range)
+            .order_by(ExecutionOrderByField::CreateTime, true)
+            .execute()
+            .await?;
+        assert_eq!(
+            executions.iter().map(|x| x.id).collect::<Vec<_>>(),
+            expected.into_iter().map(|i| ids[i]).collect::<Vec<_>>(),
+            "{:?}",
+            range
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_executions_by_artifacts_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("Dataset").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+    let a2 = store.post_artifact(artifact_type_id).execute().await?;
+
+    let execution_type_id = store.put_execution_type("Trainer").execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    let _e2 = store.post_execution(execution_type_id).execute().await?;
+
+    store
+        .put_event(e0, a0)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    store
+        .put_event(e0, a1)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    store
+        .put_event(e1, a1)
+        .ty(EventType::Output)
+        .execute()
+        .await?;
+
+    let ids = |executions: Vec<Execution>| executions.into_iter().map(|x| x.id).collect::<Vec<_>>();
+
+    let executions = store.get_executions().artifact(a1).execute().await?;
+    assert_eq!(ids(executions), vec![e0, e1]);
+    let executions = store.get_executions().artifact(a2).execute().await?;
+    assert_eq!(ids(executions), vec![]);
+
+    // `e0` has events with both artifacts but appears only once.
+    let request = || store.get_executions().artifacts([a0, a1].into_iter());
+    assert_eq!(ids(request().execute().await?), vec![e0, e1]);
+    assert_eq!(request().count().await?, 2);
+
+    // By event type.
+    let executions = request().event_type(EventType::Input).execute().await?;
+    assert_eq!(ids(executions), vec![e0]);
+    let executions = request().event_type(EventType::Output).execute().await?;
+    assert_eq!(ids(executions), vec![e1]);
+    let executions = store
+        .get_executions()
+        .event_type(EventType::Output)
+        .execute()
+        .await?;
+    assert_eq!(executions.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replace_custom_properties_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let artifact_id = store
+        .post_artifact(type_id)
+        .property("p0", 0)
+        .custom_property("a", 1)
+        .custom_property("b", 2)
+        .execute()
+        .await?;
+
+    // Additive update.
+    store
+        .put_artifact(artifact_id)
+        .custom_property("c", 3)
+        .execute()
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(
+        artifacts[0].custom_properties.keys().collect::<Vec<_>>(),
+        ["a", "b", "c"]
+    );
+
+    // Replacement.
+    store
+        .put_artifact(artifact_id)
+        .replace_custom_properties(
+            vec![("b".to_owned(), PropertyValue::Int(20))]
+                .into_iter()
+                .collect(),
+        )
+        .execute()
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(
+        artifacts[0].custom_properties,
+        vec![("b".to_owned(), PropertyValue::Int(20))]
+            .into_iter()
+            .collect()
+    );
+    assert_eq!(artifacts[0].properties["p0"], PropertyValue::Int(0));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_execution_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert_eq!(store.get_executions().execute().await?.len(), 1);
+
+    let mut execution = execution0();
+    execution.name = Some("foo".to_string());
+    execution.last_known_state = ExecutionState::Running;
+    execution
+        .custom_properties
+        .insert("bar".to_string(), PropertyValue::Int(10));
+    store
+        .put_execution(execution.id)
+        .name(execution.name.as_ref().unwrap())
+        .state(execution.last_known_state)
+        .custom_properties(execution.custom_properties.clone())
+        .execute()
+        .await?;
+
+    let executions = store.get_executions().execute().await?;
+    assert_eq!(executions.len(), 1);
+
+    execution.last_update_time_since_epoch = executions[0].last_update_time_since_epoch;
+    assert_eq!(executions[0], execution);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn post_execution_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    assert!(store.get_executions().execute().await?.is_empty());
+
+    let type_id = store
+        .put_execution_type("DataSet")
+        .property("day", PropertyType::Int)
+        .property("split", PropertyType::String)
+        .execute()
+        .await?;
+
+    // Simple execution.
+    let execution_id = store.post_execution(type_id).execute().await?;
+
+    let executions = store.get_executions().execute().await?;
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].id, execution_id);
+
+    // Name confilict.
+    store.post_execution(type_id).name("foo").execute().await?;
+    assert!(matches!(
+        store
+            .post_execution(type_id)
+            .name("foo")
+            .execute()
+            .await
+            .err(),
+        Some(PostError::NameAlreadyExists { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_execution_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    store
+        .put_execution_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+
+    assert!(matches!(
+        store
+            .put_execution_type("t0")
+            .property("p0", PropertyType::Double)
+            .execute()
+            .await,
+        Err(PutError::TypeAlreadyExists { .. })
+    ));
+
+    assert!(matches!(
+        store
+            .put_execution_type("t0")
+            .property("p0", PropertyType::Int)
+            .property("p1", PropertyType::String)
+            .execute()
+            .await,
+        Err(PutError::TypeAlreadyExists { .. })
+    ));
+    store
+        .put_execution_type("t0")
+        .can_add_fields()
+        .property("p0", PropertyType::Int)
+        .property("p1", PropertyType::String)
+        .execute()
+        .await?;
+
+    assert!(matches!(
+        store.put_execution_type("t0").execute().await,
+        Err(PutError::TypeAlreadyExists { .. })
+    ));
+    store
+        .put_execution_type("t0")
+        .can_omit_fields()
+        .execute()
+        .await?;
+    store.put_execution_type("t1").execute().await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test(flavor = "multi_thread")]
+async fn execution_type_input_output_types_work() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let input_type = serde_json::json!({"any": {}});
+    let output_type = serde_json::json!({"simple": {"name": "Model"}});
+    let t0_id = store
+        .put_execution_type("t0")
+        .input_type(input_type.clone())
+        .output_type(output_type.clone())
+        .execute()
+        .await?;
+    let t1_id = store.put_execution_type("t1").execute().await?;
+
+    let types = store.get_execution_types().execute().await?;
+    assert_eq!(types[0].id, t0_id);
+    assert_eq!(types[0].input_type.as_ref(), Some(&input_type));
+    assert_eq!(types[0].output_type.as_ref(), Some(&output_type));
+    assert_eq!(types[1].id, t1_id);
+    assert_eq!(types[1].input_type, None);
+    assert_eq!(types[1].output_type, None);
+
+    // Broken JSON results in a decode error.
+    sqlx::query("UPDATE Type SET input_type='{' WHERE id=?")
+        .bind(t1_id.get())
+        .execute(&store.pool)
+        .await?;
+    assert!(matches!(
+        store.get_execution_types().execute().await,
+        Err(GetError::Db(sqlx::Error::Decode(_)))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_execution_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let t0_id = store
+        .put_execution_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let t1_id = store.put_execution_type("t1").execute().await?;
+    assert_ne!(t0_id, t1_id);
+
+    assert_eq!(
+        store.get_execution_types().name("t0").execute().await?[0],
+        ExecutionType {
+            id: t0_id,
+            name: "t0".to_owned(),
+            version: None,
+            description: None,
+            #[cfg(feature = "serde")]
+            input_type: None,
+            #[cfg(feature = "serde")]
+            output_type: None,
+            properties: vec![("p0".to_owned(), PropertyType::Int)]
+                .into_iter()
+                .collect()
+        }
+    );
+    assert_eq!(
+        store.get_execution_types().name("t1").execute().await?[0],
+        ExecutionType {
+            id: t1_id,
+            name: "t1".to_owned(),
+            version: None,
+            description: None,
+            #[cfg(feature = "serde")]
+            input_type: None,
+            #[cfg(feature = "serde")]
+            output_type: None,
+            properties: BTreeMap::new(),
+        }
+    );
+    assert!(store
+        .get_execution_types()
+        .name("t2")
+        .execute()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_execution_types_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let types = store.get_execution_types().execute().await?;
+    assert_eq!(types.len(), 6);
+    assert_eq!(types[0].name, "mlmd.Train");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_contexts_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // All.
+    let contexts = store.get_contexts().execute().await?;
+    assert_eq!(contexts, vec![context0()]);
+
+    // By type name.
+    let contexts = store.get_contexts().ty("Experiment").execute().await?;
+    assert_eq!(contexts, vec![context0()]);
+
+    let contexts = store.get_contexts().ty("foo").execute().await?;
+    assert_eq!(contexts, vec![]);
+
+    let contexts = store
+        .get_contexts()
+        .type_and_name("Experiment", "exp1")
+        .execute()
+        .await?;
+    assert_eq!(contexts, vec![context0()]);
+
+    // By ID.
+    let unregistered_id = ContextId::new(100);
+    let contexts = store
+        .get_contexts()
+        .ids([ContextId::new(1), unregistered_id].iter().copied())
+        .execute()
+        .await?;
+    assert_eq!(contexts, vec![context0()]);
+
+    // By artifact.
+    let contexts = store
+        .get_contexts()
+        .artifact(ArtifactId::new(2))
+        .execute()
+        .await?;
+    assert_eq!(contexts, vec![context0()]);
+
+    // By execution.
+    let contexts = store
+        .get_contexts()
+        .execution(ExecutionId::new(1))
+        .execute()
+        .await?;
+    assert_eq!(contexts, vec![context0()]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_items_by_types_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_ids = |artifacts: Vec<Artifact>| {
+        artifacts
+            .into_iter()
+            .map(|a| a.id.get())
+            .collect::<Vec<_>>()
+    };
+
+    // Artifacts.
+    let artifacts = store
+        .get_artifacts()
+        .types(["DataSet", "SavedModel"].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(artifact_ids(artifacts), [1, 2]);
+    assert_eq!(
+        store
+            .get_artifacts()
+            .types(["DataSet", "SavedModel"].into_iter())
+            .count()
+            .await?,
+        2
+    );
+
+    let artifacts = store
+        .get_artifacts()
+        .types(["SavedModel", "foo"].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(artifact_ids(artifacts), [2]);
+
+    // The last call wins.
+    let artifacts = store
+        .get_artifacts()
+        .types(["DataSet", "SavedModel"].into_iter())
+        .ty("DataSet")
+        .execute()
+        .await?;
+    assert_eq!(artifact_ids(artifacts), [1]);
+
+    let artifacts = store
+        .get_artifacts()
+        .ty("foo")
+        .types(["DataSet", "SavedModel"].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(artifact_ids(artifacts), [1, 2]);
+
+    // Executions.
+    let executions = store
+        .get_executions()
+        .types(["mlmd.Train", "Trainer"].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(executions, vec![execution0()]);
+
+    let executions = store
+        .get_executions()
+        .types(["mlmd.Train", "mlmd.Deploy"].into_iter())
+        .execute()
+        .await?;
+    assert!(executions.is_empty());
+
+    // Contexts.
+    let contexts = store
+        .get_contexts()
+        .types(["Experiment", "foo"].into_iter())
+        .ty("foo")
+        .execute()
+        .await?;
+    assert!(contexts.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_context_ids_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    assert_eq!(
+        store
+            .get_context_ids_by_artifact(ArtifactId::new(2))
+            .await?,
+        [context0().id]
+    );
+    assert!(store
+        .get_context_ids_by_artifact(ArtifactId::new(1))
+        .await?
+        .is_empty());
+    assert_eq!(
+        store.get_context_ids_by_execution(execution0().id).await?,
+        [context0().id]
+    );
+
+    let context_id = store
+        .post_context(context0().type_id, "exp2")
+        .execute()
+        .await?;
+    store
+        .put_attribution(context_id, ArtifactId::new(2))
+        .execute()
+        .await?;
+    assert_eq!(
+        store
+            .get_context_ids_by_artifact(ArtifactId::new(2))
+            .await?,
+        [context0().id, context_id]
+    );
+    assert!(store
+        .get_context_ids_by_execution(ExecutionId::new(100))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_contexts_by_types_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let experiment_type_id = store.put_context_type("Experiment").execute().await?;
+    let run_type_id = store.put_context_type("Run").execute().await?;
+    let other_type_id = store.put_context_type("Other").execute().await?;
+    let c0 = store
+        .post_context(experiment_type_id, "c0")
+        .execute()
+        .await?;
+    let c1 = store.post_context(run_type_id, "c1").execute().await?;
+    store.post_context(other_type_id, "c2").execute().await?;
+
+    let contexts = store
+        .get_contexts()
+        .types(["Experiment", "Run"].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(
+        contexts.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![c0, c1]
+    );
+    assert_eq!(
+        store
+            .get_contexts()
+            .types(["Experiment", "Run"].into_iter())
+            .count()
+            .await?,
+        2
+    );
+
+    let contexts = store
+        .get_contexts()
+        .types(["foo"].into_iter())
+        .execute()
+        .await?;
+    assert!(contexts.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_contexts_by_type_and_create_time_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let run_type_id = store.put_context_type("Run").execute().await?;
+    let other_type_id = store.put_context_type("Other").execute().await?;
+    let mut runs = Vec::new();
+    for (i, t) in [300, 100, 200, 400].into_iter().enumerate() {
+        let time = Duration::from_millis(t);
+        runs.push(
+            store
+                .post_context(run_type_id, &format!("run{}", i))
+                .create_time_since_epoch(time)
+                .execute()
+                .await?,
+        );
+        store
+            .post_context(other_type_id, &format!("other{}", i))
+            .create_time_since_epoch(time)
+            .execute()
+            .await?;
+    }
+
+    let request = || {
+        store
+            .get_contexts()
+            .ty("Run")
+            .create_time(Duration::from_millis(100)..Duration::from_millis(400))
+            .order_by(ContextOrderByField::CreateTime, true)
+    };
+    let contexts = request().execute().await?;
+    assert_eq!(
+        contexts.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![runs[1], runs[2], runs[0]]
+    );
+    assert_eq!(request().count().await?, 3);
+
+    // The type join doesn't make SQLite scan the whole `Context` table.
+    let sql = request().dry_run_sql().sql;
+    let plan = sqlx::query(&format!("EXPLAIN QUERY PLAN {}", sql))
+        .bind("Run")
+        .bind(100i64)
+        .bind(400i64)
+        .fetch_all(&store.pool)
+        .await?
+        .into_iter()
+        .map(|row| row.try_get::<String, _>(3))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(
+        plan.iter().any(|x| x.starts_with("SEARCH A ")),
+        "{:?}",
+        plan
+    );
+    assert!(!plan.iter().any(|x| x.starts_with("SCAN A")), "{:?}", plan);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_contexts_by_parent_and_child_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let experiment_type_id = store.put_context_type("Experiment").execute().await?;
+    let run_type_id = store.put_context_type("Run").execute().await?;
+    let step_type_id = store.put_context_type("Step").execute().await?;
+    let experiment = store
+        .post_context(experiment_type_id, "exp")
+        .execute()
+        .await?;
+    let run0 = store.post_context(run_type_id, "run0").execute().await?;
+    let run1 = store.post_context(run_type_id, "run1").execute().await?;
+    let step = store.post_context(step_type_id, "step").execute().await?;
+    store.put_parent_context(experiment, run0).execute().await?;
+    store.put_parent_context(experiment, run1).execute().await?;
+    store.put_parent_context(run0, step).execute().await?;
+
+    let ids = |contexts: Vec<Context>| contexts.into_iter().map(|c| c.id).collect::<Vec<_>>();
+
+    // All runs in the experiment.
+    let contexts = store.get_contexts().parent(experiment).execute().await?;
+    assert_eq!(ids(contexts), vec![run0, run1]);
+
+    let contexts = store.get_contexts().parent(run0).execute().await?;
+    assert_eq!(ids(contexts), vec![step]);
+    assert_eq!(store.get_contexts().parent(step).count().await?, 0);
+
+    // Parents.
+    let contexts = store.get_contexts().child(step).execute().await?;
+    assert_eq!(ids(contexts), vec![run0]);
+    let contexts = store.get_contexts().child(run1).execute().await?;
+    assert_eq!(ids(contexts), vec![experiment]);
+    assert_eq!(store.get_contexts().child(experiment).count().await?, 0);
+
+    // Combined with other conditions.
+    let contexts = store
+        .get_contexts()
+        .parent(experiment)
+        .type_and_name("Run", "run1")
+        .execute()
+        .await?;
+    assert_eq!(ids(contexts), vec![run1]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_context_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert_eq!(store.get_contexts().execute().await?.len(), 1);
+
+    let mut context = context0();
+    context.name = "foo".to_string();
+    context
+        .custom_properties
+        .insert("bar".to_string(), PropertyValue::Int(10));
+    store
+        .put_context(context.id)
+        .name(&context.name)
+        .custom_properties(context.custom_properties.clone())
+        .execute()
+        .await?;
+
+    let contexts = store.get_contexts().execute().await?;
+    assert_eq!(contexts.len(), 1);
+
+    context.last_update_time_since_epoch = contexts[0].last_update_time_since_epoch;
+    assert_eq!(contexts[0], context);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn post_context_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    assert!(store.get_contexts().execute().await?.is_empty());
+
+    let type_id = store.put_context_type("Context").execute().await?;
+
+    // Simple context.
+    let context_id = store.post_context(type_id, "bar").execute().await?;
+
+    let contexts = store.get_contexts().execute().await?;
+    assert_eq!(contexts.len(), 1);
+    assert_eq!(contexts[0].id, context_id);
+
+    // Name confilict.
+    store.post_context(type_id, "foo").execute().await?;
+    assert!(matches!(
+        store.post_context(type_id, "foo").execute().await.err(),
+        Some(PostError::NameAlreadyExists { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn max_uri_length_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let type_id = store.put_artifact_type("t").execute().await?;
+
+    // Unlimited by default.
+    let long_uri = "a".repeat(1000);
+    let artifact_id = store
+        .post_artifact(type_id)
+        .uri(&long_uri)
+        .execute()
+        .await?;
+
+    store.set_max_uri_length(Some(10));
+    assert!(matches!(
+        store.post_artifact(type_id).uri(&long_uri).execute().await,
+        Err(PostError::UriTooLong { len: 1000, max: 10 })
+    ));
+    assert!(matches!(
+        store
+            .put_artifact(artifact_id)
+            .uri("01234567890")
+            .execute()
+            .await,
+        Err(PutError::UriTooLong {
+            len: 11,
+            max: 10,
+            ..
+        })
+    ));
+    store
+        .post_artifact(type_id)
+        .uri("0123456789")
+        .execute()
+        .await?;
+    store
+        .put_artifact(artifact_id)
+        .uri("0123456789")
+        .execute()
+        .await?;
+    assert_eq!(store.get_artifacts().count().await?, 2);
+
+    store.set_max_uri_length(None);
+    store
+        .post_artifact(type_id)
+        .uri(&long_uri)
+        .execute()
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn post_context_name_conflict_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let t0 = store.put_context_type("t0").execute().await?;
+    let t1 = store.put_context_type("t1").execute().await?;
+    let context_id = store.post_context(t0, "foo").execute().await?;
+
+    // The same name is allowed in a different type.
+    store.post_context(t1, "foo").execute().await?;
+
+    assert!(matches!(
+        store.post_context(t0, "foo").execute().await.err(),
+        Some(PostError::NameAlreadyExists {
+            type_kind: TypeKind::Context,
+            ..
+        })
+    ));
+    assert_eq!(store.get_contexts().ty("t0").count().await?, 1);
+
+    // The unique constraint violation that the pre-check could miss is also detected.
+    let e = sqlx::query("INSERT INTO Context (type_id, name) VALUES (?, ?)")
+        .bind(t0.get())
+        .bind("foo")
+        .execute(&store.pool)
+        .await
+        .expect_err("should fail");
+    assert!(is_unique_violation(&e));
+    assert_eq!(
+        store.get_context(context_id).await?.map(|c| c.name),
+        Some("foo".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_all_types_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let types = store.stream_all_types().try_collect::<Vec<_>>().await?;
+    let count = |kind| types.iter().filter(|t| t.kind() == kind).count();
+    assert_eq!(count(TypeKind::Artifact), 6);
+    assert_eq!(count(TypeKind::Execution), 6);
+    assert_eq!(count(TypeKind::Context), 1);
+    assert!(types
+        .iter()
+        .any(|t| t.name() == "DataSet" && !t.properties().is_empty()));
+
+    // More types than a single batch.
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    for i in 0..(TYPE_BATCH_SIZE + 1) {
+        store
+            .put_artifact_type(&format!("a{}", i))
+            .property("p", PropertyType::Int)
+            .execute()
+            .await?;
+    }
+    store.put_context_type("c").execute().await?;
+
+    let types = store.stream_all_types().try_collect::<Vec<_>>().await?;
+    let count = |kind| types.iter().filter(|t| t.kind() == kind).count();
+    assert_eq!(count(TypeKind::Artifact), TYPE_BATCH_SIZE + 1);
+    assert_eq!(count(TypeKind::Execution), 0);
+    assert_eq!(count(TypeKind::Context), 1);
+    assert!(types
+        .iter()
+        .filter(|t| t.kind() == TypeKind::Artifact)
+        .all(|t| t.properties().len() == 1));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+
+    // Commit.
+    let mut tx = store.transaction().await?;
+    let execution_id = tx.post_execution(execution_type_id).execute().await?;
+    for ty in [EventType::Input, EventType::Output] {
+        let artifact_id = tx.post_artifact(artifact_type_id).execute().await?;
+        tx.put_event(execution_id, artifact_id)
+            .ty(ty)
+            .execute()
+            .await?;
+    }
+    assert_eq!(tx.get_artifacts().count().await?, 2);
+    assert_eq!(tx.get_events().count().await?, 2);
+    tx.commit().await?;
+
+    assert_eq!(store.get_executions().count().await?, 1);
+    assert_eq!(store.get_artifacts().count().await?, 2);
+    assert_eq!(store.get_events().count().await?, 2);
+
+    // Rollback on drop.
+    let mut tx = store.transaction().await?;
+    let execution_id = tx.post_execution(execution_type_id).execute().await?;
+    let artifact_id = tx.post_artifact(artifact_type_id).execute().await?;
+    assert!(tx
+        .put_event(execution_id, ArtifactId::new(100))
+        .execute()
+        .await
+        .is_err());
+    tx.put_event(execution_id, artifact_id).execute().await?;
+    std::mem::drop(tx);
+
+    assert_eq!(store.get_executions().count().await?, 1);
+    assert_eq!(store.get_artifacts().count().await?, 2);
+    assert_eq!(store.get_events().count().await?, 2);
+
+    // Explicit rollback.
+    let mut tx = store.transaction().await?;
+    tx.post_artifact(artifact_type_id).execute().await?;
+    tx.rollback().await?;
+    assert_eq!(store.get_artifacts().count().await?, 2);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_context_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    store
+        .put_context_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+
+    assert!(matches!(
+        store
+            .put_context_type("t0")
+            .property("p0", PropertyType::Double)
+            .execute()
+            .await,
+        Err(PutError::TypeAlreadyExists { .. })
+    ));
+
+    assert!(matches!(
+        store
+            .put_context_type("t0")
+            .property("p0", PropertyType::Int)
+            .property("p1", PropertyType::String)
+            .execute()
+            .await,
+        Err(PutError::TypeAlreadyExists { .. })
+    ));
+    store
+        .put_context_type("t0")
+        .can_add_fields()
+        .property("p0", PropertyType::Int)
+        .property("p1", PropertyType::String)
+        .execute()
+        .await?;
+
+    assert!(matches!(
+        store.put_context_type("t0").execute().await,
+        Err(PutError::TypeAlreadyExists { .. })
+    ));
+    store
+        .put_context_type("t0")
+        .can_omit_fields()
+        .execute()
+        .await?;
+    store.put_context_type("t1").execute().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_context_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let t0_id = store
+        .put_context_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let t1_id = store.put_context_type("t1").execute().await?;
+    assert_ne!(t0_id, t1_id);
+
+    assert_eq!(
+        store.get_context_types().name("t0").execute().await?[0],
+        ContextType {
+            id: t0_id,
+            name: "t0".to_owned(),
+            version: None,
+            description: None,
+            properties: vec![("p0".to_owned(), PropertyType::Int)]
+                .into_iter()
+                .collect()
+        }
+    );
+    assert_eq!(
+        store.get_context_types().name("t1").execute().await?[0],
+        ContextType {
+            id: t1_id,
+            name: "t1".to_owned(),
+            version: None,
+            description: None,
+            properties: BTreeMap::new(),
+        }
+    );
+    assert!(store
+        .get_context_types()
+        .name("t2")
+        .execute()
+        .await?
+        .is_empty(),);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_context_types_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let types = store.get_context_types().execute().await?;
+    assert_eq!(types.len(), 1);
+    assert_eq!(types[0].name, "Experiment");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_attribution_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
+        .await
+        .unwrap();
+
+    let t0 = store.put_artifact_type("t0").execute().await?;
+    let a0 = store.post_artifact(t0).execute().await?;
+    let _a1 = store.post_artifact(t0).execute().await?;
+
+    let t1 = store.put_context_type("t1").execute().await?;
+    let _c0 = store.post_context(t1, "foo").execute().await?;
+    let c1 = store.post_context(t1, "bar").execute().await?;
+
+    for expected in [RelationOutcome::Inserted, RelationOutcome::AlreadyExisted] {
+        // The duplicate PUTs are ignored.
+        let outcome = store.put_attribution(c1, a0).execute().await?;
+        assert_eq!(outcome, expected);
+
+        let contexts = store.get_contexts().artifact(a0).execute().await?;
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].id, c1);
+
+        let artifacts = store.get_artifacts().context(c1).execute().await?;
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].id, a0);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_contexts_by_multiple_items_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let t0 = store.put_artifact_type("t0").execute().await?;
+    let a0 = store.post_artifact(t0).execute().await?;
+    let a1 = store.post_artifact(t0).execute().await?;
+    let a2 = store.post_artifact(t0).execute().await?;
+
+    let t1 = store.put_execution_type("t1").execute().await?;
+    let e0 = store.post_execution(t1).execute().await?;
+    let e1 = store.post_execution(t1).execute().await?;
+
+    let t2 = store.put_context_type("t2").execute().await?;
+    let c0 = store.post_context(t2, "foo").execute().await?;
+    let c1 = store.post_context(t2, "bar").execute().await?;
+    let _c2 = store.post_context(t2, "baz").execute().await?;
+
+    store.put_attribution(c0, a0).execute().await?;
+    store.put_attribution(c0, a1).execute().await?;
+    store.put_attribution(c1, a1).execute().await?;
+    store.put_association(c0, e0).execute().await?;
+    store.put_association(c0, e1).execute().await?;
+
+    // `c0` is attributed to both artifacts but appears only once.
+    let request = || store.get_contexts().artifacts([a0, a1, a2].into_iter());
+    let contexts = request().execute().await?;
+    assert_eq!(
+        contexts.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![c0, c1]
+    );
+    assert_eq!(request().count().await?, 2);
+
+    let request = || store.get_contexts().executions([e0, e1].into_iter());
+    let contexts = request().execute().await?;
+    assert_eq!(contexts.iter().map(|c| c.id).collect::<Vec<_>>(), vec![c0]);
+    assert_eq!(request().count().await?, 1);
+
+    let contexts = store
+        .get_contexts()
+        .artifacts([a1].into_iter())
+        .executions([e0].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(contexts.iter().map(|c| c.id).collect::<Vec<_>>(), vec![c0]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_association_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
+        .await
+        .unwrap();
+
+    let t0 = store.put_execution_type("t0").execute().await?;
+    let e0 = store.post_execution(t0).execute().await?;
+    let _e1 = store.post_execution(t0).execute().await?;
+
+    let t1 = store.put_context_type("t1").execute().await?;
+    let _c0 = store.post_context(t1, "foo").execute().await?;
+    let c1 = store.post_context(t1, "bar").execute().await?;
+
+    for expected in [RelationOutcome::Inserted, RelationOutcome::AlreadyExisted] {
+        // The duplicate PUTs are ignored.
+        let outcome = store.put_association(c1, e0).execute().await?;
+        assert_eq!(outcome, expected);
+
+        let contexts = store.get_contexts().execution(e0).execute().await?;
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].id, c1);
+
+        let executions = store.get_executions().context(c1).execute().await?;
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].id, e0);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_parent_context_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
+        .await
+        .unwrap();
+
+    let t0 = store.put_context_type("t0").execute().await?;
+    let c0 = store.post_context(t0, "experiment").execute().await?;
+    let c1 = store.post_context(t0, "run1").execute().await?;
+    let c2 = store.post_context(t0, "run2").execute().await?;
+
+    for expected in [RelationOutcome::Inserted, RelationOutcome::AlreadyExisted] {
+        // The duplicate PUTs are ignored.
+        let outcome = store.put_parent_context(c0, c1).execute().await?;
+        assert_eq!(outcome, expected);
+        let outcome = store.put_parent_context(c0, c2).execute().await?;
+        assert_eq!(outcome, expected);
+
+        let children = store.get_child_contexts_by_context(c0).await?;
+        assert_eq!(
+            children.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![c1, c2]
+        );
+
+        let parents = store.get_parent_contexts_by_context(c1).await?;
+        assert_eq!(parents.iter().map(|c| c.id).collect::<Vec<_>>(), vec![c0]);
+    }
+    assert!(store.get_parent_contexts_by_context(c0).await?.is_empty());
+    assert!(store.get_child_contexts_by_context(c1).await?.is_empty());
+
+    let unregistered_id = ContextId::new(100);
+    assert!(matches!(
+        store
+            .put_parent_context(unregistered_id, c1)
+            .execute()
+            .await,
+        Err(PutError::NotFound { .. })
+    ));
+
+    // Self parent.
+    assert!(matches!(
+        store.put_parent_context(c1, c1).execute().await,
+        Err(PutError::SelfParentContext { context_id }) if context_id == c1
+    ));
+    assert!(store.get_child_contexts_by_context(c1).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_event_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new().unwrap();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path()))
+        .await
+        .unwrap();
+
+    let t0 = store.put_execution_type("t0").execute().await?;
+    let e0 = store.post_execution(t0).execute().await?;
+    let e1 = store.post_execution(t0).execute().await?;
+
+    let t1 = store.put_artifact_type("t1").execute().await?;
+    let a0 = store.post_artifact(t1).execute().await?;
+    let a1 = store.post_artifact(t1).execute().await?;
+
+    store
+        .put_event(e0, a0)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    store
+        .put_event(e1, a1)
+        .ty(EventType::Output)
+        .step(EventStep::Index(30))
+        .execute()
+        .await?;
+
+    let events = store.get_events().execute().await?;
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(events[0].artifact_id, a0);
+    assert_eq!(events[0].execution_id, e0);
+    assert_eq!(events[0].ty, EventType::Input);
+    assert_eq!(events[0].path, vec![]);
+
+    assert_eq!(events[1].artifact_id, a1);
+    assert_eq!(events[1].execution_id, e1);
+    assert_eq!(events[1].ty, EventType::Output);
+    assert_eq!(events[1].path, vec![EventStep::Index(30)]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_event_paths_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let execution_type_id = store.put_execution_type("t0").execute().await?;
+    let execution_id = store.post_execution(execution_type_id).execute().await?;
+    let artifact_type_id = store.put_artifact_type("t1").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+
+    // Events without paths only.
+    store
+        .put_event(execution_id, a0)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    let events = store.get_events().execute().await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].path, vec![]);
+
+    // Steps are returned in the insertion order.
+    let path = vec![
+        EventStep::Key("b".to_owned()),
+        EventStep::Index(1),
+        EventStep::Key("a".to_owned()),
+        EventStep::Index(0),
+    ];
+    let mut request = store.put_event(execution_id, a1).ty(EventType::Output);
+    for step in &path {
+        request = request.step(step.clone());
+    }
+    request.execute().await?;
+    let events = store.get_events().execute().await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].path, vec![]);
+    assert_eq!(events[1].path, path);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn event_path_order_is_stable_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let execution_type_id = store.put_execution_type("t0").execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    let artifact_type_id = store.put_artifact_type("t1").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+
+    // The steps of the target event are surrounded by the ones of other events in the table.
+    store
+        .put_event(e1, a0)
+        .step(EventStep::Index(1))
+        .execute()
+        .await?;
+    store
+        .put_event(e0, a0)
+        .step(EventStep::Index(2))
+        .step(EventStep::Key("a".to_owned()))
+        .step(EventStep::Index(0))
+        .execute()
+        .await?;
+    store
+        .put_event(e1, a1)
+        .step(EventStep::Key("z".to_owned()))
+        .execute()
+        .await?;
+
+    let path = vec![
+        EventStep::Index(2),
+        EventStep::Key("a".to_owned()),
+        EventStep::Index(0),
+    ];
+    for _ in 0..3 {
+        let events = store.get_events().execution(e0).execute().await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, path);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_events_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let events = store.get_events().execute().await?;
+    assert_eq!(events, vec![event0(), event1()]);
+
+    let events = store
+        .get_events()
+        .artifact(ArtifactId::new(1))
+        .execute()
+        .await?;
+    assert_eq!(events, vec![event0()]);
+
+    let events = store
+        .get_events()
+        .artifact(ArtifactId::new(2))
+        .execute()
+        .await?;
+    assert_eq!(events, vec![event1()]);
+
+    let events = store
+        .get_events()
+        .execution(ExecutionId::new(1))
+        .execute()
+        .await?;
+    assert_eq!(events, vec![event0(), event1()]);
+
+    let events = store
+        .get_events()
+        .execution(ExecutionId::new(2))
+        .execute()
+        .await?;
+    assert_eq!(events, vec![]);
+
+    let events = store
+        .get_events()
+        .artifact(ArtifactId::new(1))
+        .execution(ExecutionId::new(1))
+        .execute()
+        .await?;
+    assert_eq!(events, vec![event0()]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_events_by_pairs_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("t0").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+    let execution_type_id = store.put_execution_type("t1").execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    for (e, a) in [(e0, a0), (e0, a1), (e1, a0), (e1, a1)] {
+        store.put_event(e, a).execute().await?;
+    }
+
+    let pairs = |events: Vec<Event>| {
+        events
+            .into_iter()
+            .map(|e| (e.execution_id, e.artifact_id))
+            .collect::<Vec<_>>()
+    };
+
+    // The cross product.
+    let events = store
+        .get_events()
+        .executions([e0, e1].into_iter())
+        .artifacts([a0, a1].into_iter())
+        .execute()
+        .await?;
+    assert_eq!(events.len(), 4);
+
+    // The exact pairs.
+    let request = || store.get_events().pairs([(e0, a1), (e1, a0)].into_iter());
+    assert_eq!(pairs(request().execute().await?), vec![(e0, a1), (e1, a0)]);
+    assert_eq!(request().count().await?, 2);
+
+    // Combined with the other conditions.
+    let events = request().artifact(a0).execute().await?;
+    assert_eq!(pairs(events), vec![(e1, a0)]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_events_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let mut artifact_ids = Vec::new();
+    let mut execution_ids = Vec::new();
+    for _ in 0..3 {
+        artifact_ids.push(store.post_artifact(artifact_type_id).execute().await?);
+        execution_ids.push(store.post_execution(execution_type_id).execute().await?);
+    }
+
+    // Every execution takes all the artifacts as inputs and the first one also as an output.
+    for &execution_id in &execution_ids {
+        for &artifact_id in &artifact_ids {
+            store
+                .put_event(execution_id, artifact_id)
+                .ty(EventType::Input)
+                .execute()
+                .await?;
+        }
+        store
+            .put_event(execution_id, artifact_ids[0])
+            .ty(EventType::Output)
+            .execute()
+            .await?;
+    }
+    assert_eq!(store.get_events().count().await?, 12);
+
+    let request = || {
+        store
+            .get_events()
+            .artifacts(artifact_ids[..2].iter().copied())
+            .executions(execution_ids[1..].iter().copied())
+    };
+    let events = request().execute().await?;
+    assert_eq!(events.len(), 6);
+    assert_eq!(request().count().await?, events.len());
+
+    let request = || {
+        store
+            .get_events()
+            .artifact(artifact_ids[2])
+            .execution(execution_ids[0])
+    };
+    assert_eq!(request().count().await?, request().execute().await?.len());
+    assert_eq!(request().count().await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn project_artifacts_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_artifact_type("DataSet")
+        .property("day", PropertyType::Int)
+        .property("split", PropertyType::String)
+        .execute()
+        .await?;
+    let other_type_id = store.put_artifact_type("Other").execute().await?;
+
+    let a0 = store
+        .post_artifact(type_id)
+        .properties(
+            vec![
+                ("day".to_owned(), PropertyValue::Int(1)),
+                ("split".to_owned(), PropertyValue::from("train")),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .execute()
+        .await?;
+    let a1 = store
+        .post_artifact(type_id)
+        .properties(
+            vec![("split".to_owned(), PropertyValue::from("test"))]
+                .into_iter()
+                .collect(),
+        )
+        .execute()
+        .await?;
+    let a2 = store
+        .post_artifact(type_id)
+        .custom_properties(
+            vec![("day".to_owned(), PropertyValue::Int(3))]
+                .into_iter()
+                .collect(),
+        )
+        .execute()
+        .await?;
+    store.post_artifact(other_type_id).execute().await?;
+
+    let rows = store
+        .project_artifacts("DataSet", &["split", "day"])
+        .await?;
+    assert_eq!(
+        rows,
+        vec![
+            (
+                a0,
+                vec![
+                    Some(PropertyValue::from("train")),
+                    Some(PropertyValue::Int(1))
+                ]
+            ),
+            (a1, vec![Some(PropertyValue::from("test")), None]),
+            (a2, vec![None, Some(PropertyValue::Int(3))]),
+        ]
+    );
+
+    let rows = store.project_artifacts("DataSet", &[]).await?;
+    assert_eq!(rows, vec![(a0, vec![]), (a1, vec![]), (a2, vec![])]);
+    assert!(store.project_artifacts("foo", &["day"]).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn artifacts_touched_by_execution_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let trainer_type_id = store.put_execution_type("Trainer").execute().await?;
+    let other_type_id = store.put_execution_type("Other").execute().await?;
+    let dataset = store.post_artifact(artifact_type_id).execute().await?;
+    let model0 = store.post_artifact(artifact_type_id).execute().await?;
+    let model1 = store.post_artifact(artifact_type_id).execute().await?;
+    let report = store.post_artifact(artifact_type_id).execute().await?;
+
+    for model in [model0, model1] {
+        let trainer = store.post_execution(trainer_type_id).execute().await?;
+        store
+            .put_event(trainer, dataset)
+            .ty(EventType::Input)
+            .execute()
+            .await?;
+        store
+            .put_event(trainer, model)
+            .ty(EventType::Output)
+            .execute()
+            .await?;
+    }
+    let other = store.post_execution(other_type_id).execute().await?;
+    store
+        .put_event(other, model0)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    store
+        .put_event(other, report)
+        .ty(EventType::Output)
+        .execute()
+        .await?;
+
+    let ids = |artifacts: Vec<Artifact>| artifacts.into_iter().map(|a| a.id).collect::<Vec<_>>();
+    assert_eq!(
+        ids(store
+            .artifacts_touched_by_execution_type("Trainer", None)
+            .await?),
+        vec![dataset, model0, model1]
+    );
+    assert_eq!(
+        ids(store
+            .artifacts_touched_by_execution_type("Trainer", Some(vec![EventType::Output]))
+            .await?),
+        vec![model0, model1]
+    );
+    assert_eq!(
+        ids(store
+            .artifacts_touched_by_execution_type(
+                "Trainer",
+                Some(vec![EventType::Input, EventType::DeclaredInput])
+            )
+            .await?),
+        vec![dataset]
+    );
+    assert!(store
+        .artifacts_touched_by_execution_type("Trainer", Some(Vec::new()))
+        .await?
+        .is_empty());
+    assert!(store
+        .artifacts_touched_by_execution_type("foo", None)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn latest_producing_execution_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let artifact_id = store.post_artifact(artifact_type_id).execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    let e2 = store.post_execution(execution_type_id).execute().await?;
+    assert_eq!(store.latest_producing_execution(artifact_id).await?, None);
+
+    for (execution_id, ty, time) in [
+        (e1, EventType::Output, 2000),
+        (e0, EventType::Output, 1000),
+        (e2, EventType::Input, 3000),
+    ] {
+        store
+            .put_event(execution_id, artifact_id)
+            .ty(ty)
+            .execute()
+            .await?;
+        sqlx::query("UPDATE Event SET milliseconds_since_epoch = ? WHERE execution_id = ?")
+            .bind(time as i64)
+            .bind(execution_id.get())
+            .execute(&store.pool)
+            .await?;
+    }
+    let execution = store.latest_producing_execution(artifact_id).await?;
+    assert_eq!(execution.map(|x| x.id), Some(e1));
+
+    // Ties are broken by the event ID.
+    sqlx::query("UPDATE Event SET milliseconds_since_epoch = 2000 WHERE execution_id = ?")
+        .bind(e0.get())
+        .execute(&store.pool)
+        .await?;
+    let execution = store.latest_producing_execution(artifact_id).await?;
+    assert_eq!(execution.map(|x| x.id), Some(e0));
+
+    // Other output event types are also taken into account.
+    let e3 = store.post_execution(execution_type_id).execute().await?;
+    store
+        .put_event(e3, artifact_id)
+        .ty(EventType::InternalOutput)
+        .execute()
+        .await?;
+    sqlx::query("UPDATE Event SET milliseconds_since_epoch = 4000 WHERE execution_id = ?")
+        .bind(e3.get())
+        .execute(&store.pool)
+        .await?;
+    let execution = store.latest_producing_execution(artifact_id).await?;
+    assert_eq!(execution.map(|x| x.id), Some(e3));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn execution_io_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("Trainer").execute().await?;
+    let dataset = store.post_artifact(artifact_type_id).execute().await?;
+    let model = store.post_artifact(artifact_type_id).execute().await?;
+    let trainer = store.post_execution(execution_type_id).execute().await?;
+    let idle = store.post_execution(execution_type_id).execute().await?;
+    store
+        .put_event(trainer, dataset)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    store
+        .put_event(trainer, model)
+        .ty(EventType::Output)
+        .execute()
+        .await?;
+
+    let io = store.execution_io([trainer, idle].into_iter()).await?;
+    assert_eq!(io.len(), 2);
+    assert_eq!(
+        io[&trainer],
+        (
+            [dataset].into_iter().collect(),
+            [model].into_iter().collect()
+        )
+    );
+    assert_eq!(io[&idle], Default::default());
+
+    let io = store.execution_io([idle].into_iter()).await?;
+    assert_eq!(io.keys().copied().collect::<Vec<_>>(), [idle]);
+    assert!(store.execution_io(std::iter::empty()).await?.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test(flavor = "multi_thread")]
+async fn validate_execution_io_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let examples_type_id = store.put_artifact_type("Examples").execute().await?;
+    let model_type_id = store.put_artifact_type("Model").execute().await?;
+    let execution_type_id = store
+        .put_execution_type("Trainer")
+        .input_type(serde_json::json!({"dict": {"properties": {
+            "examples": {"list": {"element": {"simple": {"name": "Examples"}}}}
+        }}}))
+        .output_type(serde_json::json!({"dict": {"properties": {
+            "model": {"simple": {"name": "Model"}}
+        }}}))
+        .execute()
+        .await?;
+    let examples0 = store.post_artifact(examples_type_id).execute().await?;
+    let examples1 = store.post_artifact(examples_type_id).execute().await?;
+    let model = store.post_artifact(model_type_id).execute().await?;
+
+    // Conforming execution.
+    let conforming = store.post_execution(execution_type_id).execute().await?;
+    for (i, examples) in [examples0, examples1].into_iter().enumerate() {
+        store
+            .put_event(conforming, examples)
+            .ty(EventType::Input)
+            .step(EventStep::Key("examples".to_owned()))
+            .step(EventStep::Index(i as i32))
+            .execute()
+            .await?;
+    }
+    store
+        .put_event(conforming, model)
+        .ty(EventType::Output)
+        .step(EventStep::Key("model".to_owned()))
+        .execute()
+        .await?;
+    assert_eq!(
+        store.validate_execution_io(conforming).await?,
+        Some(Vec::new())
+    );
+
+    // Non-conforming execution: a model is given as an input and no output is produced.
+    let non_conforming = store.post_execution(execution_type_id).execute().await?;
+    store
+        .put_event(non_conforming, model)
+        .ty(EventType::Input)
+        .step(EventStep::Key("examples".to_owned()))
+        .step(EventStep::Index(0))
+        .execute()
+        .await?;
+    assert_eq!(
+        store.validate_execution_io(non_conforming).await?,
+        Some(vec![
+            ExecutionIoViolation {
+                is_output: false,
+                path: vec![EventStep::Key("examples".to_owned()), EventStep::Index(0)],
+                reason: format!(
+                    "{} has the type \"Model\" but \"Examples\" is expected",
+                    model
+                ),
+            },
+            ExecutionIoViolation {
+                is_output: true,
+                path: vec![EventStep::Key("model".to_owned())],
+                reason: "an artifact of the type \"Model\" is missing".to_owned(),
+            },
+        ])
+    );
+
+    // Undeclared types accept any artifacts.
+    let untyped_id = store.put_execution_type("Untyped").execute().await?;
+    let untyped = store.post_execution(untyped_id).execute().await?;
+    store
+        .put_event(untyped, model)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    assert_eq!(
+        store.validate_execution_io(untyped).await?,
+        Some(Vec::new())
+    );
+
+    assert_eq!(
+        store.validate_execution_io(ExecutionId::new(100)).await?,
+        None
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn duplicate_events_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert!(store.find_duplicate_events().await?.is_empty());
+    assert_eq!(store.remove_duplicate_events().await?, 0);
+
+    // Emulates an `Event` table without the unique constraint and injects duplicates.
+    for sql in [
+        concat!(
+            "CREATE TABLE EventWithoutUnique (",
+            "  id INTEGER PRIMARY KEY AUTOINCREMENT, artifact_id INT NOT NULL, ",
+            "  execution_id INT NOT NULL, type INT NOT NULL, milliseconds_since_epoch INT)"
+        ),
+        "INSERT INTO EventWithoutUnique SELECT * FROM Event",
+        "DROP TABLE Event",
+        "ALTER TABLE EventWithoutUnique RENAME TO Event",
+        "INSERT INTO Event (artifact_id, execution_id, type, milliseconds_since_epoch) VALUES (1, 1, 2, 1648979124900)",
+        "INSERT INTO Event (artifact_id, execution_id, type, milliseconds_since_epoch) VALUES (1, 1, 2, 1648979124901)",
+        "INSERT INTO Event (artifact_id, execution_id, type, milliseconds_since_epoch) VALUES (2, 1, 1, 1648979124902)",
+    ] {
+        sqlx::query(sql).execute(&store.pool).await?;
+    }
+    assert_eq!(store.get_events().execute().await?.len(), 5);
+
+    assert_eq!(
+        store.find_duplicate_events().await?,
+        vec![
+            (
+                ArtifactId::new(1),
+                ExecutionId::new(1),
+                EventType::DeclaredInput,
+                3
+            ),
+            (
+                ArtifactId::new(2),
+                ExecutionId::new(1),
+                EventType::DeclaredOutput,
+                2
+            ),
+        ]
+    );
+
+    assert_eq!(store.remove_duplicate_events().await?, 3);
+    assert!(store.find_duplicate_events().await?.is_empty());
+    assert_eq!(
+        store.get_events().execute().await?,
+        vec![event0(), event1()]
+    );
+
+    // The unique constraint has been added back.
+    let result = sqlx::query(
+        "INSERT INTO Event (artifact_id, execution_id, type, milliseconds_since_epoch) VALUES (1, 1, 2, 1648979124903)",
+    )
+    .execute(&store.pool)
+    .await;
+    assert!(result.is_err());
+    assert_eq!(store.remove_duplicate_events().await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn non_unique_constraint_violation_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // Makes inserts fail with SQLITE_CONSTRAINT_TRIGGER, which isn't a unique constraint violation.
+    for table in ["Artifact", "Event"] {
+        sqlx::query(&format!(
+            "CREATE TRIGGER reject_{0} BEFORE INSERT ON {0} BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+            table
+        ))
+        .execute(&store.pool)
+        .await?;
+    }
+
+    let result = store
+        .post_artifact(TypeId::new(10))
+        .name("foo")
+        .execute()
+        .await;
+    assert!(matches!(result, Err(PostError::Db(_))), "{:?}", result);
+
+    let result = store
+        .put_event(ExecutionId::new(1), ArtifactId::new(2))
+        .ty(EventType::Input)
+        .execute()
+        .await;
+    assert!(matches!(result, Err(PutError::Db(_))), "{:?}", result);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_event_already_exists_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let result = store
+        .put_event(event0().execution_id, event0().artifact_id)
+        .ty(event0().ty)
+        .step(EventStep::Index(0))
+        .execute()
+        .await;
+    assert!(
+        matches!(
+            result,
+            Err(PutError::EventAlreadyExists { execution_id, artifact_id, ty: EventType::DeclaredInput })
+                if execution_id == event0().execution_id && artifact_id == event0().artifact_id
+        ),
+        "{:?}",
+        result
+    );
+    assert_eq!(
+        store.get_events().execute().await?,
+        vec![event0(), event1()]
+    );
+
+    // Another type of event between the same execution and artifact.
+    store
+        .put_event(event0().execution_id, event0().artifact_id)
+        .ty(EventType::Input)
+        .execute()
+        .await?;
+    assert_eq!(store.get_events().execute().await?.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn update_event_path_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let path = vec![EventStep::Key("foo".to_owned()), EventStep::Index(1)];
+    store
+        .update_event_path(
+            event1().execution_id,
+            event1().artifact_id,
+            event1().ty,
+            path.clone(),
+        )
+        .await?;
+    let events = store.get_events().execute().await?;
+    assert_eq!(events[0], event0());
+    assert_eq!(events[1], Event { path, ..event1() });
+
+    // Clears the path.
+    store
+        .update_event_path(
+            event1().execution_id,
+            event1().artifact_id,
+            event1().ty,
+            None,
+        )
+        .await?;
+    assert_eq!(
+        store.get_events().execute().await?,
+        vec![event0(), event1()]
+    );
+
+    assert!(matches!(
+        store
+            .update_event_path(
+                event1().execution_id,
+                event1().artifact_id,
+                EventType::Output,
+                None,
+            )
+            .await,
+        Err(PutError::EventNotFound {
+            ty: EventType::Output,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_event_with_time_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+
+    let time = Duration::from_millis(1234567890123);
+    store
+        .put_event(e0, a0)
+        .ty(EventType::Input)
+        .time(time + Duration::from_micros(456))
+        .execute()
+        .await?;
+    store
+        .put_event(e0, a1)
+        .ty(EventType::Output)
+        .execute()
+        .await?;
+
+    let events = store.get_events().execute().await?;
+    assert_eq!(events[0].create_time_since_epoch, time);
+    assert!(events[1].create_time_since_epoch > time);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_events_order_by_ids_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    for (execution_id, artifact_id) in [(e1, a0), (e0, a1), (e1, a1), (e0, a0)] {
+        store.put_event(execution_id, artifact_id).execute().await?;
+    }
+
+    let pairs = |events: Vec<Event>| {
+        events
+            .into_iter()
+            .map(|e| (e.execution_id, e.artifact_id))
+            .collect::<Vec<_>>()
+    };
+
+    // Ties are broken by the insertion order.
+    let events = store
+        .get_events()
+        .order_by(EventOrderByField::ExecutionId, true)
+        .execute()
+        .await?;
+    assert_eq!(pairs(events), [(e0, a1), (e0, a0), (e1, a0), (e1, a1)]);
+
+    let events = store
+        .get_events()
+        .order_by(EventOrderByField::ArtifactId, false)
+        .execute()
+        .await?;
+    assert_eq!(pairs(events), [(e1, a1), (e0, a1), (e0, a0), (e1, a0)]);
+
+    // With joins for filtering.
+    let events = store
+        .get_events()
+        .artifacts([a0, a1].into_iter())
+        .executions([e0, e1].into_iter())
+        .order_by(EventOrderByField::ArtifactId, true)
+        .execute()
+        .await?;
+    assert_eq!(pairs(events), [(e1, a0), (e0, a0), (e0, a1), (e1, a1)]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn order_by_time_tiebreak_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store.put_artifact_type("t0").execute().await?;
+    let execution_type_id = store.put_execution_type("e0").execute().await?;
+    let execution_id = store.post_execution(execution_type_id).execute().await?;
+    let mut artifact_ids = Vec::new();
+    for i in 0..4 {
+        let artifact_id = store
+            .post_artifact(type_id)
+            .name(&format!("a{}", 3 - i))
+            .execute()
+            .await?;
+        store.put_event(execution_id, artifact_id).execute().await?;
+        artifact_ids.push(artifact_id);
+    }
+
+    // Emulates artifacts and events created within the same millisecond.
+    for sql in [
+        "UPDATE Artifact SET create_time_since_epoch = 1000, last_update_time_since_epoch = 1000",
+        "UPDATE Event SET milliseconds_since_epoch = 1000",
+    ] {
+        sqlx::query(sql).execute(&store.pool).await?;
+    }
+
+    let ids = |artifacts: Vec<Artifact>| artifacts.into_iter().map(|a| a.id).collect::<Vec<_>>();
+    for field in [
+        ArtifactOrderByField::CreateTime,
+        ArtifactOrderByField::UpdateTime,
+    ] {
+        let artifacts = store
+            .get_artifacts()
+            .ty("t0")
+            .order_by(field, true)
+            .execute()
+            .await?;
+        assert_eq!(ids(artifacts), artifact_ids);
+
+        let artifacts = store
+            .get_artifacts()
+            .order_by(field, false)
+            .execute()
+            .await?;
+        assert_eq!(
+            ids(artifacts),
+            artifact_ids.iter().rev().copied().collect::<Vec<_>>()
+        );
+    }
+
+    // The columns are qualified so that they don't conflict with the joined `Type` table.
+    let artifacts = store
+        .get_artifacts()
+        .ty("t0")
+        .order_by(ArtifactOrderByField::Name, true)
+        .execute()
+        .await?;
+    assert_eq!(
+        ids(artifacts),
+        artifact_ids.iter().rev().copied().collect::<Vec<_>>()
+    );
+
+    let events = store
+        .get_events()
+        .execution(execution_id)
+        .order_by(EventOrderByField::CreateTime, false)
+        .execute()
+        .await?;
+    assert_eq!(
+        events.iter().map(|e| e.artifact_id).collect::<Vec<_>>(),
+        artifact_ids.iter().rev().copied().collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_artifacts_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let a0 = store.post_artifact(type_id).execute().await?;
+    let a1 = store.post_artifact(type_id).execute().await?;
+
+    store
+        .put_artifacts(vec![
+            (a0, ArtifactOptions::new().state(ArtifactState::Live)),
+            (
+                a1,
+                ArtifactOptions::new()
+                    .state(ArtifactState::Live)
+                    .property("p0", 1)
+                    .uri("path/to/a1"),
+            ),
+        ])
+        .await?;
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts[0].state, ArtifactState::Live);
+    assert_eq!(artifacts[1].state, ArtifactState::Live);
+    assert_eq!(artifacts[1].properties["p0"], PropertyValue::Int(1));
+    assert_eq!(artifacts[1].uri.as_deref(), Some("path/to/a1"));
+
+    // The whole batch is rolled back on failure.
+    assert!(matches!(
+        store
+            .put_artifacts(vec![
+                (a0, ArtifactOptions::new().state(ArtifactState::Deleted)),
+                (a1, ArtifactOptions::new().property("p0", "foo")),
+            ])
+            .await,
+        Err(PutError::PropertyTypeMismatch { .. })
+    ));
+    assert!(matches!(
+        store
+            .put_artifacts(vec![
+                (a0, ArtifactOptions::new().state(ArtifactState::Deleted)),
+                (
+                    ArtifactId::new(100),
+                    ArtifactOptions::new().state(ArtifactState::Deleted)
+                ),
+            ])
+            .await,
+        Err(PutError::NotFound { .. })
+    ));
+    let artifacts = store.get_artifacts().execute().await?;
+    assert_eq!(artifacts[0].state, ArtifactState::Live);
+    assert_eq!(artifacts[1].properties["p0"], PropertyValue::Int(1));
+
+    store.put_artifacts(Vec::new()).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn property_type_mismatch_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_execution_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+
+    assert!(matches!(
+        store
+            .post_execution(type_id)
+            .property("p0", "foo")
+            .execute()
+            .await,
+        Err(PostError::PropertyTypeMismatch {
+            property_name,
+            expected: PropertyType::Int,
+            actual: PropertyType::String,
+        }) if property_name == "p0"
+    ));
+    assert!(matches!(
+        store
+            .post_execution(type_id)
+            .property("p1", 1)
+            .execute()
+            .await,
+        Err(PostError::UndefinedProperty { property_name, .. }) if property_name == "p1"
+    ));
+
+    let execution_id = store
+        .post_execution(type_id)
+        .property("p0", 1)
+        .execute()
+        .await?;
+    assert!(matches!(
+        store
+            .put_execution(execution_id)
+            .property("p0", 1.5)
+            .execute()
+            .await,
+        Err(PutError::PropertyTypeMismatch {
+            item_id: Id::Execution(id),
+            property_name,
+            expected: PropertyType::Int,
+            actual: PropertyType::Double,
+        }) if id == execution_id && property_name == "p0"
+    ));
+    assert!(matches!(
+        store
+            .put_execution(execution_id)
+            .property("p1", 1)
+            .execute()
+            .await,
+        Err(PutError::UndefinedProperty { property_name, .. }) if property_name == "p1"
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_names_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let long_name = "a".repeat(256);
+
+    // Types.
+    for name in ["", &long_name] {
+        assert!(matches!(
+            store.put_artifact_type(name).execute().await,
+            Err(PutError::InvalidName { .. })
+        ));
+        assert!(matches!(
+            store
+                .put_execution_type("t")
+                .property(name, PropertyType::Int)
+                .execute()
+                .await,
+            Err(PutError::InvalidPropertyName { .. })
+        ));
+    }
+    let type_id = store
+        .put_artifact_type(&"a".repeat(255))
+        .property("p", PropertyType::Int)
+        .execute()
+        .await?;
+
+    // Post items.
+    for name in ["", &long_name] {
+        assert!(matches!(
+            store.post_artifact(type_id).name(name).execute().await,
+            Err(PostError::InvalidName { name: n }) if n == name
+        ));
+        assert!(matches!(
+            store
+                .post_artifact(type_id)
+                .custom_property(name, 1)
+                .execute()
+                .await,
+            Err(PostError::InvalidPropertyName { name: n }) if n == name
+        ));
+    }
+    let artifact_id = store
+        .post_artifact(type_id)
+        .name(&"a".repeat(255))
+        .custom_property(&"a".repeat(255), 1)
+        .execute()
+        .await?;
+
+    // Put items.
+    for name in ["", &long_name] {
+        assert!(matches!(
+            store.put_artifact(artifact_id).name(name).execute().await,
+            Err(PutError::InvalidName { name: n }) if n == name
+        ));
+        assert!(matches!(
+            store
+                .put_artifact(artifact_id)
+                .property(name, 1)
+                .execute()
+                .await,
+            Err(PutError::InvalidPropertyName { name: n }) if n == name
+        ));
+    }
+    assert_eq!(store.get_artifacts().execute().await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_artifacts_order_by_last_event_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+    let a2 = store.post_artifact(artifact_type_id).execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    for (execution_id, artifact_id, time) in [(e0, a0, 100), (e0, a1, 200), (e1, a0, 300)] {
+        store.put_event(execution_id, artifact_id).execute().await?;
+        sqlx::query(
+            "UPDATE Event SET milliseconds_since_epoch = ? WHERE artifact_id = ? AND execution_id = ?",
+        )
+        .bind(time as i64)
+        .bind(artifact_id.get())
+        .bind(execution_id.get())
+        .execute(&store.pool)
+        .await?;
+    }
+
+    // a0: 300, a1: 200, a2: no events.
+    let ids = |artifacts: Vec<Artifact>| artifacts.into_iter().map(|a| a.id).collect::<Vec<_>>();
+    let artifacts = store
+        .get_artifacts()
+        .order_by_last_event(false)
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), [a0, a1, a2]);
+
+    let artifacts = store
+        .get_artifacts()
+        .order_by_last_event(true)
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), [a1, a0, a2]);
+
+    let artifacts = store
+        .get_artifacts()
+        .order_by_last_event(false)
+        .limit(1)
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), [a0]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_context_contents_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let (artifacts, executions, events) = store.get_context_contents(ContextId::new(1)).await?;
+    assert_eq!(artifacts, vec![artifact1()]);
+    assert_eq!(executions, vec![execution0()]);
+    assert_eq!(events, vec![event1()]);
+
+    let context_id = store
+        .post_context(TypeId::new(13), "exp2")
+        .execute()
+        .await?;
+    store
+        .put_association(context_id, ExecutionId::new(1))
+        .execute()
+        .await?;
+    let (artifacts, executions, events) = store.get_context_contents(context_id).await?;
+    assert!(artifacts.is_empty());
+    assert_eq!(executions, vec![execution0()]);
+    assert!(events.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_lineage_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // a0 -> e0 -> a1 -> e1 -> a0 (cycle), and an unrelated a2 -> e2.
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let a0 = store.post_artifact(artifact_type_id).execute().await?;
+    let a1 = store.post_artifact(artifact_type_id).execute().await?;
+    let a2 = store.post_artifact(artifact_type_id).execute().await?;
+    let e0 = store.post_execution(execution_type_id).execute().await?;
+    let e1 = store.post_execution(execution_type_id).execute().await?;
+    let e2 = store.post_execution(execution_type_id).execute().await?;
+    for (execution_id, artifact_id, ty) in [
+        (e0, a0, EventType::Input),
+        (e0, a1, EventType::Output),
+        (e1, a1, EventType::Input),
+        (e1, a0, EventType::Output),
+        (e2, a2, EventType::Input),
+    ] {
+        store
+            .put_event(execution_id, artifact_id)
+            .ty(ty)
+            .execute()
+            .await?;
+    }
+
+    fn ids(lineage: &Lineage) -> (Vec<ArtifactId>, Vec<ExecutionId>, usize) {
+        (
+            lineage.artifacts.iter().map(|a| a.id).collect(),
+            lineage.executions.iter().map(|e| e.id).collect(),
+            lineage.events.len(),
+        )
+    }
+
+    let lineage = store.get_lineage(Id::Artifact(a0), 0).await?;
+    assert_eq!(ids(&lineage), (vec![a0], vec![], 0));
+
+    let lineage = store.get_lineage(Id::Artifact(a0), 1).await?;
+    assert_eq!(ids(&lineage), (vec![a0], vec![e0, e1], 2));
+
+    let lineage = store.get_lineage(Id::Artifact(a0), 100).await?;
+    assert_eq!(ids(&lineage), (vec![a0, a1], vec![e0, e1], 4));
+
+    let lineage = store.get_lineage(Id::Execution(e2), 100).await?;
+    assert_eq!(ids(&lineage), (vec![a2], vec![e2], 1));
+
+    let context_type_id = store.put_context_type("c").execute().await?;
+    let context_id = store.post_context(context_type_id, "c0").execute().await?;
+    store.put_association(context_id, e0).execute().await?;
+    let lineage = store.get_lineage(Id::Context(context_id), 1).await?;
+    assert_eq!(ids(&lineage), (vec![a0, a1], vec![e0], 2));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_custom_property_names_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let t0 = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let t1 = store.put_artifact_type("t1").execute().await?;
+    store
+        .post_artifact(t0)
+        .property("p0", 1)
+        .custom_property("foo", 1)
+        .custom_property("bar", "a")
+        .execute()
+        .await?;
+    store
+        .post_artifact(t0)
+        .custom_property("foo", 2)
+        .custom_property("baz", 1.5)
+        .execute()
+        .await?;
+    store
+        .post_artifact(t1)
+        .custom_property("qux", 1)
+        .execute()
+        .await?;
+
+    let names = store
+        .get_custom_property_names(TypeKind::Artifact, t0)
+        .await?;
+    assert_eq!(
+        names.into_iter().collect::<Vec<_>>(),
+        vec!["bar".to_owned(), "baz".to_owned(), "foo".to_owned()]
+    );
+    let names = store
+        .get_custom_property_names(TypeKind::Execution, t0)
+        .await?;
+    assert!(names.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inconsistent_event_path_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // An index step without `step_index`.
+    sqlx::query("INSERT INTO EventPath (event_id, is_index_step, step_key) VALUES (2, 1, 'foo')")
+        .execute(&store.pool)
+        .await?;
+    match store.get_events().execute().await {
+        Err(GetError::Db(sqlx::Error::Decode(e))) => {
+            assert!(e.to_string().contains("the event 2 "), "{}", e);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_items_by_type_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let counts = store.count_items_by_type(TypeKind::Artifact).await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(TypeId::new(10), 1), (TypeId::new(11), 1)]
+    );
+    let counts = store.count_items_by_type(TypeKind::Execution).await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(TypeId::new(12), 1)]
+    );
+    let counts = store.count_items_by_type(TypeKind::Context).await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(TypeId::new(13), 1)]
+    );
+
+    store.post_artifact(TypeId::new(10)).execute().await?;
+    let counts = store.count_items_by_type(TypeKind::Artifact).await?;
+    assert_eq!(counts[&TypeId::new(10)], 2);
+
+    Ok(())
+}
+
+#[test]
+fn property_value_from_str_works() {
+    let parse = |s: &str| s.parse::<PropertyValue>().unwrap();
+    assert_eq!(parse("1"), PropertyValue::Int(1));
+    assert_eq!(parse("-1"), PropertyValue::Int(-1));
+    assert_eq!(parse("1.5"), PropertyValue::Double(1.5));
+    assert_eq!(parse("1e3"), PropertyValue::Double(1000.0));
+    assert_eq!(parse("hello"), PropertyValue::String("hello".to_owned()));
+    assert_eq!(parse("\"1\""), PropertyValue::String("1".to_owned()));
+    assert_eq!(parse("\"\""), PropertyValue::String("".to_owned()));
+    assert_eq!(parse("\""), PropertyValue::String("\"".to_owned()));
+    assert_eq!(parse(""), PropertyValue::String("".to_owned()));
+
+    for v in [
+        PropertyValue::Int(1),
+        PropertyValue::Double(1.0),
+        PropertyValue::Double(-0.25),
+        PropertyValue::String("hello".to_owned()),
+        PropertyValue::String("1".to_owned()),
+        PropertyValue::String("1.5".to_owned()),
+        PropertyValue::String("\"quoted\"".to_owned()),
+    ] {
+        assert_eq!(parse(&v.to_string()), v);
+    }
+    assert_eq!(PropertyValue::Double(1.0).to_string(), "1.0");
+    assert_eq!(PropertyValue::String("1".to_owned()).to_string(), "\"1\"");
+    assert_eq!(
+        PropertyValue::Bytes(b"a\n".to_vec()).to_string(),
+        "b\"a\\n\""
+    );
+    assert_eq!(PropertyValue::Bool(true).to_string(), "true");
+}
+
+#[test]
+fn str_names_works() {
+    for v in 0..=6 {
+        let ty = EventType::from_i32(v).unwrap();
+        assert_eq!(EventType::from_str_name(ty.as_str_name()), Some(ty));
+
+        let state = ExecutionState::from_i32(v).unwrap();
+        assert_eq!(
+            ExecutionState::from_str_name(state.as_str_name()),
+            Some(state)
+        );
+    }
+    for v in 0..=4 {
+        let state = ArtifactState::from_i32(v).unwrap();
+        assert_eq!(
+            ArtifactState::from_str_name(state.as_str_name()),
+            Some(state)
+        );
+    }
+
+    for ty in [
+        PropertyType::Int,
+        PropertyType::Double,
+        PropertyType::String,
+        PropertyType::Bytes,
+        PropertyType::Bool,
+    ] {
+        assert_eq!(PropertyType::from_str_name(ty.as_str_name()), Some(ty));
+    }
+
+    assert_eq!(EventType::DeclaredOutput.as_str_name(), "DECLARED_OUTPUT");
+    assert_eq!(
+        ArtifactState::MarkedForDeletion.as_str_name(),
+        "MARKED_FOR_DELETION"
+    );
+    assert_eq!(ExecutionState::Complete.as_str_name(), "COMPLETE");
+    assert_eq!(EventType::from_str_name("DeclaredOutput"), None);
+    assert_eq!(ArtifactState::from_str_name("FOO"), None);
+    assert_eq!(ExecutionState::from_str_name(""), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn event_type_counts_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert!(store.event_type_counts().await?.is_empty());
+
+    let artifact_type_id = store.put_artifact_type("a").execute().await?;
+    let execution_type_id = store.put_execution_type("e").execute().await?;
+    let execution_id = store.post_execution(execution_type_id).execute().await?;
+    for ty in [EventType::Input, EventType::Output, EventType::Input] {
+        let artifact_id = store.post_artifact(artifact_type_id).execute().await?;
+        store
+            .put_event(execution_id, artifact_id)
+            .ty(ty)
+            .execute()
+            .await?;
+    }
+
+    let counts = store.event_type_counts().await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(EventType::Input, 2), (EventType::Output, 1)]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_items_by_state_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    assert!(store.count_artifacts_by_state(None).await?.is_empty());
+    assert!(store.count_executions_by_state(None).await?.is_empty());
+
+    let t0 = store.put_artifact_type("t0").execute().await?;
+    let t1 = store.put_artifact_type("t1").execute().await?;
+    for (type_id, state) in [
+        (t0, Some(ArtifactState::Live)),
+        (t0, Some(ArtifactState::Live)),
+        (t0, None),
+        (t1, Some(ArtifactState::Deleted)),
+        (t1, Some(ArtifactState::Unknown)),
+    ] {
+        let mut request = store.post_artifact(type_id);
+        if let Some(state) = state {
+            request = request.state(state);
+        }
+        request.execute().await?;
+    }
+
+    // Artifacts without states are counted as `Unknown`.
+    let counts = store.count_artifacts_by_state(None).await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![
+            (ArtifactState::Unknown, 2),
+            (ArtifactState::Live, 2),
+            (ArtifactState::Deleted, 1)
+        ]
+    );
+
+    let counts = store.count_artifacts_by_state(Some("t0")).await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(ArtifactState::Unknown, 1), (ArtifactState::Live, 2)]
+    );
+    assert!(store.count_artifacts_by_state(Some("t2")).await?.is_empty());
+
+    let t2 = store.put_execution_type("t2").execute().await?;
+    for state in [
+        ExecutionState::Complete,
+        ExecutionState::Failed,
+        ExecutionState::Complete,
+    ] {
+        store.post_execution(t2).state(state).execute().await?;
+    }
+
+    let counts = store.count_executions_by_state(Some("t2")).await?;
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(ExecutionState::Complete, 2), (ExecutionState::Failed, 1)]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn snapshot_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let snapshot = store.snapshot().await?;
+    assert_eq!(snapshot.artifact_types().len(), 6);
+    assert_eq!(snapshot.events(), [event0(), event1()]);
+
+    let artifacts = store.get_artifacts().ty("DataSet").execute().await?;
+    assert_eq!(
+        snapshot
+            .artifacts_by_type("DataSet")
+            .cloned()
+            .collect::<Vec<_>>(),
+        artifacts
+    );
+
+    let artifacts = store
+        .get_artifacts()
+        .uri("path/to/model/file")
+        .execute()
+        .await?;
+    assert_eq!(
+        snapshot
+            .artifacts_by_uri("path/to/model/file")
+            .cloned()
+            .collect::<Vec<_>>(),
+        artifacts
+    );
+
+    let value = PropertyValue::String("COMPLETED".to_owned());
+    assert_eq!(
+        snapshot
+            .executions_by_property("state", &value)
+            .cloned()
+            .collect::<Vec<_>>(),
+        vec![execution0()]
+    );
+    assert_eq!(
+        snapshot
+            .events_by_artifact(ArtifactId::new(2))
+            .cloned()
+            .collect::<Vec<_>>(),
+        store
+            .get_events()
+            .artifact(ArtifactId::new(2))
+            .execute()
+            .await?
+    );
+
+    Ok(())
+}
+
+fn sqlite_uri(path: impl AsRef<std::path::Path>) -> String {
+    format!(
+        "sqlite://{}",
+        path.as_ref()
+            .to_str()
+            .ok_or_else(|| format!("invalid path: {:?}", path.as_ref()))
+            .unwrap()
+    )
+}
+
+fn existing_db() -> NamedTempFile {
+    let mut file = NamedTempFile::new().expect("cannot create a temporary file");
+    std::io::copy(
+        &mut std::fs::File::open("tests/test.db").expect("cannot open 'tests/test.db'"),
+        &mut file,
+    )
+    .expect("cannot copy the existing database file");
+    file
+}
+
+fn artifact0() -> Artifact {
+    Artifact {
+        id: ArtifactId::new(1),
+        type_id: TypeId::new(1),
+        name: None,
+        uri: Some("path/to/data".to_owned()),
+        properties: vec![
+            ("day".to_owned(), PropertyValue::Int(1)),
+            (
+                "split".to_owned(),
+                PropertyValue::String("train".to_owned()),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+        custom_properties: BTreeMap::new(),
+        state: ArtifactState::Unknown,
+        create_time_since_epoch: Duration::from_millis(1648979124872),
+        last_update_time_since_epoch: Duration::from_millis(1648979124872),
+    }
+}
+
+fn artifact1() -> Artifact {
+    Artifact {
+        id: ArtifactId::new(2),
+        type_id: TypeId::new(11),
+        name: None,
+        uri: Some("path/to/model/file".to_owned()),
+        properties: vec![
+            (
+                "name".to_owned(),
+                PropertyValue::String("MNIST-v1".to_owned()),
+            ),
+            ("version".to_owned(), PropertyValue::Int(1)),
+        ]
+        .into_iter()
+        .collect(),
+        custom_properties: BTreeMap::new(),
+        state: ArtifactState::Unknown,
+        create_time_since_epoch: Duration::from_millis(1648979124885),
+        last_update_time_since_epoch: Duration::from_millis(1648979124885),
+    }
+}
+
+fn execution0() -> Execution {
+    Execution {
+        id: ExecutionId::new(1),
+        type_id: TypeId::new(12),
+        name: None,
+        last_known_state: ExecutionState::Unknown,
+        properties: vec![(
+            "state".to_owned(),
+            PropertyValue::String("COMPLETED".to_owned()),
+        )]
+        .into_iter()
+        .collect(),
+        custom_properties: BTreeMap::new(),
+        create_time_since_epoch: Duration::from_millis(1648979124878),
+        last_update_time_since_epoch: Duration::from_millis(1648979124891),
+    }
+}
+
+fn context0() -> Context {
+    Context {
+        id: ContextId::new(1),
+        type_id: TypeId::new(13),
+        name: "exp1".to_owned(),
+        properties: vec![(
+            "note".to_owned(),
+            PropertyValue::String("My first experiment.".to_owned()),
+        )]
+        .into_iter()
+        .collect(),
+        custom_properties: BTreeMap::new(),
+        create_time_since_epoch: Duration::from_millis(1648979124896),
+        last_update_time_since_epoch: Duration::from_millis(1648979124896),
+    }
+}
+
+fn event0() -> Event {
+    Event {
+        artifact_id: ArtifactId::new(1),
+        execution_id: ExecutionId::new(1),
+        path: Vec::new(),
+        ty: EventType::DeclaredInput,
+        create_time_since_epoch: Duration::from_millis(1648979124882),
+    }
+}
+
+fn event1() -> Event {
+    Event {
+        artifact_id: ArtifactId::new(2),
+        execution_id: ExecutionId::new(1),
+        path: Vec::new(),
+        ty: EventType::DeclaredOutput,
+        create_time_since_epoch: Duration::from_millis(1648979124888),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn property_range_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("accuracy", PropertyType::Double)
+        .property("epoch", PropertyType::Int)
+        .execute()
+        .await?;
+    let a0 = store
+        .post_artifact(type_id)
+        .property("accuracy", 0.5)
+        .property("epoch", 1)
+        .execute()
+        .await?;
+    let a1 = store
+        .post_artifact(type_id)
+        .property("accuracy", 0.8)
+        .property("epoch", 2)
+        .execute()
+        .await?;
+    let a2 = store
+        .post_artifact(type_id)
+        .property("epoch", 3)
+        .custom_property("accuracy", 0.9)
+        .execute()
+        .await?;
+
+    let ids = |artifacts: Vec<Artifact>| artifacts.into_iter().map(|x| x.id).collect::<Vec<_>>();
+    let artifacts = store
+        .get_artifacts()
+        .property_range("accuracy", 0.5..)
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), vec![a0, a1]);
+    let artifacts = store
+        .get_artifacts()
+        .property_range("accuracy", 0.5..0.8)
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), vec![a0]);
+    let artifacts = store
+        .get_artifacts()
+        .property_range("accuracy", (Bound::Excluded(0.5), Bound::Included(0.8)))
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), vec![a1]);
+    let artifacts = store
+        .get_artifacts()
+        .int_property_range("epoch", 2..=3)
+        .execute()
+        .await?;
+    assert_eq!(ids(artifacts), vec![a1, a2]);
+
+    let request = || {
+        store
+            .get_artifacts()
+            .int_property_range("epoch", ..3)
+            .property_range("accuracy", 0.6..)
+    };
+    assert_eq!(ids(request().execute().await?), vec![a1]);
+    assert_eq!(request().count().await?, 1);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_type_names_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let names = store.get_type_names(TypeKind::Artifact).await?;
+    assert_eq!(
+        names,
+        [
+            "DataSet",
+            "SavedModel",
+            "mlmd.Dataset",
+            "mlmd.Metrics",
+            "mlmd.Model",
+            "mlmd.Statistics"
+        ]
+    );
+    let names = store.get_type_names(TypeKind::Execution).await?;
+    assert_eq!(
+        names,
+        [
+            "Trainer",
+            "mlmd.Deploy",
+            "mlmd.Evaluate",
+            "mlmd.Process",
+            "mlmd.Train",
+            "mlmd.Transform"
+        ]
+    );
+    let names = store.get_type_names(TypeKind::Context).await?;
+    assert_eq!(names, ["Experiment"]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn page_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifact_type_id = store.put_artifact_type("t0").execute().await?;
+    let execution_type_id = store.put_execution_type("t1").execute().await?;
+    let context_type_id = store.put_context_type("t2").execute().await?;
+    let mut artifacts = Vec::new();
+    let mut executions = Vec::new();
+    let mut contexts = Vec::new();
+    for i in 0..5 {
+        artifacts.push(store.post_artifact(artifact_type_id).execute().await?);
+        executions.push(store.post_execution(execution_type_id).execute().await?);
+        contexts.push(
+            store
+                .post_context(context_type_id, &format!("c{}", i))
+                .execute()
+                .await?,
+        );
+    }
+    for (&e, &a) in executions.iter().zip(artifacts.iter()) {
+        store.put_event(e, a).execute().await?;
+    }
+
+    for (page_index, expected) in [
+        (0, &artifacts[0..2]),
+        (1, &artifacts[2..4]),
+        (2, &artifacts[4..]),
+        (3, &[][..]),
+    ] {
+        let result = store.get_artifacts().page(page_index, 2).execute().await?;
+        assert_eq!(
+            result.into_iter().map(|x| x.id).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    let result = store.get_executions().page(1, 3).execute().await?;
+    assert_eq!(
+        result.into_iter().map(|x| x.id).collect::<Vec<_>>(),
+        &executions[3..]
+    );
+
+    let result = store.get_contexts().page(0, 3).execute().await?;
+    assert_eq!(
+        result.into_iter().map(|x| x.id).collect::<Vec<_>>(),
+        &contexts[..3]
+    );
+
+    let result = store.get_events().page(2, 2).execute().await?;
+    assert_eq!(
+        result
+            .into_iter()
+            .map(|x| x.artifact_id)
+            .collect::<Vec<_>>(),
+        &artifacts[4..]
+    );
+
+    // Generated SQL.
+    let sql = store.get_artifacts().page(2, 2).dry_run_sql().sql;
+    assert!(sql.ends_with(" LIMIT 2 OFFSET 4"), "{}", sql);
+    let sql = store.get_contexts().page(0, 3).dry_run_sql().sql;
+    assert!(sql.ends_with(" LIMIT 3 OFFSET 0"), "{}", sql);
+    let sql = store.get_events().page(1, 5).dry_run_sql().sql;
+    assert!(sql.ends_with(" LIMIT 5 OFFSET 5"), "{}", sql);
+
+    // The offset saturates instead of overflowing.
+    let sql = store.get_executions().page(usize::MAX, 2).dry_run_sql().sql;
+    assert!(
+        sql.ends_with(&format!(" LIMIT 2 OFFSET {}", usize::MAX)),
+        "{}",
+        sql
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_items_by_context_and_type_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let dataset_type_id = store.put_artifact_type("DataSet").execute().await?;
+    let model_type_id = store.put_artifact_type("SavedModel").execute().await?;
+    let trainer_type_id = store.put_execution_type("Trainer").execute().await?;
+    let context_type_id = store.put_context_type("Experiment").execute().await?;
+    let c0 = store.post_context(context_type_id, "c0").execute().await?;
+    let c1 = store.post_context(context_type_id, "c1").execute().await?;
+
+    let a0 = store.post_artifact(dataset_type_id).execute().await?;
+    let a1 = store.post_artifact(model_type_id).execute().await?;
+    let a2 = store.post_artifact(dataset_type_id).execute().await?;
+    let e0 = store.post_execution(trainer_type_id).execute().await?;
+    store.put_attribution(c0, a0).execute().await?;
+    store.put_attribution(c0, a1).execute().await?;
+    store.put_attribution(c1, a2).execute().await?;
+    store.put_association(c0, e0).execute().await?;
+
+    let request = || store.get_artifacts().context(c0).ty("DataSet");
+    let artifacts = request().execute().await?;
+    assert_eq!(
+        artifacts.into_iter().map(|x| x.id).collect::<Vec<_>>(),
+        vec![a0]
+    );
+    assert_eq!(request().count().await?, 1);
+
+    let artifacts = store
+        .get_artifacts()
+        .context(c1)
+        .ty("SavedModel")
+        .execute()
+        .await?;
+    assert!(artifacts.is_empty());
+
+    let executions = store
+        .get_executions()
+        .context(c0)
+        .ty("Trainer")
+        .execute()
+        .await?;
+    assert_eq!(
+        executions.into_iter().map(|x| x.id).collect::<Vec<_>>(),
+        vec![e0]
+    );
+    let executions = store
+        .get_executions()
+        .context(c1)
+        .ty("Trainer")
+        .execute()
+        .await?;
+    assert!(executions.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test(flavor = "multi_thread")]
+async fn export_json_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let parent_type_id = store.put_context_type("Project").execute().await?;
+    store
+        .put_parent_type(TypeId::new(13), parent_type_id)
+        .execute()
+        .await?;
+    let parent_context_id = store
+        .post_context(parent_type_id, "project1")
+        .execute()
+        .await?;
+    store
+        .put_parent_context(parent_context_id, ContextId::new(1))
+        .execute()
+        .await?;
+
+    let json: serde_json::Value = serde_json::from_str(&store.export_json().await?)?;
+    let names = |key: &str| {
+        json[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["name"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert!(names("artifactTypes").contains(&"DataSet".to_owned()));
+    assert!(names("executionTypes").contains(&"Trainer".to_owned()));
+    assert_eq!(names("contextTypes"), ["Experiment", "Project"]);
+
+    let data_set = json["artifactTypes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|x| x["name"] == "DataSet")
+        .unwrap();
+    assert_eq!(data_set["id"], "10");
+    assert_eq!(data_set["properties"]["day"], "INT");
+    assert_eq!(data_set["properties"]["split"], "STRING");
+
+    let artifact = &json["artifacts"][0];
+    assert_eq!(artifact["id"], "1");
+    assert_eq!(artifact["typeId"], "10");
+    assert_eq!(artifact["uri"], "path/to/data");
+    assert_eq!(artifact["properties"]["day"]["intValue"], "1");
+    assert_eq!(artifact["properties"]["split"]["stringValue"], "train");
+    assert_eq!(artifact["state"], "UNKNOWN");
+    assert_eq!(json["executions"][0]["lastKnownState"], "UNKNOWN");
+    assert_eq!(json["contexts"][0]["name"], "exp1");
+
+    let events = json["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["type"], "DECLARED_INPUT");
+    assert_eq!(events[1]["type"], "DECLARED_OUTPUT");
+    assert_eq!(
+        json["attributions"],
+        serde_json::json!([{"artifactId": "2", "contextId": "1"}])
+    );
+    assert_eq!(
+        json["associations"],
+        serde_json::json!([{"executionId": "1", "contextId": "1"}])
+    );
+    assert_eq!(
+        json["parentTypes"],
+        serde_json::json!([{"childId": "13", "parentId": parent_type_id.to_string()}])
+    );
+    assert_eq!(
+        json["parentContexts"],
+        serde_json::json!([{"childId": "1", "parentId": parent_context_id.to_string()}])
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test(flavor = "multi_thread")]
+async fn import_json_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    store
+        .put_event(ExecutionId::new(1), ArtifactId::new(2))
+        .ty(EventType::Output)
+        .step(EventStep::Key("model".to_owned()))
+        .step(EventStep::Index(0))
+        .execute()
+        .await?;
+    store
+        .put_artifact(ArtifactId::new(2))
+        .state(ArtifactState::Live)
+        .custom_property("bytes", b"\x00\xff".to_vec())
+        .custom_property("double", 0.5)
+        .execute()
+        .await?;
+    let project_type_id = store.put_context_type("Project").execute().await?;
+    store
+        .put_parent_type(TypeId::new(13), project_type_id)
+        .execute()
+        .await?;
+    let project_id = store
+        .post_context(project_type_id, "project1")
+        .execute()
+        .await?;
+    let run_id = store
+        .post_context(TypeId::new(13), "exp1-run1")
+        .execute()
+        .await?;
+    store
+        .put_parent_context(project_id, ContextId::new(1))
+        .execute()
+        .await?;
+    store
+        .put_parent_context(ContextId::new(1), run_id)
+        .execute()
+        .await?;
+    let exported = store.export_json().await?;
+
+    // Imports into an empty store.
+    let file = NamedTempFile::new()?;
+    let mut new_store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let summary = new_store.import_json(&exported).await?;
+    assert_eq!(
+        summary,
+        crate::ImportSummary {
+            types: 14,
+            artifacts: 2,
+            executions: 1,
+            contexts: 3,
+            events: 3,
+            attributions: 1,
+            associations: 1,
+            parent_types: 1,
+            parent_contexts: 2,
+        }
+    );
+
+    // Type IDs may differ, so they are replaced with the type names before comparing.
+    let normalize = |json: &str| -> anyhow::Result<serde_json::Value> {
+        let mut json: serde_json::Value = serde_json::from_str(json)?;
+        let mut names = HashMap::new();
+        for (types, items) in [
+            ("artifactTypes", "artifacts"),
+            ("executionTypes", "executions"),
+            ("contextTypes", "contexts"),
+        ] {
+            for ty in json[types].as_array_mut().unwrap() {
+                let id = ty.as_object_mut().unwrap().remove("id").unwrap();
+                names.insert(id, ty["name"].clone());
+            }
+            for item in json[items].as_array_mut().unwrap() {
+                item["typeId"] = names[&item["typeId"]].clone();
+            }
+        }
+        for x in json["parentTypes"].as_array_mut().unwrap() {
+            x["childId"] = names[&x["childId"]].clone();
+            x["parentId"] = names[&x["parentId"]].clone();
+        }
+        Ok(json)
+    };
+    let reexported = new_store.export_json().await?;
+    assert_eq!(normalize(&reexported)?, normalize(&exported)?);
+
+    // Importing the same dump again fails due to the duplicate context name and changes nothing.
+    assert!(matches!(
+        new_store.import_json(&exported).await,
+        Err(ImportError::Post(PostError::NameAlreadyExists { .. }))
+    ));
+    assert_eq!(new_store.get_artifacts().count().await?, 2);
+    assert!(matches!(
+        new_store.import_json("{").await,
+        Err(ImportError::Json(_))
+    ));
+
+    // Imports into a store that already has items.
+    let file = NamedTempFile::new()?;
+    let mut new_store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+    let type_id = new_store.put_artifact_type("Other").execute().await?;
+    new_store.post_artifact(type_id).execute().await?;
+    new_store.import_json(&exported).await?;
+
+    let artifacts = new_store
+        .get_artifacts()
+        .uri("path/to/model/file")
+        .execute()
+        .await?;
+    assert_eq!(artifacts.len(), 1);
+    assert_ne!(artifacts[0].id, ArtifactId::new(2));
+    assert_eq!(artifacts[0].state, ArtifactState::Live);
+    assert_eq!(
+        artifacts[0].custom_properties["bytes"],
+        PropertyValue::Bytes(b"\x00\xff".to_vec())
+    );
+    let events = new_store
+        .get_events()
+        .artifact(artifacts[0].id)
+        .execute()
+        .await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1].path,
+        [EventStep::Key("model".to_owned()), EventStep::Index(0)]
+    );
+    let contexts = new_store
+        .get_contexts()
+        .artifact(artifacts[0].id)
+        .execute()
+        .await?;
+    assert_eq!(contexts.len(), 1);
+    let parents = new_store
+        .get_contexts()
+        .child(contexts[0].id)
+        .execute()
+        .await?;
+    assert_eq!(parents.len(), 1);
+    assert_eq!(parents[0].name, "project1");
+    let children = new_store
+        .get_contexts()
+        .parent(contexts[0].id)
+        .execute()
+        .await?;
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].name, "exp1-run1");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_type_by_name_args_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // The number of the arguments must match the placeholders (MySQL rejects extra arguments).
+    for version in [None, Some("v1")] {
+        let (sql, args) = store
+            .query
+            .get_type_by_name(TypeKind::Artifact, "t0", version);
+        assert_eq!(sql.matches('?').count(), args.len(), "{}", sql);
+    }
+
+    let t0 = store.put_artifact_type("t0").execute().await?;
+    let t1 = store
+        .put_artifact_type("t0")
+        .version("v1")
+        .execute()
+        .await?;
+    assert_ne!(t0, t1);
+    assert_eq!(store.put_artifact_type("t0").execute().await?, t0);
+    assert_eq!(
+        store
+            .put_artifact_type("t0")
+            .version("v1")
+            .execute()
+            .await?,
+        t1
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_type_by_name_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let ty = store.get_artifact_type("DataSet").await?;
+    assert_eq!(ty.map(|ty| ty.id), Some(TypeId::new(10)));
+    let ty = store.get_execution_type("Trainer").await?;
+    assert_eq!(ty.map(|ty| ty.id), Some(TypeId::new(12)));
+    let ty = store.get_context_type("Experiment").await?;
+    assert_eq!(ty.map(|ty| ty.id), Some(TypeId::new(13)));
+
+    // Other kinds or undefined names.
+    assert_eq!(store.get_artifact_type("Trainer").await?, None);
+    assert_eq!(store.get_context_type("DataSet").await?, None);
+    assert_eq!(store.get_execution_type("foo").await?, None);
+
+    // Versioned types aren't returned.
+    store
+        .put_artifact_type("Model")
+        .version("v1")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    assert_eq!(store.get_artifact_type("Model").await?, None);
+    let type_id = store
+        .put_artifact_type("Model")
+        .property("p1", PropertyType::String)
+        .execute()
+        .await?;
+    let ty = store.get_artifact_type("Model").await?.expect("not found");
+    assert_eq!(ty.id, type_id);
+    assert_eq!(ty.properties.keys().collect::<Vec<_>>(), ["p1"]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn strict_custom_properties_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let type_id = store.put_artifact_type("t0").execute().await?;
+    let artifact_id = store
+        .post_artifact(type_id)
+        .custom_property("c0", 1)
+        .custom_property("c1", "foo")
+        .execute()
+        .await?;
+
+    // Flipping the type of a custom property is rejected.
+    assert!(matches!(
+        store
+            .put_artifact(artifact_id)
+            .custom_property("c0", "1")
+            .strict_custom_properties()
+            .execute()
+            .await,
+        Err(PutError::CustomPropertyTypeMismatch {
+            property_name,
+            expected: PropertyType::Int,
+            actual: PropertyType::String,
+            ..
+        }) if property_name == "c0"
+    ));
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(artifact.custom_properties["c0"], PropertyValue::Int(1));
+
+    // Values of the same type, bools for ints and new custom properties are accepted.
+    store
+        .put_artifact(artifact_id)
+        .custom_property("c0", true)
+        .custom_property("c1", "bar")
+        .custom_property("c2", 0.5)
+        .strict_custom_properties()
+        .execute()
+        .await?;
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(artifact.custom_properties["c0"], PropertyValue::Int(1));
+    assert_eq!(artifact.custom_properties["c1"], PropertyValue::from("bar"));
+    assert_eq!(artifact.custom_properties["c2"], PropertyValue::Double(0.5));
+
+    // Without the strict mode, the type can be changed.
+    store
+        .put_artifact(artifact_id)
+        .custom_property("c0", "1")
+        .execute()
+        .await?;
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(artifact.custom_properties["c0"], PropertyValue::from("1"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_properties_works() -> anyhow::Result<()> {
+    let file = existing_db();
+    let store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    let artifacts = store
+        .get_artifacts()
+        .only_properties(&["day", "name"])
+        .execute()
+        .await?;
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(artifacts[0].properties.keys().collect::<Vec<_>>(), ["day"]);
+    assert_eq!(artifacts[1].properties.keys().collect::<Vec<_>>(), ["name"]);
+
+    // The other fields are fetched as usual.
+    let mut expected = store
+        .get_artifact(ArtifactId::new(1))
+        .await?
+        .expect("not found");
+    expected.properties.remove("split");
+    assert_eq!(artifacts[0], expected);
+
+    let artifacts = store.get_artifacts().only_properties(&[]).execute().await?;
+    assert_eq!(artifacts.len(), 2);
+    assert!(artifacts.iter().all(|a| a.properties.is_empty()));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn remove_property_works() -> anyhow::Result<()> {
+    let file = NamedTempFile::new()?;
+    let mut store = MetadataStore::connect(&sqlite_uri(file.path())).await?;
+
+    // Artifacts.
+    let type_id = store
+        .put_artifact_type("t0")
+        .property("p0", PropertyType::Int)
+        .property("p1", PropertyType::String)
+        .execute()
+        .await?;
+    let artifact_id = store
+        .post_artifact(type_id)
+        .property("p0", 1)
+        .property("p1", "foo")
+        .custom_property("p0", 2)
+        .custom_property("c0", 3)
+        .execute()
+        .await?;
+    store
+        .put_artifact(artifact_id)
+        .remove_property("p0")
+        .remove_custom_property("c0")
+        .remove_custom_property("undefined")
+        .custom_property("c1", 4)
+        .execute()
+        .await?;
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(
+        artifact.properties.into_iter().collect::<Vec<_>>(),
+        [("p1".to_owned(), PropertyValue::from("foo"))]
+    );
+    assert_eq!(
+        artifact.custom_properties.into_iter().collect::<Vec<_>>(),
+        [
+            ("c1".to_owned(), PropertyValue::Int(4)),
+            ("p0".to_owned(), PropertyValue::Int(2))
+        ]
+    );
+
+    // Removing and setting the same property in a request results in setting it.
+    store
+        .put_artifact(artifact_id)
+        .remove_property("p1")
+        .property("p1", "bar")
+        .execute()
+        .await?;
+    let artifact = store.get_artifact(artifact_id).await?.expect("not found");
+    assert_eq!(artifact.properties["p1"], PropertyValue::from("bar"));
+
+    // Executions.
+    let type_id = store
+        .put_execution_type("t1")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let execution_id = store
+        .post_execution(type_id)
+        .property("p0", 1)
+        .custom_property("c0", 2)
+        .execute()
+        .await?;
+    store
+        .put_execution(execution_id)
+        .remove_property("p0")
+        .remove_custom_property("c0")
+        .execute()
+        .await?;
+    let execution = store.get_execution(execution_id).await?.expect("not found");
+    assert!(execution.properties.is_empty());
+    assert!(execution.custom_properties.is_empty());
+
+    // Contexts.
+    let type_id = store
+        .put_context_type("t2")
+        .property("p0", PropertyType::Int)
+        .execute()
+        .await?;
+    let context_id = store
+        .post_context(type_id, "c")
+        .property("p0", 1)
+        .custom_property("c0", 2)
+        .execute()
+        .await?;
+    store
+        .put_context(context_id)
+        .remove_custom_property("c0")
+        .execute()
+        .await?;
+    let context = store.get_context(context_id).await?.expect("not found");
+    assert_eq!(context.properties["p0"], PropertyValue::Int(1));
+    assert!(context.custom_properties.is_empty());
+
+    Ok(())
 }
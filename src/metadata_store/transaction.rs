@@ -0,0 +1,174 @@
+use super::MetadataStore;
+use crate::errors::PutError;
+use crate::metadata::{ArtifactId, ContextId, ExecutionId, TypeId};
+use crate::requests;
+use std::marker::PhantomData;
+
+/// Transaction.
+///
+/// A transaction is started by [`MetadataStore::transaction`].
+/// The requests issued via a transaction are not visible to others until [`Transaction::commit`] is called.
+/// If a transaction is dropped without being committed, the requests are rolled back.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    store: MetadataStore,
+    _parent: PhantomData<&'a mut MetadataStore>,
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn new(store: MetadataStore) -> Self {
+        Self {
+            store,
+            _parent: PhantomData,
+        }
+    }
+
+    pub(crate) fn store(&self) -> &MetadataStore {
+        &self.store
+    }
+
+    /// Commits the transaction.
+    pub async fn commit(mut self) -> Result<(), PutError> {
+        let transaction = self.store.transaction.take().expect("bug");
+        transaction.into_inner().commit().await?;
+
+        // Types may have been updated in this transaction.
+        if let Some(cache) = &self.store.type_cache {
+            cache.lock().expect("poisoned").clear();
+        }
+        Ok(())
+    }
+
+    /// Rolls back the transaction.
+    pub async fn rollback(mut self) -> Result<(), PutError> {
+        let transaction = self.store.transaction.take().expect("bug");
+        transaction.into_inner().rollback().await?;
+        Ok(())
+    }
+
+    /// Makes a request builder to put an artifact type.
+    pub fn put_artifact_type(&mut self, type_name: &str) -> requests::PutArtifactTypeRequest<'_> {
+        self.store.put_artifact_type(type_name)
+    }
+
+    /// Makes a request builder to put an execution type.
+    pub fn put_execution_type(&mut self, type_name: &str) -> requests::PutExecutionTypeRequest<'_> {
+        self.store.put_execution_type(type_name)
+    }
+
+    /// Makes a request builder to put a context type.
+    pub fn put_context_type(&mut self, type_name: &str) -> requests::PutContextTypeRequest<'_> {
+        self.store.put_context_type(type_name)
+    }
+
+    /// Makes a request builder to create a new parent-child relationship between types.
+    pub fn put_parent_type(
+        &mut self,
+        child_type_id: TypeId,
+        parent_type_id: TypeId,
+    ) -> requests::PutParentTypeRequest<'_> {
+        self.store.put_parent_type(child_type_id, parent_type_id)
+    }
+
+    /// Makes a request builder to create a new artifact.
+    pub fn post_artifact(&mut self, type_id: TypeId) -> requests::PostArtifactRequest<'_> {
+        self.store.post_artifact(type_id)
+    }
+
+    /// Makes a request builder to update an artifact.
+    pub fn put_artifact(&mut self, artifact_id: ArtifactId) -> requests::PutArtifactRequest<'_> {
+        self.store.put_artifact(artifact_id)
+    }
+
+    /// Makes a request builder to get artifacts.
+    ///
+    /// The changes made in this transaction are visible to the request.
+    pub fn get_artifacts(&self) -> requests::GetArtifactsRequest<'_> {
+        self.store.get_artifacts()
+    }
+
+    /// Makes a request builder to create a new execution.
+    pub fn post_execution(&mut self, type_id: TypeId) -> requests::PostExecutionRequest<'_> {
+        self.store.post_execution(type_id)
+    }
+
+    /// Makes a request builder to update an execution.
+    pub fn put_execution(
+        &mut self,
+        execution_id: ExecutionId,
+    ) -> requests::PutExecutionRequest<'_> {
+        self.store.put_execution(execution_id)
+    }
+
+    /// Makes a request builder to get executions.
+    ///
+    /// The changes made in this transaction are visible to the request.
+    pub fn get_executions(&self) -> requests::GetExecutionsRequest<'_> {
+        self.store.get_executions()
+    }
+
+    /// Makes a request builder to create a new context.
+    pub fn post_context(
+        &mut self,
+        type_id: TypeId,
+        context_name: &str,
+    ) -> requests::PostContextRequest<'_> {
+        self.store.post_context(type_id, context_name)
+    }
+
+    /// Makes a request builder to update a context.
+    pub fn put_context(&mut self, context_id: ContextId) -> requests::PutContextRequest<'_> {
+        self.store.put_context(context_id)
+    }
+
+    /// Makes a request builder to get contexts.
+    ///
+    /// The changes made in this transaction are visible to the request.
+    pub fn get_contexts(&self) -> requests::GetContextsRequest<'_> {
+        self.store.get_contexts()
+    }
+
+    /// Makes a request builder to create a new attribution.
+    pub fn put_attribution(
+        &mut self,
+        context_id: ContextId,
+        artifact_id: ArtifactId,
+    ) -> requests::PutAttributionRequest<'_> {
+        self.store.put_attribution(context_id, artifact_id)
+    }
+
+    /// Makes a request builder to create a new association.
+    pub fn put_association(
+        &mut self,
+        context_id: ContextId,
+        execution_id: ExecutionId,
+    ) -> requests::PutAssociationRequest<'_> {
+        self.store.put_association(context_id, execution_id)
+    }
+
+    /// Makes a request builder to create a new parent-child relationship between contexts.
+    pub fn put_parent_context(
+        &mut self,
+        parent_context_id: ContextId,
+        child_context_id: ContextId,
+    ) -> requests::PutParentContextRequest<'_> {
+        self.store
+            .put_parent_context(parent_context_id, child_context_id)
+    }
+
+    /// Makes a request builder to create a new event.
+    pub fn put_event(
+        &mut self,
+        execution_id: ExecutionId,
+        artifact_id: ArtifactId,
+    ) -> requests::PutEventRequest<'_> {
+        self.store.put_event(execution_id, artifact_id)
+    }
+
+    /// Makes a request builder to get events.
+    ///
+    /// The changes made in this transaction are visible to the request.
+    pub fn get_events(&self) -> requests::GetEventsRequest<'_> {
+        self.store.get_events()
+    }
+}
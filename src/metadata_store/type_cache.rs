@@ -0,0 +1,54 @@
+use crate::metadata::{PropertyTypes, TypeId, TypeKind};
+use std::collections::HashMap;
+
+/// LRU cache of the property definitions of types.
+#[derive(Debug)]
+pub struct TypeCache {
+    capacity: usize,
+    entries: HashMap<(TypeKind, TypeId), (PropertyTypes, u64)>,
+    clock: u64,
+}
+
+impl TypeCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    pub fn get(&mut self, type_kind: TypeKind, type_id: TypeId) -> Option<PropertyTypes> {
+        self.clock += 1;
+        let (properties, last_used) = self.entries.get_mut(&(type_kind, type_id))?;
+        *last_used = self.clock;
+        Some(properties.clone())
+    }
+
+    pub fn insert(&mut self, type_kind: TypeKind, type_id: TypeId, properties: PropertyTypes) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&(type_kind, type_id))
+        {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| *key)
+                .expect("unreachable");
+            self.entries.remove(&lru);
+        }
+        self.entries
+            .insert((type_kind, type_id), (properties, self.clock));
+    }
+
+    pub fn remove(&mut self, type_kind: TypeKind, type_id: TypeId) {
+        self.entries.remove(&(type_kind, type_id));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -1,10 +1,10 @@
 // https://github.com/google/ml-metadata/blob/v0.26.0/ml_metadata/util/metadata_source_query_config.cc
-use crate::metadata::{EventStep, Id, PropertyValue, TypeId, TypeKind};
+use crate::metadata::{EventStep, EventType, Id, PropertyType, PropertyValue, TypeId, TypeKind};
 use crate::metadata_store::options::{
     GetArtifactsOptions, GetContextsOptions, GetEventsOptions, GetExecutionsOptions,
     GetItemsOptions, GetTypesOptions, ItemOptions,
 };
-use sqlx::any::AnyArguments;
+use sqlx::any::{Any, AnyArguments};
 use sqlx::Arguments as _;
 use std::ops::Bound;
 use std::time::UNIX_EPOCH;
@@ -45,16 +45,80 @@ impl Query {
         }
     }
 
-    pub fn get_type_id(&self, item_id: Id) -> (String, AnyArguments) {
+    pub fn insert_or_ignore_parent_type(&self) -> &'static str {
+        match self {
+            Self::Sqlite(x) => x.insert_parent_type(),
+            Self::Mysql(x) => x.insert_parent_type(),
+        }
+    }
+
+    pub fn insert_or_ignore_parent_context(&self) -> &'static str {
+        match self {
+            Self::Sqlite(x) => x.insert_parent_context(),
+            Self::Mysql(x) => x.insert_parent_context(),
+        }
+    }
+
+    pub fn get_type_id(&self, item_id: Id) -> (String, QueryArgs<'_>) {
         let sql = format!(
             "SELECT type_id FROM {} WHERE id = ?",
             item_id.kind().item_table_name(),
         );
-        let mut args = sqlx::any::AnyArguments::default();
+        let mut args = QueryArgs::default();
         args.add(item_id.get());
         (sql, args)
     }
 
+    pub fn acquire_initialization_lock(&self) -> Option<&'static str> {
+        match self {
+            Self::Sqlite(_) => None,
+            Self::Mysql(_) => Some("SELECT GET_LOCK('mlmd_initialize_database', -1)"),
+        }
+    }
+
+    pub fn release_initialization_lock(&self) -> Option<&'static str> {
+        match self {
+            Self::Sqlite(_) => None,
+            Self::Mysql(_) => Some("SELECT RELEASE_LOCK('mlmd_initialize_database')"),
+        }
+    }
+
+    pub fn create_indices(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Sqlite(x) => x.create_indices(),
+            Self::Mysql(x) => x.create_indices(),
+        }
+    }
+
+    pub fn migration_steps(&self) -> &'static [MigrationStep] {
+        match self {
+            Self::Sqlite(x) => x.migration_steps(),
+            Self::Mysql(x) => x.migration_steps(),
+        }
+    }
+
+    pub fn schema_repairs(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Sqlite(x) => x.schema_repairs(),
+            Self::Mysql(x) => x.schema_repairs(),
+        }
+    }
+
+    pub fn add_event_unique_constraint(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Sqlite(x) => x.add_event_unique_constraint(),
+            Self::Mysql(x) => x.add_event_unique_constraint(),
+        }
+    }
+
+    pub fn update_schema_version(&self) -> &'static str {
+        "UPDATE MLMDEnv SET schema_version = ?"
+    }
+
+    pub fn ping(&self) -> &'static str {
+        "SELECT 1"
+    }
+
     pub fn select_schema_version(&self) -> &'static str {
         "SELECT schema_version FROM MLMDEnv"
     }
@@ -67,22 +131,64 @@ impl Query {
     }
 
     pub fn get_types(&self, options: &GetTypesOptions) -> String {
-        let mut query = "SELECT id, name FROM Type WHERE type_kind=? ".to_owned();
+        let mut query =
+            "SELECT id, name, version, description, input_type, output_type FROM Type WHERE type_kind=? ".to_owned();
         if options.name.is_some() {
             query += "AND name = ? ";
         }
+        if options.name_pattern.is_some() {
+            query += "AND name LIKE ? ";
+        }
         if !options.ids.is_empty() {
-            query += &format!("AND id IN ({})", params(options.ids.len()));
+            query += &format!("AND id IN ({}) ", params(options.ids.len()));
+        }
+        if options.child_type_id.is_some() {
+            query += "AND id IN (SELECT parent_type_id FROM ParentType WHERE type_id = ?) ";
         }
         query
     }
 
+    pub fn get_type_kind(&self) -> &'static str {
+        "SELECT type_kind FROM Type WHERE id=?"
+    }
+
+    pub fn get_type_names(&self) -> &'static str {
+        "SELECT name FROM Type WHERE type_kind=? ORDER BY name"
+    }
+
     pub fn get_type_properties(&self) -> &'static str {
         "SELECT type_id, name, data_type FROM TypeProperty"
     }
 
-    pub fn get_type_by_name(&self) -> &'static str {
-        "SELECT id, name FROM Type WHERE type_kind=? AND name=?"
+    pub fn get_type_by_name<'a>(
+        &self,
+        type_kind: TypeKind,
+        type_name: &'a str,
+        version: Option<&'a str>,
+    ) -> (&'static str, QueryArgs<'a>) {
+        let mut args = QueryArgs::default();
+        args.add(type_kind as i32);
+        args.add(type_name);
+        if let Some(v) = version {
+            args.add(v);
+            ("SELECT id, name, version, description, input_type, output_type FROM Type WHERE type_kind=? AND name=? AND version=?", args)
+        } else {
+            ("SELECT id, name, version, description, input_type, output_type FROM Type WHERE type_kind=? AND name=? AND version IS NULL", args)
+        }
+    }
+
+    pub fn get_type_properties_by_type_ids(&self, n: usize) -> String {
+        format!(
+            "SELECT type_id, name, data_type FROM TypeProperty WHERE type_id IN ({})",
+            params(n)
+        )
+    }
+
+    pub fn get_type_ids_after(&self, limit: usize) -> String {
+        format!(
+            "SELECT id FROM Type WHERE type_kind=? AND id > ? ORDER BY id LIMIT {}",
+            limit
+        )
     }
 
     pub fn get_type_properties_by_type_id(&self) -> &'static str {
@@ -90,7 +196,20 @@ impl Query {
     }
 
     pub fn insert_type(&self) -> &'static str {
-        "INSERT INTO Type (type_kind, name) VALUES (?, ?)"
+        "INSERT INTO Type (type_kind, name, version, description, input_type, output_type) \
+         VALUES (?, ?, ?, ?, ?, ?)"
+    }
+
+    pub fn update_type_description(&self) -> &'static str {
+        "UPDATE Type SET description=? WHERE id=?"
+    }
+
+    pub fn update_type_input_type(&self) -> &'static str {
+        "UPDATE Type SET input_type=? WHERE id=?"
+    }
+
+    pub fn update_type_output_type(&self) -> &'static str {
+        "UPDATE Type SET output_type=? WHERE id=?"
     }
 
     pub fn insert_type_property(&self) -> &'static str {
@@ -109,7 +228,17 @@ impl Query {
         "SELECT count(*) FROM Execution WHERE id=?"
     }
 
-    pub fn insert_item(&self, type_id: TypeId, options: &ItemOptions) -> (String, AnyArguments) {
+    pub fn delete_artifact(&self) -> &'static [&'static str] {
+        &[
+            "DELETE FROM EventPath WHERE event_id IN (SELECT id FROM Event WHERE artifact_id=?)",
+            "DELETE FROM Event WHERE artifact_id=?",
+            "DELETE FROM Attribution WHERE artifact_id=?",
+            "DELETE FROM ArtifactProperty WHERE artifact_id=?",
+            "DELETE FROM Artifact WHERE id=?",
+        ]
+    }
+
+    pub fn insert_item(&self, type_id: TypeId, options: &ItemOptions) -> (String, QueryArgs<'_>) {
         let current_millis = current_millis();
 
         let mut fields = vec![
@@ -117,10 +246,18 @@ impl Query {
             "create_time_since_epoch",
             "last_update_time_since_epoch",
         ];
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
         args.add(type_id.get());
-        args.add(current_millis);
-        args.add(current_millis);
+        args.add(
+            options
+                .create_time_since_epoch()
+                .map_or(current_millis, |t| t.as_millis() as i64),
+        );
+        args.add(
+            options
+                .last_update_time_since_epoch()
+                .map_or(current_millis, |t| t.as_millis() as i64),
+        );
 
         if let Some(name) = options.name() {
             fields.push("name");
@@ -143,9 +280,9 @@ impl Query {
         (sql, args)
     }
 
-    pub fn update_item(&self, item_id: Id, options: &ItemOptions) -> (String, AnyArguments) {
+    pub fn update_item(&self, item_id: Id, options: &ItemOptions) -> (String, QueryArgs<'_>) {
         let mut fields = "last_update_time_since_epoch=?".to_owned();
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
         args.add(current_millis());
 
         if let Some(v) = options.name() {
@@ -176,18 +313,14 @@ impl Query {
         property_name: &str,
         value: &PropertyValue,
         is_custom: bool,
-    ) -> (String, AnyArguments) {
+    ) -> (String, QueryArgs<'_>) {
         let sql = self.upsert_item_property_sql(item_id, value);
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
         args.add(item_id.get());
         args.add(property_name.to_owned());
         args.add(is_custom);
         for _ in 0..2 {
-            match value.clone() {
-                PropertyValue::Int(v) => args.add(v),
-                PropertyValue::Double(v) => args.add(v),
-                PropertyValue::String(v) => args.add(v),
-            }
+            add_property_value(&mut args, value.clone());
         }
         (sql, args)
     }
@@ -199,33 +332,83 @@ impl Query {
         }
     }
 
+    pub fn delete_custom_properties(&self, item_id: Id) -> String {
+        format!(
+            "DELETE FROM {}Property WHERE {}_id=? AND is_custom_property=1",
+            item_id.kind().item_table_name(),
+            item_id.kind()
+        )
+    }
+
+    pub fn delete_item_property(&self, item_id: Id) -> String {
+        format!(
+            "DELETE FROM {}Property WHERE {}_id=? AND name=? AND is_custom_property=?",
+            item_id.kind().item_table_name(),
+            item_id.kind()
+        )
+    }
+
     pub fn get_item_properties(
         &self,
         type_kind: TypeKind,
         ids: impl Iterator<Item = i32>,
-    ) -> (String, AnyArguments) {
+        names: Option<&[String]>,
+    ) -> (String, QueryArgs<'_>) {
         let mut n = 0;
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
         for id in ids {
             args.add(id);
             n += 1;
         }
         assert_ne!(n, 0);
 
-        let sql = format!(
+        let mut sql = format!(
             concat!(
-                "SELECT {0}_id as id, name, is_custom_property, int_value, double_value, string_value ",
-                "FROM {1}Property ",
-                "WHERE {0}_id IN ({2})"
+                "SELECT P.{0}_id as id, P.name, P.is_custom_property, ",
+                "       P.int_value, P.double_value, P.string_value, P.byte_value, T.data_type ",
+                "FROM {1}Property as P ",
+                "JOIN {1} as I ON P.{0}_id = I.id ",
+                "LEFT JOIN TypeProperty as T ",
+                "  ON I.type_id = T.type_id AND P.name = T.name AND P.is_custom_property = 0 ",
+                "WHERE P.{0}_id IN ({2})"
             ),
             type_kind,
             type_kind.item_table_name(),
             params(n)
         );
+        if let Some(names) = names {
+            sql += &format!(" AND P.name IN ({})", params(names.len()));
+            for name in names {
+                args.add(name.clone());
+            }
+        }
         (sql, args)
     }
 
-    pub fn get_items(&self, options: &GetItemsOptions, count: bool) -> (String, AnyArguments) {
+    pub fn get_custom_property_names(&self, type_kind: TypeKind) -> String {
+        format!(
+            concat!(
+                "SELECT DISTINCT P.name FROM {1}Property as P ",
+                "JOIN {1} as I ON P.{0}_id = I.id ",
+                "WHERE I.type_id = ? AND P.is_custom_property = 1"
+            ),
+            type_kind,
+            type_kind.item_table_name()
+        )
+    }
+
+    fn name_eq(&self, column: &str, ignore_case: bool) -> String {
+        if !ignore_case {
+            return format!("{} = ?", column);
+        }
+        match self {
+            // Note that `NOCASE` only folds ASCII characters.
+            Self::Sqlite(_) => format!("{} = ? COLLATE NOCASE", column),
+            Self::Mysql(_) => format!("LOWER({}) = LOWER(?)", column),
+        }
+    }
+
+    pub fn get_items(&self, options: &GetItemsOptions, count: bool) -> (String, QueryArgs<'_>) {
         match options {
             GetItemsOptions::Artifact(x) => self.get_artifacts(x, count),
             GetItemsOptions::Execution(x) => self.get_executions(x, count),
@@ -237,7 +420,7 @@ impl Query {
         &self,
         options: &GetArtifactsOptions,
         count: bool,
-    ) -> (String, AnyArguments) {
+    ) -> (String, QueryArgs<'_>) {
         let mut sql = format!(
             "SELECT {} FROM Artifact as A ",
             if count {
@@ -246,42 +429,166 @@ impl Query {
                 "A.id, A.type_id, A.name, A.uri, A.state, A.create_time_since_epoch, A.last_update_time_since_epoch "
             }
         );
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
 
-        if options.type_name.is_some() {
+        if options.type_name.is_some() || !options.type_names.is_empty() {
             sql += "JOIN Type as T ON A.type_id = T.id ";
         };
         if options.context_id.is_some() {
             sql += "JOIN Attribution as C ON A.id = C.artifact_id ";
         }
+        for i in 0..options.property_eq.len() {
+            sql += &format!(
+                "JOIN ArtifactProperty as P{0} ON A.id = P{0}.artifact_id ",
+                i
+            );
+        }
+        for i in 0..options.property_ranges.len() {
+            sql += &format!(
+                "JOIN ArtifactProperty as R{0} ON A.id = R{0}.artifact_id ",
+                i
+            );
+        }
+
+        if let Some((name, is_custom)) = &options.order_by_property {
+            sql += &order_by_property_join(TypeKind::Artifact);
+            args.add(name.clone());
+            args.add(*is_custom);
+        }
+        if options.order_by_last_event {
+            sql += concat!(
+                "LEFT JOIN (SELECT artifact_id, MAX(milliseconds_since_epoch) AS last_event_time ",
+                "FROM Event GROUP BY artifact_id) as LE ON A.id = LE.artifact_id "
+            );
+        }
 
         let mut conditions = Vec::new();
         if let Some(v) = options.type_name.clone() {
-            conditions.push("T.name = ?".to_owned());
+            conditions.push(self.name_eq("T.name", options.ignore_case));
             args.add(v)
         }
+        if !options.type_names.is_empty() {
+            conditions.push(format!("T.name IN ({})", params(options.type_names.len())));
+            for name in &options.type_names {
+                args.add(name.clone());
+            }
+        }
         if let Some(v) = options.artifact_name.clone() {
-            conditions.push("A.name = ?".to_owned());
+            conditions.push(self.name_eq("A.name", options.ignore_case));
             args.add(v);
         }
         if let Some(v) = options.artifact_name_pattern.clone() {
             conditions.push("A.name LIKE ?".to_owned());
             args.add(v);
         }
+
+        // The ID and URI conditions are OR-ed if `match_any` is set.
+        let mut id_or_uri_conditions = Vec::new();
         if !options.artifact_ids.is_empty() {
-            conditions.push(format!("A.id IN ({})", params(options.artifact_ids.len())));
+            id_or_uri_conditions.push(format!("A.id IN ({})", params(options.artifact_ids.len())));
             for id in &options.artifact_ids {
                 args.add(id.get());
             }
         }
         if let Some(v) = options.uri.clone() {
-            conditions.push("A.uri = ?".to_owned());
+            id_or_uri_conditions.push("A.uri = ?".to_owned());
             args.add(v);
         }
+        if let Some(v) = options.uri_pattern.clone() {
+            id_or_uri_conditions.push("A.uri LIKE ?".to_owned());
+            args.add(v);
+        }
+        if !options.uris.is_empty() {
+            id_or_uri_conditions.push(format!("A.uri IN ({})", params(options.uris.len())));
+            for uri in &options.uris {
+                args.add(uri.clone());
+            }
+        }
+        if options.match_any && !id_or_uri_conditions.is_empty() {
+            conditions.push(format!("({})", id_or_uri_conditions.join(" OR ")));
+        } else {
+            conditions.extend(id_or_uri_conditions);
+        }
+
+        if options.without_uri {
+            conditions.push("(A.uri IS NULL OR A.uri = '')".to_owned());
+        }
+        if let Some(v) = options.state {
+            conditions.push("A.state = ?".to_owned());
+            args.add(v as i32);
+        }
         if let Some(v) = options.context_id {
             conditions.push("C.context_id = ?".to_owned());
             args.add(v.get());
         }
+        if !options.execution_ids.is_empty() {
+            conditions.push(format!(
+                "A.id IN (SELECT artifact_id FROM Event WHERE execution_id IN ({}){})",
+                params(options.execution_ids.len()),
+                if options.output_events_only {
+                    " AND type IN (?, ?, ?)"
+                } else {
+                    ""
+                }
+            ));
+            for id in &options.execution_ids {
+                args.add(id.get());
+            }
+            if options.output_events_only {
+                for ty in [
+                    EventType::DeclaredOutput,
+                    EventType::Output,
+                    EventType::InternalOutput,
+                ] {
+                    args.add(ty as i32);
+                }
+            }
+        }
+        for (i, (name, value)) in options.property_eq.iter().enumerate() {
+            conditions.push(format!(
+                "P{0}.name = ? AND P{0}.is_custom_property = 0 AND P{0}.{1} = ?",
+                i,
+                property_value_column(value)
+            ));
+            args.add(name.clone());
+            add_property_value(&mut args, value.clone());
+        }
+        for (name, present) in &options.property_presence {
+            conditions.push(property_presence_condition(TypeKind::Artifact, *present));
+            args.add(name.clone());
+        }
+        for (i, (name, range)) in options.property_ranges.iter().enumerate() {
+            let column = format!("R{}.{}", i, range.column());
+            conditions.push(format!(
+                "R{0}.name = ? AND R{0}.is_custom_property = 0 AND {1} IS NOT NULL",
+                i, column
+            ));
+            args.add(name.clone());
+
+            let (start, end) = range.bounds();
+            match start {
+                Bound::Unbounded => {}
+                Bound::Included(v) => {
+                    conditions.push(format!("? <= {}", column));
+                    add_property_value(&mut args, v);
+                }
+                Bound::Excluded(v) => {
+                    conditions.push(format!("? < {}", column));
+                    add_property_value(&mut args, v);
+                }
+            }
+            match end {
+                Bound::Unbounded => {}
+                Bound::Included(v) => {
+                    conditions.push(format!("{} <= ?", column));
+                    add_property_value(&mut args, v);
+                }
+                Bound::Excluded(v) => {
+                    conditions.push(format!("{} < ?", column));
+                    add_property_value(&mut args, v);
+                }
+            }
+        }
 
         match options
             .create_time
@@ -352,11 +659,20 @@ impl Query {
         }
 
         if let Some(field) = options.order_by {
+            // `A.id` breaks ties (e.g., items created within the same millisecond) to make the order deterministic.
             sql += &format!(
-                " ORDER BY {} {}",
+                " ORDER BY A.{0} {1}, A.id {1}",
                 field.field_name(),
                 if options.desc { "DESC" } else { "ASC" }
             );
+        } else if options.order_by_property.is_some() {
+            sql += &order_by_property_clause(TypeKind::Artifact, options.desc);
+        } else if options.order_by_last_event {
+            // Artifacts that have no events are placed last.
+            sql += &format!(
+                " ORDER BY LE.last_event_time IS NULL, LE.last_event_time {0}, A.id {0}",
+                if options.desc { "DESC" } else { "ASC" }
+            );
         }
 
         if let Some(n) = options.limit {
@@ -373,7 +689,7 @@ impl Query {
         &self,
         options: &GetExecutionsOptions,
         count: bool,
-    ) -> (String, AnyArguments) {
+    ) -> (String, QueryArgs<'_>) {
         let mut sql = format!(
             "SELECT {} FROM Execution as A ",
             if count {
@@ -382,22 +698,34 @@ impl Query {
                 "A.id, A.name, A.type_id, A.last_known_state, A.create_time_since_epoch, A.last_update_time_since_epoch "
             }
         );
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
 
-        if options.type_name.is_some() {
+        if options.type_name.is_some() || !options.type_names.is_empty() {
             sql += "JOIN Type as T ON A.type_id = T.id ";
         };
         if options.context_id.is_some() {
             sql += "JOIN Association as C ON A.id = C.execution_id ";
         }
 
+        if let Some((name, is_custom)) = &options.order_by_property {
+            sql += &order_by_property_join(TypeKind::Execution);
+            args.add(name.clone());
+            args.add(*is_custom);
+        }
+
         let mut conditions = Vec::new();
         if let Some(v) = options.type_name.clone() {
-            conditions.push("T.name = ?".to_owned());
+            conditions.push(self.name_eq("T.name", options.ignore_case));
             args.add(v);
         }
+        if !options.type_names.is_empty() {
+            conditions.push(format!("T.name IN ({})", params(options.type_names.len())));
+            for name in &options.type_names {
+                args.add(name.clone());
+            }
+        }
         if let Some(v) = options.execution_name.clone() {
-            conditions.push("A.name = ?".to_owned());
+            conditions.push(self.name_eq("A.name", options.ignore_case));
             args.add(v);
         }
         if let Some(v) = options.execution_name_pattern.clone() {
@@ -410,10 +738,37 @@ impl Query {
                 args.add(id.get());
             }
         }
+        if let Some(v) = options.state {
+            conditions.push("A.last_known_state = ?".to_owned());
+            args.add(v as i32);
+        }
         if let Some(v) = options.context_id {
             conditions.push("C.context_id = ?".to_owned());
             args.add(v.get());
         }
+        if !options.artifact_ids.is_empty() {
+            // A subquery (instead of a join) prevents an execution from appearing more than once
+            // when it has events with multiple specified artifacts.
+            conditions.push(format!(
+                "A.id IN (SELECT execution_id FROM Event WHERE artifact_id IN ({}){})",
+                params(options.artifact_ids.len()),
+                if options.event_type.is_some() {
+                    " AND type = ?"
+                } else {
+                    ""
+                }
+            ));
+            for id in &options.artifact_ids {
+                args.add(id.get());
+            }
+            if let Some(ty) = options.event_type {
+                args.add(ty as i32);
+            }
+        }
+        for (name, present) in &options.property_presence {
+            conditions.push(property_presence_condition(TypeKind::Execution, *present));
+            args.add(name.clone());
+        }
 
         match options
             .create_time
@@ -484,11 +839,14 @@ impl Query {
         }
 
         if let Some(field) = options.order_by {
+            // `A.id` breaks ties (e.g., items created within the same millisecond) to make the order deterministic.
             sql += &format!(
-                " ORDER BY {} {}",
+                " ORDER BY A.{0} {1}, A.id {1}",
                 field.field_name(),
                 if options.desc { "DESC" } else { "ASC" }
             );
+        } else if options.order_by_property.is_some() {
+            sql += &order_by_property_clause(TypeKind::Execution, options.desc);
         }
 
         if let Some(n) = options.limit {
@@ -505,7 +863,7 @@ impl Query {
         &self,
         options: &GetContextsOptions,
         count: bool,
-    ) -> (String, AnyArguments) {
+    ) -> (String, QueryArgs<'_>) {
         let mut sql = format!(
             "SELECT {} FROM Context as A ",
             if count {
@@ -514,25 +872,37 @@ impl Query {
                 "A.id, A.name, A.type_id, A.create_time_since_epoch, A.last_update_time_since_epoch "
             }
         );
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
 
-        if options.type_name.is_some() {
+        if options.type_name.is_some() || !options.type_names.is_empty() {
             sql += "JOIN Type as T ON A.type_id = T.id ";
         };
-        if !options.artifact_ids.is_empty() {
-            sql += "JOIN Attribution as B ON A.id = B.context_id ";
+        if options.parent_context_id.is_some() {
+            sql += "JOIN ParentContext as P ON A.id = P.context_id ";
         }
-        if !options.execution_ids.is_empty() {
-            sql += "JOIN Association as C ON A.id = C.context_id ";
+        if options.child_context_id.is_some() {
+            sql += "JOIN ParentContext as Q ON A.id = Q.parent_context_id ";
+        }
+
+        if let Some((name, is_custom)) = &options.order_by_property {
+            sql += &order_by_property_join(TypeKind::Context);
+            args.add(name.clone());
+            args.add(*is_custom);
         }
 
         let mut conditions = Vec::new();
         if let Some(v) = options.type_name.clone() {
-            conditions.push("T.name = ?".to_owned());
+            conditions.push(self.name_eq("T.name", options.ignore_case));
             args.add(v);
         }
+        if !options.type_names.is_empty() {
+            conditions.push(format!("T.name IN ({})", params(options.type_names.len())));
+            for name in &options.type_names {
+                args.add(name.clone());
+            }
+        }
         if let Some(v) = options.context_name.clone() {
-            conditions.push("A.name = ?".to_owned());
+            conditions.push(self.name_eq("A.name", options.ignore_case));
             args.add(v);
         }
         if let Some(v) = options.context_name_pattern.clone() {
@@ -546,8 +916,10 @@ impl Query {
             }
         }
         if !options.artifact_ids.is_empty() {
+            // Subqueries (instead of joins) prevent a context from appearing more than once
+            // when it is attributed to (or associated with) multiple specified items.
             conditions.push(format!(
-                "B.artifact_id IN ({})",
+                "A.id IN (SELECT context_id FROM Attribution WHERE artifact_id IN ({}))",
                 params(options.artifact_ids.len())
             ));
             for id in &options.artifact_ids {
@@ -556,13 +928,25 @@ impl Query {
         }
         if !options.execution_ids.is_empty() {
             conditions.push(format!(
-                "C.execution_id IN ({})",
+                "A.id IN (SELECT context_id FROM Association WHERE execution_id IN ({}))",
                 params(options.execution_ids.len())
             ));
             for id in &options.execution_ids {
                 args.add(id.get());
             }
         }
+        if let Some(v) = options.parent_context_id {
+            conditions.push("P.parent_context_id = ?".to_owned());
+            args.add(v.get());
+        }
+        if let Some(v) = options.child_context_id {
+            conditions.push("Q.context_id = ?".to_owned());
+            args.add(v.get());
+        }
+        for (name, present) in &options.property_presence {
+            conditions.push(property_presence_condition(TypeKind::Context, *present));
+            args.add(name.clone());
+        }
 
         match options
             .create_time
@@ -633,11 +1017,14 @@ impl Query {
         }
 
         if let Some(field) = options.order_by {
+            // `A.id` breaks ties (e.g., items created within the same millisecond) to make the order deterministic.
             sql += &format!(
-                " ORDER BY {} {}",
+                " ORDER BY A.{0} {1}, A.id {1}",
                 field.field_name(),
                 if options.desc { "DESC" } else { "ASC" }
             );
+        } else if options.order_by_property.is_some() {
+            sql += &order_by_property_clause(TypeKind::Context, options.desc);
         }
 
         if let Some(n) = options.limit {
@@ -657,18 +1044,25 @@ impl Query {
         )
     }
 
+    pub fn get_item_id_by_name(&self, type_kind: TypeKind) -> String {
+        format!(
+            "SELECT id FROM {} WHERE type_id=? AND name=?",
+            type_kind.item_table_name()
+        )
+    }
+
     pub fn check_item_name(
         &self,
         type_kind: TypeKind,
         type_id: TypeId,
         item_id: Option<Id>,
         item_name: &str,
-    ) -> (String, AnyArguments) {
+    ) -> (String, QueryArgs<'_>) {
         let mut sql = format!(
             "SELECT count(*) FROM {} WHERE type_id=? AND name=?",
             type_kind.item_table_name()
         );
-        let mut args = AnyArguments::default();
+        let mut args = QueryArgs::default();
         args.add(type_id.get());
         args.add(item_name.to_owned());
 
@@ -688,6 +1082,14 @@ impl Query {
         "SELECT id FROM Event ORDER BY id DESC LIMIT 1"
     }
 
+    pub fn get_event_ids(&self) -> &'static str {
+        "SELECT id FROM Event WHERE artifact_id = ? AND execution_id = ? AND type = ?"
+    }
+
+    pub fn delete_event_path(&self) -> &'static str {
+        "DELETE FROM EventPath WHERE event_id = ?"
+    }
+
     pub fn insert_event_path(&self, step: &EventStep) -> &'static str {
         match step {
             EventStep::Index(_) => {
@@ -699,13 +1101,125 @@ impl Query {
         }
     }
 
-    pub fn get_events(&self, options: &GetEventsOptions, count: bool) -> String {
+    pub fn artifact_time_bounds(&self, has_type_name: bool) -> &'static str {
+        if has_type_name {
+            concat!(
+                "SELECT MIN(A.create_time_since_epoch), MAX(A.create_time_since_epoch) ",
+                "FROM Artifact as A JOIN Type as T ON A.type_id = T.id WHERE T.name = ?"
+            )
+        } else {
+            "SELECT MIN(create_time_since_epoch), MAX(create_time_since_epoch) FROM Artifact"
+        }
+    }
+
+    pub fn get_artifact_ids_by_execution_type(&self, n_event_types: usize) -> String {
+        let mut sql = concat!(
+            "SELECT DISTINCT E.artifact_id FROM Event as E ",
+            "JOIN Execution as X ON E.execution_id = X.id ",
+            "JOIN Type as T ON X.type_id = T.id ",
+            "WHERE T.name = ?"
+        )
+        .to_owned();
+        if n_event_types > 0 {
+            sql += &format!(" AND E.type IN ({})", params(n_event_types));
+        }
+        sql
+    }
+
+    pub fn get_latest_producing_execution_id(&self, n_types: usize) -> String {
+        format!(
+            concat!(
+                "SELECT execution_id FROM Event WHERE artifact_id = ? AND type IN ({}) ",
+                "ORDER BY milliseconds_since_epoch DESC, id DESC LIMIT 1"
+            ),
+            params(n_types)
+        )
+    }
+
+    pub fn get_context_ids_by_artifact(&self) -> &'static str {
+        "SELECT context_id FROM Attribution WHERE artifact_id = ? ORDER BY context_id"
+    }
+
+    pub fn get_context_ids_by_execution(&self) -> &'static str {
+        "SELECT context_id FROM Association WHERE execution_id = ? ORDER BY context_id"
+    }
+
+    pub fn count_items_by_type(&self, type_kind: TypeKind) -> String {
+        format!(
+            "SELECT type_id, COUNT(*) FROM {} GROUP BY type_id",
+            type_kind.item_table_name()
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn get_attributions(&self) -> &'static str {
+        "SELECT context_id, artifact_id FROM Attribution ORDER BY id"
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn get_associations(&self) -> &'static str {
+        "SELECT context_id, execution_id FROM Association ORDER BY id"
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn get_parent_types(&self) -> &'static str {
+        "SELECT type_id, parent_type_id FROM ParentType ORDER BY type_id, parent_type_id"
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn get_parent_contexts(&self) -> &'static str {
+        "SELECT context_id, parent_context_id FROM ParentContext ORDER BY context_id, parent_context_id"
+    }
+
+    pub fn count_artifacts_by_state(&self, has_type_name: bool) -> String {
+        count_items_by_state("Artifact", "state", has_type_name)
+    }
+
+    pub fn count_executions_by_state(&self, has_type_name: bool) -> String {
+        count_items_by_state("Execution", "last_known_state", has_type_name)
+    }
+
+    pub fn count_events_by_type(&self) -> &'static str {
+        "SELECT type, COUNT(*) FROM Event GROUP BY type"
+    }
+
+    pub fn find_duplicate_events(&self) -> &'static str {
+        concat!(
+            "SELECT artifact_id, execution_id, type, COUNT(*) FROM Event ",
+            "GROUP BY artifact_id, execution_id, type ",
+            "HAVING COUNT(*) > 1 ",
+            "ORDER BY artifact_id, execution_id, type"
+        )
+    }
+
+    pub fn get_duplicate_event_ids(&self) -> &'static str {
+        concat!(
+            "SELECT E.id FROM Event AS E WHERE EXISTS (",
+            "  SELECT 1 FROM Event AS F ",
+            "  WHERE F.artifact_id = E.artifact_id AND F.execution_id = E.execution_id ",
+            "    AND F.type = E.type AND F.id < E.id",
+            ")"
+        )
+    }
+
+    pub fn delete_events(&self, n: usize) -> (String, String) {
+        (
+            format!("DELETE FROM EventPath WHERE event_id IN ({})", params(n)),
+            format!("DELETE FROM Event WHERE id IN ({})", params(n)),
+        )
+    }
+
+    pub fn get_events(&self, options: &GetEventsOptions, count: bool) -> (String, QueryArgs<'_>) {
+        let mut args = QueryArgs::default();
         let mut query = format!(
             "SELECT {} FROM Event ",
             if count {
                 "count(*)"
             } else {
-                "Event.id, artifact_id, execution_id, Event.type, milliseconds_since_epoch"
+                concat!(
+                    "Event.id, artifact_id, execution_id, Event.type, milliseconds_since_epoch, ",
+                    "EXISTS (SELECT 1 FROM EventPath WHERE EventPath.event_id = Event.id) AS has_path"
+                )
             }
         );
         if !options.artifact_ids.is_empty() {
@@ -721,19 +1235,34 @@ impl Query {
                 "Artifact.id IN ({}) ",
                 params(options.artifact_ids.len())
             ));
+            for id in &options.artifact_ids {
+                args.add(id.get());
+            }
         }
         if !options.execution_ids.is_empty() {
             conditions.push(format!(
                 "Execution.id IN ({}) ",
                 params(options.execution_ids.len())
             ));
+            for id in &options.execution_ids {
+                args.add(id.get());
+            }
+        }
+        if !options.pairs.is_empty() {
+            let pair_conditions =
+                vec!["(Event.execution_id = ? AND Event.artifact_id = ?)"; options.pairs.len()];
+            conditions.push(format!("({}) ", pair_conditions.join(" OR ")));
+            for (execution_id, artifact_id) in &options.pairs {
+                args.add(execution_id.get());
+                args.add(artifact_id.get());
+            }
         }
         if !conditions.is_empty() {
             query += &format!("WHERE {}", conditions.join(" AND "));
         }
         if let Some(field) = options.order_by {
             query += &format!(
-                " ORDER BY {} {}",
+                " ORDER BY Event.{0} {1}, Event.id {1}",
                 field.field_name(),
                 if options.desc { "DESC" } else { "ASC" }
             );
@@ -745,12 +1274,20 @@ impl Query {
                 query += &format!(" OFFSET {}", n);
             }
         }
-        query
+        (query, args)
     }
 
     pub fn get_event_paths(&self, n_events: usize) -> String {
-        format!("SELECT event_id, is_index_step, step_index, step_key FROM EventPath WHERE event_id IN ({})",
-                params(n_events))
+        let sql = format!("SELECT event_id, is_index_step, step_index, step_key FROM EventPath WHERE event_id IN ({})",
+                params(n_events));
+
+        match self {
+            // The steps of an event are ordered by their insertion order.
+            Self::Sqlite(_) => sql + " ORDER BY event_id, rowid",
+
+            // `EventPath` has no column to order the steps by, so their order isn't guaranteed.
+            Self::Mysql(_) => sql,
+        }
     }
 }
 
@@ -758,6 +1295,64 @@ impl Query {
 pub struct SqliteQuery;
 
 impl SqliteQuery {
+    fn migration_steps(&self) -> &'static [MigrationStep] {
+        &[
+            MigrationStep {
+                version: 7,
+                statements: &[
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM pragma_table_info('Type') ",
+                        "WHERE name = 'version'"
+                    ),
+                    "ALTER TABLE `Type` ADD COLUMN `version` VARCHAR(255)",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM pragma_table_info('Type') ",
+                        "WHERE name = 'description'"
+                    ),
+                    "ALTER TABLE `Type` ADD COLUMN `description` TEXT",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM pragma_table_info('ArtifactProperty') ",
+                        "WHERE name = 'byte_value'"
+                    ),
+                    "ALTER TABLE `ArtifactProperty` ADD COLUMN `byte_value` BLOB",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM pragma_table_info('ExecutionProperty') ",
+                        "WHERE name = 'byte_value'"
+                    ),
+                    "ALTER TABLE `ExecutionProperty` ADD COLUMN `byte_value` BLOB",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM pragma_table_info('ContextProperty') ",
+                        "WHERE name = 'byte_value'"
+                    ),
+                    "ALTER TABLE `ContextProperty` ADD COLUMN `byte_value` BLOB",
+                ),
+                ],
+            },
+            MigrationStep {
+                version: 8,
+                statements: &[
+                (
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = 'idx_type_name'",
+                    "CREATE INDEX `idx_type_name` ON `Type`(`name`)",
+                ),
+                (
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = 'idx_parentcontext_parent_context_id'",
+                    "CREATE INDEX `idx_parentcontext_parent_context_id` ON `ParentContext`(`parent_context_id`)",
+                ),
+                ],
+            },
+        ]
+    }
+
     fn create_tables(&self) -> &'static [&'static str] {
         &[
             concat!(
@@ -993,6 +1588,23 @@ impl SqliteQuery {
         ]
     }
 
+    // The tables created by the older releases of this crate already have the current schema.
+    fn schema_repairs(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    // The indices are created by `create_tables` as SQLite supports `CREATE INDEX IF NOT EXISTS`.
+    fn create_indices(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    fn add_event_unique_constraint(&self) -> (&'static str, &'static str) {
+        (
+            "SELECT COUNT(*) FROM pragma_index_list('Event') WHERE \"unique\" = 1",
+            "CREATE UNIQUE INDEX UniqueEvent ON Event(artifact_id, execution_id, type)",
+        )
+    }
+
     fn insert_attribution(&self) -> &'static str {
         "INSERT OR IGNORE INTO Attribution (context_id, artifact_id) VALUES (?, ?)"
     }
@@ -1001,20 +1613,29 @@ impl SqliteQuery {
         "INSERT OR IGNORE INTO Association (context_id, execution_id) VALUES (?, ?)"
     }
 
+    fn insert_parent_type(&self) -> &'static str {
+        "INSERT OR IGNORE INTO ParentType (type_id, parent_type_id) VALUES (?, ?)"
+    }
+
+    fn insert_parent_context(&self) -> &'static str {
+        "INSERT OR IGNORE INTO ParentContext (context_id, parent_context_id) VALUES (?, ?)"
+    }
+
     fn upsert_item_property_sql(&self, item_id: Id, value: &PropertyValue) -> String {
         format!(
             concat!(
                 "INSERT INTO {3}Property ",
-                "({4}_id, name, is_custom_property, int_value, double_value, string_value) ",
-                "VALUES (?, ?, ?, {0}, {1}, {2}) ",
+                "({4}_id, name, is_custom_property, int_value, double_value, string_value, byte_value) ",
+                "VALUES (?, ?, ?, {0}, {1}, {2}, {5}) ",
                 "ON CONFLICT ({4}_id, name, is_custom_property) ",
-                "DO UPDATE SET int_value={0}, double_value={1}, string_value={2}"
+                "DO UPDATE SET int_value={0}, double_value={1}, string_value={2}, byte_value={5}"
             ),
-            maybe_null(value.as_int().is_some(), "?"),
+            maybe_null(value.as_int().is_some() || value.as_bool().is_some(), "?"),
             maybe_null(value.as_double().is_some(), "?"),
             maybe_null(value.as_string().is_some(), "?"),
             item_id.kind().item_table_name(),
-            item_id.kind()
+            item_id.kind(),
+            maybe_null(value.as_bytes().is_some(), "?")
         )
     }
 }
@@ -1023,6 +1644,124 @@ impl SqliteQuery {
 pub struct MysqlQuery;
 
 impl MysqlQuery {
+    // The tables created by the older releases of this crate lack the `byte_value` columns
+    // even if the database has the schema version 8.
+    // These are the same statements as the ones adding the columns in the version 7 migration step.
+    const BYTE_VALUE_COLUMNS: &'static [(&'static str, &'static str)] = &[
+        (
+            concat!(
+                "SELECT COUNT(*) FROM information_schema.columns ",
+                "WHERE table_schema = DATABASE() AND table_name = 'ArtifactProperty' ",
+                "AND column_name = 'byte_value'"
+            ),
+            "ALTER TABLE `ArtifactProperty` ADD COLUMN `byte_value` MEDIUMBLOB",
+        ),
+        (
+            concat!(
+                "SELECT COUNT(*) FROM information_schema.columns ",
+                "WHERE table_schema = DATABASE() AND table_name = 'ExecutionProperty' ",
+                "AND column_name = 'byte_value'"
+            ),
+            "ALTER TABLE `ExecutionProperty` ADD COLUMN `byte_value` MEDIUMBLOB",
+        ),
+        (
+            concat!(
+                "SELECT COUNT(*) FROM information_schema.columns ",
+                "WHERE table_schema = DATABASE() AND table_name = 'ContextProperty' ",
+                "AND column_name = 'byte_value'"
+            ),
+            "ALTER TABLE `ContextProperty` ADD COLUMN `byte_value` MEDIUMBLOB",
+        ),
+    ];
+
+    fn schema_repairs(&self) -> &'static [(&'static str, &'static str)] {
+        Self::BYTE_VALUE_COLUMNS
+    }
+
+    // The unique constraint is declared in `create_tables`, so any unique index other than
+    // the primary key is regarded as it.
+    fn add_event_unique_constraint(&self) -> (&'static str, &'static str) {
+        (
+            concat!(
+                "SELECT COUNT(*) FROM information_schema.statistics ",
+                "WHERE table_schema = DATABASE() AND table_name = 'Event' ",
+                "AND non_unique = 0 AND index_name <> 'PRIMARY'"
+            ),
+            "ALTER TABLE `Event` ADD UNIQUE INDEX `UniqueEvent` (`artifact_id`, `execution_id`, `type`)",
+        )
+    }
+
+    fn migration_steps(&self) -> &'static [MigrationStep] {
+        &[
+            MigrationStep {
+                version: 7,
+                statements: &[
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.columns ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'Type' ",
+                        "AND column_name = 'version'"
+                    ),
+                    "ALTER TABLE `Type` ADD COLUMN `version` VARCHAR(255)",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.columns ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'Type' ",
+                        "AND column_name = 'description'"
+                    ),
+                    "ALTER TABLE `Type` ADD COLUMN `description` TEXT",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.columns ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'ArtifactProperty' ",
+                        "AND column_name = 'byte_value'"
+                    ),
+                    "ALTER TABLE `ArtifactProperty` ADD COLUMN `byte_value` MEDIUMBLOB",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.columns ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'ExecutionProperty' ",
+                        "AND column_name = 'byte_value'"
+                    ),
+                    "ALTER TABLE `ExecutionProperty` ADD COLUMN `byte_value` MEDIUMBLOB",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.columns ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'ContextProperty' ",
+                        "AND column_name = 'byte_value'"
+                    ),
+                    "ALTER TABLE `ContextProperty` ADD COLUMN `byte_value` MEDIUMBLOB",
+                ),
+                ],
+            },
+            MigrationStep {
+                version: 8,
+                statements: &[
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.statistics ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'Type' ",
+                        "AND index_name = 'idx_type_name'"
+                    ),
+                    "ALTER TABLE `Type` ADD INDEX `idx_type_name` (`name`)",
+                ),
+                (
+                    concat!(
+                        "SELECT COUNT(*) FROM information_schema.statistics ",
+                        "WHERE table_schema = DATABASE() AND table_name = 'ParentContext' ",
+                        "AND index_name = 'idx_parentcontext_parent_context_id'"
+                    ),
+                    "ALTER TABLE `ParentContext` ADD INDEX `idx_parentcontext_parent_context_id` (`parent_context_id`)",
+                ),
+                ],
+            },
+        ]
+    }
+
     fn create_tables(&self) -> &'static [&'static str] {
         &[
             concat!(
@@ -1069,6 +1808,7 @@ impl MysqlQuery {
                 "   `int_value` INT, ",
                 "   `double_value` DOUBLE, ",
                 "   `string_value` TEXT, ",
+                "   `byte_value` MEDIUMBLOB, ",
                 " PRIMARY KEY (`artifact_id`, `name`, `is_custom_property`)); "
             ),
             concat!(
@@ -1090,6 +1830,7 @@ impl MysqlQuery {
                 "   `int_value` INT, ",
                 "   `double_value` DOUBLE, ",
                 "   `string_value` TEXT, ",
+                "   `byte_value` MEDIUMBLOB, ",
                 " PRIMARY KEY (`execution_id`, `name`, `is_custom_property`)); "
             ),
             concat!(
@@ -1110,6 +1851,7 @@ impl MysqlQuery {
                 "   `int_value` INT, ",
                 "   `double_value` DOUBLE, ",
                 "   `string_value` TEXT, ",
+                "   `byte_value` MEDIUMBLOB, ",
                 " PRIMARY KEY (`context_id`, `name`, `is_custom_property`)); "
             ),
             concat!(
@@ -1124,7 +1866,8 @@ impl MysqlQuery {
                 "   `artifact_id` INT NOT NULL, ",
                 "   `execution_id` INT NOT NULL, ",
                 "   `type` INT NOT NULL, ",
-                "   `milliseconds_since_epoch` BIGINT ",
+                "   `milliseconds_since_epoch` BIGINT, ",
+                "   UNIQUE(`artifact_id`, `execution_id`, `type`) ",
                 " ); "
             ),
             concat!(
@@ -1156,41 +1899,100 @@ impl MysqlQuery {
                 "   `schema_version` INTEGER PRIMARY KEY ",
                 " ); "
             ),
-            concat!(
-                " ALTER TABLE `Artifact` ",
-                "  ADD INDEX `idx_artifact_uri`(`uri`(255)), ",
-                "  ADD INDEX `idx_artifact_create_time_since_epoch` ",
-                "             (`create_time_since_epoch`), ",
-                "  ADD INDEX `idx_artifact_last_update_time_since_epoch` ",
-                "             (`last_update_time_since_epoch`); "
+        ]
+    }
+
+    // Unlike SQLite, MySQL has no `CREATE INDEX IF NOT EXISTS`,
+    // so each index is only created if the probe query returns zero.
+    fn create_indices(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Artifact' ",
+                    "AND index_name = 'idx_artifact_uri'"
+                ),
+                "ALTER TABLE `Artifact` ADD INDEX `idx_artifact_uri` (`uri`(255))",
             ),
-            concat!(
-                " ALTER TABLE `Event` ",
-                " ADD INDEX `idx_event_artifact_id` (`artifact_id`), ",
-                " ADD INDEX `idx_event_execution_id` (`execution_id`); "
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Artifact' ",
+                    "AND index_name = 'idx_artifact_create_time_since_epoch'"
+                ),
+                "ALTER TABLE `Artifact` ADD INDEX `idx_artifact_create_time_since_epoch` (`create_time_since_epoch`)",
             ),
-            concat!(
-                " ALTER TABLE `ParentContext` ",
-                " ADD INDEX ",
-                "   `idx_parentcontext_parent_context_id` (`parent_context_id`); "
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Artifact' ",
+                    "AND index_name = 'idx_artifact_last_update_time_since_epoch'"
+                ),
+                "ALTER TABLE `Artifact` ADD INDEX `idx_artifact_last_update_time_since_epoch` (`last_update_time_since_epoch`)",
             ),
-            concat!(
-                " ALTER TABLE `Type` ",
-                " ADD INDEX `idx_type_name` (`name`); "
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Event' ",
+                    "AND index_name = 'idx_event_artifact_id'"
+                ),
+                "ALTER TABLE `Event` ADD INDEX `idx_event_artifact_id` (`artifact_id`)",
             ),
-            concat!(
-                " ALTER TABLE `Execution` ",
-                "  ADD INDEX `idx_execution_create_time_since_epoch` ",
-                "             (`create_time_since_epoch`), ",
-                "  ADD INDEX `idx_execution_last_update_time_since_epoch` ",
-                "             (`last_update_time_since_epoch`); "
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Event' ",
+                    "AND index_name = 'idx_event_execution_id'"
+                ),
+                "ALTER TABLE `Event` ADD INDEX `idx_event_execution_id` (`execution_id`)",
             ),
-            concat!(
-                " ALTER TABLE `Context` ",
-                "  ADD INDEX `idx_context_create_time_since_epoch` ",
-                "             (`create_time_since_epoch`), ",
-                "  ADD INDEX `idx_context_last_update_time_since_epoch` ",
-                "             (`last_update_time_since_epoch`); "
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'ParentContext' ",
+                    "AND index_name = 'idx_parentcontext_parent_context_id'"
+                ),
+                "ALTER TABLE `ParentContext` ADD INDEX `idx_parentcontext_parent_context_id` (`parent_context_id`)",
+            ),
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Type' ",
+                    "AND index_name = 'idx_type_name'"
+                ),
+                "ALTER TABLE `Type` ADD INDEX `idx_type_name` (`name`)",
+            ),
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Execution' ",
+                    "AND index_name = 'idx_execution_create_time_since_epoch'"
+                ),
+                "ALTER TABLE `Execution` ADD INDEX `idx_execution_create_time_since_epoch` (`create_time_since_epoch`)",
+            ),
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Execution' ",
+                    "AND index_name = 'idx_execution_last_update_time_since_epoch'"
+                ),
+                "ALTER TABLE `Execution` ADD INDEX `idx_execution_last_update_time_since_epoch` (`last_update_time_since_epoch`)",
+            ),
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Context' ",
+                    "AND index_name = 'idx_context_create_time_since_epoch'"
+                ),
+                "ALTER TABLE `Context` ADD INDEX `idx_context_create_time_since_epoch` (`create_time_since_epoch`)",
+            ),
+            (
+                concat!(
+                    "SELECT COUNT(*) FROM information_schema.statistics ",
+                    "WHERE table_schema = DATABASE() AND table_name = 'Context' ",
+                    "AND index_name = 'idx_context_last_update_time_since_epoch'"
+                ),
+                "ALTER TABLE `Context` ADD INDEX `idx_context_last_update_time_since_epoch` (`last_update_time_since_epoch`)",
             ),
         ]
     }
@@ -1203,28 +2005,54 @@ impl MysqlQuery {
         "INSERT IGNORE INTO Association (context_id, execution_id) VALUES (?, ?)"
     }
 
+    fn insert_parent_type(&self) -> &'static str {
+        "INSERT IGNORE INTO ParentType (type_id, parent_type_id) VALUES (?, ?)"
+    }
+
+    fn insert_parent_context(&self) -> &'static str {
+        "INSERT IGNORE INTO ParentContext (context_id, parent_context_id) VALUES (?, ?)"
+    }
+
     fn upsert_item_property_sql(&self, item_id: Id, value: &PropertyValue) -> String {
         format!(
             concat!(
                 "INSERT INTO {3}Property ",
-                "({4}_id, name, is_custom_property, int_value, double_value, string_value) ",
-                "VALUES (?, ?, ?, {0}, {1}, {2}) ",
+                "({4}_id, name, is_custom_property, int_value, double_value, string_value, byte_value) ",
+                "VALUES (?, ?, ?, {0}, {1}, {2}, {5}) ",
                 "ON DUPLICATE KEY ",
-                "UPDATE int_value={0}, double_value={1}, string_value={2}"
+                "UPDATE int_value={0}, double_value={1}, string_value={2}, byte_value={5}"
             ),
-            maybe_null(value.as_int().is_some(), "?"),
+            maybe_null(value.as_int().is_some() || value.as_bool().is_some(), "?"),
             maybe_null(value.as_double().is_some(), "?"),
             maybe_null(value.as_string().is_some(), "?"),
             item_id.kind().item_table_name(),
-            item_id.kind()
+            item_id.kind(),
+            maybe_null(value.as_bytes().is_some(), "?")
         )
     }
 }
 
+/// A step to migrate the database schema to `version`.
+///
+/// Each statement is a pair of a probe query and a DDL.
+/// The DDL is only executed if the probe returns zero (i.e., the change hasn't been applied yet),
+/// so that a step can be safely re-run against a partially migrated database.
+#[derive(Debug)]
+pub struct MigrationStep {
+    pub version: i32,
+    pub statements: &'static [(&'static str, &'static str)],
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct Type {
     pub id: i32,
     pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub input_type: Option<String>,
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub output_type: Option<String>,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -1248,6 +2076,10 @@ pub struct Property {
     pub int_value: Option<i32>,
     pub double_value: Option<f64>,
     pub string_value: Option<String>,
+    pub byte_value: Option<Vec<u8>>,
+
+    // The declared type of the property (always `None` for custom properties).
+    pub data_type: Option<i32>,
 }
 
 impl Property {
@@ -1258,6 +2090,16 @@ impl Property {
                 int_value: Some(v),
                 double_value: None,
                 string_value: None,
+                byte_value: None,
+                data_type: Some(data_type),
+                ..
+            } if data_type == PropertyType::Bool as i32 => Ok((name, PropertyValue::Bool(v != 0))),
+            Self {
+                name,
+                int_value: Some(v),
+                double_value: None,
+                string_value: None,
+                byte_value: None,
                 ..
             } => Ok((name, PropertyValue::Int(v))),
             Self {
@@ -1265,6 +2107,7 @@ impl Property {
                 int_value: None,
                 double_value: Some(v),
                 string_value: None,
+                byte_value: None,
                 ..
             } => Ok((name, PropertyValue::Double(v))),
             Self {
@@ -1272,8 +2115,17 @@ impl Property {
                 int_value: None,
                 double_value: None,
                 string_value: Some(v),
+                byte_value: None,
                 ..
             } => Ok((name, PropertyValue::String(v))),
+            Self {
+                name,
+                int_value: None,
+                double_value: None,
+                string_value: None,
+                byte_value: Some(v),
+                ..
+            } => Ok((name, PropertyValue::Bytes(v))),
             _ => Err(sqlx::Error::Decode(
                 anyhow::anyhow!("a property must have just one value: {:?}", self).into(),
             )),
@@ -1289,6 +2141,7 @@ pub struct Event {
     pub artifact_id: i32,
     pub execution_id: i32,
     pub milliseconds_since_epoch: i64,
+    pub has_path: i32,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -1303,11 +2156,31 @@ pub trait InsertProperty {
     fn insert_property(&mut self, is_custom: bool, name: String, value: PropertyValue);
 }
 
-pub trait GetItemsQueryGenerator {
-    type Item: for<'a> sqlx::FromRow<'a, sqlx::any::AnyRow> + InsertProperty;
+/// Arguments of a generated query.
+///
+/// Unlike [`AnyArguments`], this knows the number of the added arguments.
+#[derive(Default)]
+pub struct QueryArgs<'q> {
+    args: AnyArguments<'q>,
+    len: usize,
+}
+
+impl<'q> QueryArgs<'q> {
+    pub fn add<T>(&mut self, value: T)
+    where
+        T: 'q + Send + sqlx::Encode<'q, Any> + sqlx::Type<Any>,
+    {
+        self.args.add(value);
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
 
-    fn generate_select_items_sql(&self) -> String;
-    fn query_values(&self) -> Vec<QueryValue>;
+    pub fn into_inner(self) -> AnyArguments<'q> {
+        self.args
+    }
 }
 
 fn maybe_null(b: bool, s: &str) -> &str {
@@ -1318,10 +2191,83 @@ fn maybe_null(b: bool, s: &str) -> &str {
     }
 }
 
+fn count_items_by_state(table: &str, state_column: &str, has_type_name: bool) -> String {
+    // NULL states are treated as UNKNOWN (0) as the same as when fetching items.
+    let mut sql = format!(
+        "SELECT COALESCE(A.{0}, 0) AS S, COUNT(*) FROM {1} as A ",
+        state_column, table
+    );
+    if has_type_name {
+        sql += "JOIN Type as T ON A.type_id = T.id WHERE T.name = ? ";
+    }
+    sql += "GROUP BY S";
+    sql
+}
+
 fn current_millis() -> i64 {
     UNIX_EPOCH.elapsed().unwrap_or_default().as_millis() as i64
 }
 
+/// Logs the given SQL and the number of the arguments bound to it at the debug level.
+///
+/// This does nothing unless the `trace-sql` feature is enabled.
+/// Argument values are never logged as they may contain sensitive data.
+#[inline]
+pub fn trace_sql(sql: &str, param_count: usize) {
+    #[cfg(feature = "trace-sql")]
+    log::debug!(target: "mlmd::sql", "{} (params: {})", sql, param_count);
+    #[cfg(not(feature = "trace-sql"))]
+    let _ = (sql, param_count);
+}
+
 fn params(n: usize) -> String {
     (0..n).map(|_| "?").collect::<Vec<_>>().join(",")
 }
+
+fn property_value_column(value: &PropertyValue) -> &'static str {
+    match value {
+        PropertyValue::Int(_) | PropertyValue::Bool(_) => "int_value",
+        PropertyValue::Double(_) => "double_value",
+        PropertyValue::String(_) => "string_value",
+        PropertyValue::Bytes(_) => "byte_value",
+    }
+}
+
+fn add_property_value(args: &mut QueryArgs, value: PropertyValue) {
+    match value {
+        PropertyValue::Int(v) => args.add(v),
+        PropertyValue::Double(v) => args.add(v),
+        PropertyValue::String(v) => args.add(v),
+        PropertyValue::Bytes(v) => args.add(v),
+        PropertyValue::Bool(v) => args.add(v as i32),
+    }
+}
+
+fn property_presence_condition(type_kind: TypeKind, present: bool) -> String {
+    format!(
+        "{}EXISTS (SELECT 1 FROM {}Property WHERE {}_id = A.id AND name = ?)",
+        if present { "" } else { "NOT " },
+        type_kind.item_table_name(),
+        type_kind
+    )
+}
+
+fn order_by_property_join(type_kind: TypeKind) -> String {
+    format!(
+        concat!(
+            "LEFT JOIN {0}Property as O ",
+            "ON A.id = O.{1}_id AND O.name = ? AND O.is_custom_property = ? "
+        ),
+        type_kind.item_table_name(),
+        type_kind
+    )
+}
+
+fn order_by_property_clause(type_kind: TypeKind, desc: bool) -> String {
+    // Items that don't have the property are placed last.
+    format!(
+        " ORDER BY O.{0}_id IS NULL, COALESCE(O.int_value, O.double_value) {1}, O.string_value {1}, A.id {1}",
+        type_kind,
+        if desc { "DESC" } else { "ASC" }
+    )
+}
@@ -5,11 +5,58 @@ use crate::metadata::{
     EventStep, EventType, Execution, ExecutionId, ExecutionState, ExecutionType, Id, PropertyType,
     PropertyTypes, PropertyValue, PropertyValues, TypeId, TypeKind,
 };
+pub use crate::metadata_store::options::ArtifactOptions;
 use crate::metadata_store::{options, MetadataStore};
+use crate::query::QueryArgs;
 use std::iter;
 use std::ops::{Bound, Range, RangeBounds};
 use std::time::Duration;
 
+/// SQL generated by a GET request builder without executing it.
+///
+/// See [`GetArtifactsRequest::dry_run_sql`] for example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunSql {
+    /// The SQL statement.
+    ///
+    /// Parameters are represented by `?` placeholders.
+    pub sql: String,
+
+    /// The number of the parameters bound to the placeholders of [`sql`](Self::sql).
+    ///
+    /// The actual values aren't exposed.
+    pub param_count: usize,
+}
+
+impl DryRunSql {
+    fn new((sql, args): (String, QueryArgs)) -> Self {
+        Self {
+            sql,
+            param_count: args.len(),
+        }
+    }
+}
+
+/// Outcome of a request that puts a relation (e.g., [`PutAttributionRequest::execute`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelationOutcome {
+    /// A new relation has been inserted.
+    Inserted,
+
+    /// The same relation already existed, so nothing has been changed.
+    AlreadyExisted,
+}
+
+impl RelationOutcome {
+    pub(crate) fn from_rows_affected(n: u64) -> Self {
+        if n == 0 {
+            Self::AlreadyExisted
+        } else {
+            Self::Inserted
+        }
+    }
+}
+
 /// Possible values for [`GetArtifactsRequest::order_by`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(missing_docs)]
@@ -80,12 +127,16 @@ impl ContextOrderByField {
 #[allow(missing_docs)]
 pub enum EventOrderByField {
     CreateTime,
+    ArtifactId,
+    ExecutionId,
 }
 
 impl EventOrderByField {
     pub(crate) fn field_name(self) -> &'static str {
         match self {
             Self::CreateTime => "milliseconds_since_epoch",
+            Self::ArtifactId => "artifact_id",
+            Self::ExecutionId => "execution_id",
         }
     }
 }
@@ -107,6 +158,23 @@ impl<'a> PutArtifactTypeRequest<'a> {
         }
     }
 
+    /// Sets the version of the type.
+    ///
+    /// Types with the same name but different versions are regarded as different types.
+    pub fn version(mut self, version: &str) -> Self {
+        self.options.version = Some(version.to_owned());
+        self
+    }
+
+    /// Sets the description of the type.
+    ///
+    /// The description of an existing type is always updated regardless of
+    /// [`can_add_fields`](Self::can_add_fields) and [`can_omit_fields`](Self::can_omit_fields).
+    pub fn description(mut self, description: &str) -> Self {
+        self.options.description = Some(description.to_owned());
+        self
+    }
+
     /// When specified, stored properties can be omitted in the request type.
     ///
     /// Otherwise, returns [`PutError::TypeAlreadyExists`]
@@ -150,12 +218,12 @@ impl<'a> PutArtifactTypeRequest<'a> {
 /// Request builder for [`MetadataStore::get_artifact_types`].
 #[derive(Debug)]
 pub struct GetArtifactTypesRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetTypesOptions,
 }
 
 impl<'a> GetArtifactTypesRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: options::GetTypesOptions::default(),
@@ -163,8 +231,22 @@ impl<'a> GetArtifactTypesRequest<'a> {
     }
 
     /// Specifies the type name of the target types.
+    ///
+    /// This overrides [`name_pattern`](Self::name_pattern) and vice versa.
     pub fn name(mut self, type_name: &str) -> Self {
         self.options.name = Some(type_name.to_owned());
+        self.options.name_pattern = None;
+        self
+    }
+
+    /// Specifies the type name pattern of the target types.
+    ///
+    /// `type_name_pattern` can contain wildcard characters for the SQL LIKE statement.
+    ///
+    /// This overrides [`name`](Self::name) and vice versa.
+    pub fn name_pattern(mut self, type_name_pattern: &str) -> Self {
+        self.options.name_pattern = Some(type_name_pattern.to_owned());
+        self.options.name = None;
         self
     }
 
@@ -181,6 +263,14 @@ impl<'a> GetArtifactTypesRequest<'a> {
         self
     }
 
+    /// Specifies the child type of the target types (i.e., gets the parent types of `type_id`).
+    ///
+    /// See also: [`MetadataStore::put_parent_type`]
+    pub fn child(mut self, type_id: TypeId) -> Self {
+        self.options.child_type_id = Some(type_id);
+        self
+    }
+
     /// Gets specified artifact types.
     ///
     /// If multiple conditions are specified, types which satisfy all the conditions are returned.
@@ -188,10 +278,14 @@ impl<'a> GetArtifactTypesRequest<'a> {
         self.store
             .execute_get_types(
                 TypeKind::Artifact,
-                |id, name, properties| ArtifactType {
-                    id,
-                    name,
-                    properties,
+                |ty, properties| {
+                    Ok(ArtifactType {
+                        id: TypeId::new(ty.id),
+                        name: ty.name,
+                        version: ty.version,
+                        description: ty.description,
+                        properties,
+                    })
                 },
                 self.options,
             )
@@ -216,6 +310,41 @@ impl<'a> PutExecutionTypeRequest<'a> {
         }
     }
 
+    /// Sets the version of the type.
+    ///
+    /// Types with the same name but different versions are regarded as different types.
+    pub fn version(mut self, version: &str) -> Self {
+        self.options.version = Some(version.to_owned());
+        self
+    }
+
+    /// Sets the description of the type.
+    ///
+    /// The description of an existing type is always updated regardless of
+    /// [`can_add_fields`](Self::can_add_fields) and [`can_omit_fields`](Self::can_omit_fields).
+    pub fn description(mut self, description: &str) -> Self {
+        self.options.description = Some(description.to_owned());
+        self
+    }
+
+    /// Sets the JSON representation of the artifact structure taken by the executions of this type.
+    ///
+    /// The input type of an existing type is updated if this is specified.
+    #[cfg(feature = "serde")]
+    pub fn input_type(mut self, input_type: serde_json::Value) -> Self {
+        self.options.input_type = Some(input_type.to_string());
+        self
+    }
+
+    /// Sets the JSON representation of the artifact structure produced by the executions of this type.
+    ///
+    /// The output type of an existing type is updated if this is specified.
+    #[cfg(feature = "serde")]
+    pub fn output_type(mut self, output_type: serde_json::Value) -> Self {
+        self.options.output_type = Some(output_type.to_string());
+        self
+    }
+
     /// When specified, stored properties can be omitted in the request type.
     ///
     /// Otherwise, returns [`PutError::TypeAlreadyExists`]
@@ -259,12 +388,12 @@ impl<'a> PutExecutionTypeRequest<'a> {
 /// Request builder for [`MetadataStore::get_execution_types`].
 #[derive(Debug)]
 pub struct GetExecutionTypesRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetTypesOptions,
 }
 
 impl<'a> GetExecutionTypesRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: options::GetTypesOptions::default(),
@@ -272,8 +401,22 @@ impl<'a> GetExecutionTypesRequest<'a> {
     }
 
     /// Specifies the type name of the target types.
+    ///
+    /// This overrides [`name_pattern`](Self::name_pattern) and vice versa.
     pub fn name(mut self, type_name: &str) -> Self {
         self.options.name = Some(type_name.to_owned());
+        self.options.name_pattern = None;
+        self
+    }
+
+    /// Specifies the type name pattern of the target types.
+    ///
+    /// `type_name_pattern` can contain wildcard characters for the SQL LIKE statement.
+    ///
+    /// This overrides [`name`](Self::name) and vice versa.
+    pub fn name_pattern(mut self, type_name_pattern: &str) -> Self {
+        self.options.name_pattern = Some(type_name_pattern.to_owned());
+        self.options.name = None;
         self
     }
 
@@ -290,6 +433,14 @@ impl<'a> GetExecutionTypesRequest<'a> {
         self
     }
 
+    /// Specifies the child type of the target types (i.e., gets the parent types of `type_id`).
+    ///
+    /// See also: [`MetadataStore::put_parent_type`]
+    pub fn child(mut self, type_id: TypeId) -> Self {
+        self.options.child_type_id = Some(type_id);
+        self
+    }
+
     /// Gets specified execution types.
     ///
     /// If multiple conditions are specified, types which satisfy all the conditions are returned.
@@ -297,10 +448,18 @@ impl<'a> GetExecutionTypesRequest<'a> {
         self.store
             .execute_get_types(
                 TypeKind::Execution,
-                |id, name, properties| ExecutionType {
-                    id,
-                    name,
-                    properties,
+                |ty, properties| {
+                    Ok(ExecutionType {
+                        id: TypeId::new(ty.id),
+                        name: ty.name,
+                        version: ty.version,
+                        description: ty.description,
+                        #[cfg(feature = "serde")]
+                        input_type: parse_json("Type.input_type", ty.input_type)?,
+                        #[cfg(feature = "serde")]
+                        output_type: parse_json("Type.output_type", ty.output_type)?,
+                        properties,
+                    })
                 },
                 self.options,
             )
@@ -325,6 +484,23 @@ impl<'a> PutContextTypeRequest<'a> {
         }
     }
 
+    /// Sets the version of the type.
+    ///
+    /// Types with the same name but different versions are regarded as different types.
+    pub fn version(mut self, version: &str) -> Self {
+        self.options.version = Some(version.to_owned());
+        self
+    }
+
+    /// Sets the description of the type.
+    ///
+    /// The description of an existing type is always updated regardless of
+    /// [`can_add_fields`](Self::can_add_fields) and [`can_omit_fields`](Self::can_omit_fields).
+    pub fn description(mut self, description: &str) -> Self {
+        self.options.description = Some(description.to_owned());
+        self
+    }
+
     /// When specified, stored properties can be omitted in the request type.
     ///
     /// Otherwise, returns [`PutError::TypeAlreadyExists`]
@@ -368,12 +544,12 @@ impl<'a> PutContextTypeRequest<'a> {
 /// Request builder for [`MetadataStore::get_context_types`].
 #[derive(Debug)]
 pub struct GetContextTypesRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetTypesOptions,
 }
 
 impl<'a> GetContextTypesRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: options::GetTypesOptions::default(),
@@ -381,8 +557,22 @@ impl<'a> GetContextTypesRequest<'a> {
     }
 
     /// Specifies the type name of the target types.
+    ///
+    /// This overrides [`name_pattern`](Self::name_pattern) and vice versa.
     pub fn name(mut self, type_name: &str) -> Self {
         self.options.name = Some(type_name.to_owned());
+        self.options.name_pattern = None;
+        self
+    }
+
+    /// Specifies the type name pattern of the target types.
+    ///
+    /// `type_name_pattern` can contain wildcard characters for the SQL LIKE statement.
+    ///
+    /// This overrides [`name`](Self::name) and vice versa.
+    pub fn name_pattern(mut self, type_name_pattern: &str) -> Self {
+        self.options.name_pattern = Some(type_name_pattern.to_owned());
+        self.options.name = None;
         self
     }
 
@@ -399,6 +589,14 @@ impl<'a> GetContextTypesRequest<'a> {
         self
     }
 
+    /// Specifies the child type of the target types (i.e., gets the parent types of `type_id`).
+    ///
+    /// See also: [`MetadataStore::put_parent_type`]
+    pub fn child(mut self, type_id: TypeId) -> Self {
+        self.options.child_type_id = Some(type_id);
+        self
+    }
+
     /// Gets specified context types.
     ///
     /// If multiple conditions are specified, types which satisfy all the conditions are returned.
@@ -406,10 +604,14 @@ impl<'a> GetContextTypesRequest<'a> {
         self.store
             .execute_get_types(
                 TypeKind::Context,
-                |id, name, properties| ContextType {
-                    id,
-                    name,
-                    properties,
+                |ty, properties| {
+                    Ok(ContextType {
+                        id: TypeId::new(ty.id),
+                        name: ty.name,
+                        version: ty.version,
+                        description: ty.description,
+                        properties,
+                    })
                 },
                 self.options,
             )
@@ -420,12 +622,12 @@ impl<'a> GetContextTypesRequest<'a> {
 /// Request builder for [`MetadataStore::get_artifacts`].
 #[derive(Debug)]
 pub struct GetArtifactsRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetArtifactsOptions,
 }
 
 impl<'a> GetArtifactsRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: Default::default(),
@@ -435,14 +637,36 @@ impl<'a> GetArtifactsRequest<'a> {
     /// Specifies the type of the target artifacts.
     pub fn ty(mut self, type_name: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
+        self
+    }
+
+    /// Specifies the types of the target artifacts.
+    ///
+    /// Artifacts that belong to any of the given types are returned.
+    /// This overrides the type specified by the previous call of [`ty`](Self::ty) and its variants.
+    pub fn types<'b>(mut self, type_names: impl Iterator<Item = &'b str>) -> Self {
+        self.options.type_names = type_names.map(|x| x.to_owned()).collect();
+        self.options.type_name = None;
         self
     }
 
     /// Specifies the type and name of the target artifact.
     pub fn type_and_name(mut self, type_name: &str, artifact_name: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
         self.options.artifact_name = Some(artifact_name.to_owned());
         self.options.artifact_name_pattern = None;
+        self.options.ignore_case = false;
+        self
+    }
+
+    /// Specifies the type and name of the target artifact in a case-insensitive manner.
+    ///
+    /// Note that only ASCII characters are case-folded on SQLite.
+    pub fn type_and_name_ci(mut self, type_name: &str, artifact_name: &str) -> Self {
+        self = self.type_and_name(type_name, artifact_name);
+        self.options.ignore_case = true;
         self
     }
 
@@ -451,8 +675,10 @@ impl<'a> GetArtifactsRequest<'a> {
     /// `artifact_name_pattern` can contain wildcard characters for the SQL LIKE statement.
     pub fn type_and_name_pattern(mut self, type_name: &str, artifact_name_pattern: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
         self.options.artifact_name_pattern = Some(artifact_name_pattern.to_owned());
         self.options.artifact_name = None;
+        self.options.ignore_case = false;
         self
     }
 
@@ -472,6 +698,54 @@ impl<'a> GetArtifactsRequest<'a> {
     /// Specifies the URI of the target artifacts.
     pub fn uri(mut self, uri: &str) -> Self {
         self.options.uri = Some(uri.to_owned());
+        self.options.uri_pattern = None;
+        self
+    }
+
+    /// Specifies the URI pattern of the target artifacts.
+    ///
+    /// `uri_pattern` can contain wildcard characters for the SQL LIKE statement.
+    pub fn uri_pattern(mut self, uri_pattern: &str) -> Self {
+        self.options.uri_pattern = Some(uri_pattern.to_owned());
+        self.options.uri = None;
+        self
+    }
+
+    /// Specifies the URI set of the target artifacts.
+    ///
+    /// Artifacts whose URI is equal to any of the given URIs are returned.
+    /// As with [`ids`](Self::ids), an empty iterator means no URI restriction
+    /// (i.e., it doesn't filter out any artifacts).
+    pub fn uris<'b>(mut self, uris: impl Iterator<Item = &'b str>) -> Self {
+        self.options.uris = uris.map(|uri| uri.to_owned()).collect();
+        self
+    }
+
+    /// Specifies that the target artifacts have no URI.
+    ///
+    /// Artifacts with an empty URI are also matched because they are read as having no URI
+    /// (i.e., `Artifact::uri` is `None`).
+    pub fn without_uri(mut self) -> Self {
+        self.options.without_uri = true;
+        self
+    }
+
+    /// Specifies to return the artifacts that match any of the ID and URI conditions.
+    ///
+    /// By default, all the conditions must be met.
+    /// With this option, the conditions specified by [`ids`](Self::ids), [`uri`](Self::uri),
+    /// [`uri_pattern`](Self::uri_pattern) and [`uris`](Self::uris) (and their variants) are OR-ed instead,
+    /// and the result is AND-ed with the other conditions (e.g., type and state) as usual.
+    ///
+    /// [`without_uri`](Self::without_uri) doesn't participate in this and is always AND-ed.
+    pub fn match_any(mut self) -> Self {
+        self.options.match_any = true;
+        self
+    }
+
+    /// Specifies the state of the target artifacts.
+    pub fn state(mut self, state: ArtifactState) -> Self {
+        self.options.state = Some(state);
         self
     }
 
@@ -481,9 +755,149 @@ impl<'a> GetArtifactsRequest<'a> {
         self
     }
 
+    /// Specifies the execution which the target artifacts have events with.
+    ///
+    /// If you need to specify multiple executions, please use [`executions`](Self::executions) instead.
+    pub fn execution(self, execution_id: ExecutionId) -> Self {
+        self.executions(iter::once(execution_id))
+    }
+
+    /// Specifies the executions which the target artifacts have events with.
+    ///
+    /// Artifacts that have an event with any of the given executions are returned.
+    pub fn executions(mut self, execution_ids: impl Iterator<Item = ExecutionId>) -> Self {
+        self.options.execution_ids = execution_ids.collect();
+        self
+    }
+
+    /// Specifies to only consider the output events (see [`EventType::is_output`])
+    /// between the target artifacts and the executions specified by [`executions`](Self::executions)
+    /// and its variant (i.e., only the artifacts produced by the executions are returned).
+    ///
+    /// This is ignored if no executions are specified.
+    pub fn outputs_only(mut self) -> Self {
+        self.options.output_events_only = true;
+        self
+    }
+
+    /// Specifies a property value that the target artifacts have.
+    ///
+    /// Only the properties defined by artifact types are checked (i.e., custom properties are ignored).
+    /// If this method is called multiple times, the artifacts having all the specified values are returned.
+    pub fn property_eq(mut self, name: &str, value: PropertyValue) -> Self {
+        self.options.property_eq.push((name.to_owned(), value));
+        self
+    }
+
+    /// Specifies a range of the value of a double property that the target artifacts have.
+    ///
+    /// As with [`property_eq`](Self::property_eq), only the properties defined by artifact types are checked.
+    /// If this method (or [`int_property_range`](Self::int_property_range)) is called multiple times,
+    /// the artifacts satisfying all the ranges are returned.
+    pub fn property_range(mut self, name: &str, range: impl RangeBounds<f64>) -> Self {
+        let range = Range {
+            start: clone_bound(range.start_bound()),
+            end: clone_bound(range.end_bound()),
+        };
+        self.options
+            .property_ranges
+            .push((name.to_owned(), options::PropertyRange::Double(range)));
+        self
+    }
+
+    /// Specifies a range of the value of an int property that the target artifacts have.
+    ///
+    /// See [`property_range`](Self::property_range) for the details.
+    pub fn int_property_range(mut self, name: &str, range: impl RangeBounds<i32>) -> Self {
+        let range = Range {
+            start: clone_bound(range.start_bound()),
+            end: clone_bound(range.end_bound()),
+        };
+        self.options
+            .property_ranges
+            .push((name.to_owned(), options::PropertyRange::Int(range)));
+        self
+    }
+
+    /// Specifies the names of the properties (and custom properties) to be fetched.
+    ///
+    /// The returned artifacts only contain the properties having the given names,
+    /// so they are projections rather than the full artifacts.
+    /// This reduces the amount of data fetched from the database for artifacts having many properties.
+    /// Note that this doesn't filter the artifacts (use [`has_property`](Self::has_property) for that purpose).
+    pub fn only_properties(mut self, names: &[&str]) -> Self {
+        self.options.only_properties = Some(names.iter().map(|&name| name.to_owned()).collect());
+        self
+    }
+
+    /// Specifies a property that the target artifacts have.
+    ///
+    /// Both the properties defined by artifact types and custom properties are checked.
+    /// If this method (or [`missing_property`](Self::missing_property)) is called multiple times,
+    /// the artifacts satisfying all the conditions are returned.
+    pub fn has_property(mut self, name: &str) -> Self {
+        self.options.property_presence.push((name.to_owned(), true));
+        self
+    }
+
+    /// Specifies a property that the target artifacts don't have.
+    ///
+    /// See [`has_property`](Self::has_property) for the details.
+    pub fn missing_property(mut self, name: &str) -> Self {
+        self.options
+            .property_presence
+            .push((name.to_owned(), false));
+        self
+    }
+
     /// Specifies how to order the result.
+    ///
+    /// The artifacts having the same value of `field` are ordered by their (internal) IDs.
     pub fn order_by(mut self, field: ArtifactOrderByField, asc: bool) -> Self {
         self.options.order_by = Some(field);
+        self.options.order_by_property = None;
+        self.options.order_by_last_event = false;
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the value of the given property.
+    ///
+    /// Numeric values are ordered numerically and string values lexicographically.
+    /// The artifacts that don't have the property are placed last regardless of `asc`.
+    ///
+    /// This overrides [`order_by`](Self::order_by) and vice versa.
+    pub fn order_by_property(mut self, name: &str, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = Some((name.to_owned(), false));
+        self.options.order_by_last_event = false;
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the value of the given custom property.
+    ///
+    /// See [`order_by_property`](Self::order_by_property) for the details.
+    pub fn order_by_custom_property(mut self, name: &str, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = Some((name.to_owned(), true));
+        self.options.order_by_last_event = false;
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the time of the latest event of each artifact.
+    ///
+    /// The artifacts that have no events are placed last regardless of `asc`.
+    ///
+    /// Note that this option makes the query aggregate the whole `Event` table,
+    /// so it may be costly if the database has a large number of events.
+    ///
+    /// This overrides [`order_by`](Self::order_by) and [`order_by_property`](Self::order_by_property) and vice versa.
+    pub fn order_by_last_event(mut self, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = None;
+        self.options.order_by_last_event = true;
         self.options.desc = !asc;
         self
     }
@@ -502,6 +916,14 @@ impl<'a> GetArtifactsRequest<'a> {
         self
     }
 
+    /// Specifies the page of the returned artifacts.
+    ///
+    /// This is equivalent to `self.limit(page_size).offset(page_index.saturating_mul(page_size))`.
+    pub fn page(self, page_index: usize, page_size: usize) -> Self {
+        self.limit(page_size)
+            .offset(page_index.saturating_mul(page_size))
+    }
+
     /// Specifies creation time range.
     pub fn create_time(mut self, range: impl RangeBounds<Duration>) -> Self {
         self.options.create_time = Some(Range {
@@ -537,9 +959,37 @@ impl<'a> GetArtifactsRequest<'a> {
             .execute_count_items(options::GetItemsOptions::Artifact(self.options))
             .await
     }
+
+    /// Deletes the artifacts that satisfy the specified conditions and returns the number of them.
+    ///
+    /// The events, attributions and properties related to the artifacts are deleted as well.
+    /// All the deletions are executed in a single transaction.
+    pub async fn delete(self) -> Result<usize, PutError> {
+        self.store.execute_delete_artifacts(self.options).await
+    }
+
+    /// Returns the SQL that would be issued by [`execute`](Self::execute) without executing it.
+    ///
+    /// This is intended for debugging.
+    pub fn dry_run_sql(&self) -> DryRunSql {
+        DryRunSql::new(self.store.query.get_artifacts(&self.options, false))
+    }
+}
+
+#[cfg(feature = "serde")]
+fn parse_json(column: &str, value: Option<String>) -> Result<Option<serde_json::Value>, GetError> {
+    value
+        .map(|v| {
+            serde_json::from_str(&v).map_err(|e| {
+                GetError::Db(sqlx::Error::Decode(
+                    anyhow::anyhow!("{column} is not a valid JSON: {e}").into(),
+                ))
+            })
+        })
+        .transpose()
 }
 
-fn clone_bound(x: Bound<&Duration>) -> Bound<Duration> {
+fn clone_bound<T: Copy>(x: Bound<&T>) -> Bound<T> {
     match x {
         Bound::Excluded(x) => Bound::Excluded(*x),
         Bound::Included(x) => Bound::Included(*x),
@@ -550,12 +1000,12 @@ fn clone_bound(x: Bound<&Duration>) -> Bound<Duration> {
 /// Request builder for [`MetadataStore::get_executions`].
 #[derive(Debug)]
 pub struct GetExecutionsRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetExecutionsOptions,
 }
 
 impl<'a> GetExecutionsRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: Default::default(),
@@ -565,14 +1015,36 @@ impl<'a> GetExecutionsRequest<'a> {
     /// Specifies the type of the target executions.
     pub fn ty(mut self, type_name: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
+        self
+    }
+
+    /// Specifies the types of the target executions.
+    ///
+    /// Executions that belong to any of the given types are returned.
+    /// This overrides the type specified by the previous call of [`ty`](Self::ty) and its variants.
+    pub fn types<'b>(mut self, type_names: impl Iterator<Item = &'b str>) -> Self {
+        self.options.type_names = type_names.map(|x| x.to_owned()).collect();
+        self.options.type_name = None;
         self
     }
 
     /// Specifies the type and name of the target execution.
     pub fn type_and_name(mut self, type_name: &str, execution_name: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
         self.options.execution_name = Some(execution_name.to_owned());
         self.options.execution_name_pattern = None;
+        self.options.ignore_case = false;
+        self
+    }
+
+    /// Specifies the type and name of the target execution in a case-insensitive manner.
+    ///
+    /// Note that only ASCII characters are case-folded on SQLite.
+    pub fn type_and_name_ci(mut self, type_name: &str, execution_name: &str) -> Self {
+        self = self.type_and_name(type_name, execution_name);
+        self.options.ignore_case = true;
         self
     }
 
@@ -581,8 +1053,10 @@ impl<'a> GetExecutionsRequest<'a> {
     /// `execution_name_pattern` can contain wildcard characters for the SQL LIKE statement.
     pub fn type_and_name_pattern(mut self, type_name: &str, execution_name_pattern: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
         self.options.execution_name_pattern = Some(execution_name_pattern.to_owned());
         self.options.execution_name = None;
+        self.options.ignore_case = false;
         self
     }
 
@@ -599,15 +1073,91 @@ impl<'a> GetExecutionsRequest<'a> {
         self
     }
 
+    /// Specifies the state of the target executions.
+    pub fn state(mut self, state: ExecutionState) -> Self {
+        self.options.state = Some(state);
+        self
+    }
+
     /// Specifies the context to which the target executions belong.
     pub fn context(mut self, context_id: ContextId) -> Self {
         self.options.context_id = Some(context_id);
         self
     }
 
+    /// Specifies a property that the target executions have.
+    ///
+    /// Both the properties defined by execution types and custom properties are checked.
+    /// If this method (or [`missing_property`](Self::missing_property)) is called multiple times,
+    /// the executions satisfying all the conditions are returned.
+    pub fn has_property(mut self, name: &str) -> Self {
+        self.options.property_presence.push((name.to_owned(), true));
+        self
+    }
+
+    /// Specifies a property that the target executions don't have.
+    ///
+    /// See [`has_property`](Self::has_property) for the details.
+    pub fn missing_property(mut self, name: &str) -> Self {
+        self.options
+            .property_presence
+            .push((name.to_owned(), false));
+        self
+    }
+
+    /// Specifies the artifact which the target executions have events with.
+    ///
+    /// If you need to specify multiple artifacts, please use [`artifacts`](Self::artifacts) instead.
+    pub fn artifact(self, artifact_id: ArtifactId) -> Self {
+        self.artifacts(iter::once(artifact_id))
+    }
+
+    /// Specifies the artifacts which the target executions have events with.
+    ///
+    /// Executions that have an event with any of the given artifacts are returned.
+    pub fn artifacts(mut self, artifact_ids: impl Iterator<Item = ArtifactId>) -> Self {
+        self.options.artifact_ids = artifact_ids.collect();
+        self
+    }
+
+    /// Specifies the type of the events between the target executions and the artifacts
+    /// specified by [`artifacts`](Self::artifacts) and its variant.
+    ///
+    /// This is ignored if no artifacts are specified.
+    pub fn event_type(mut self, event_type: EventType) -> Self {
+        self.options.event_type = Some(event_type);
+        self
+    }
+
     /// Specifies how to order the result.
+    ///
+    /// The executions having the same value of `field` are ordered by their (internal) IDs.
     pub fn order_by(mut self, field: ExecutionOrderByField, asc: bool) -> Self {
         self.options.order_by = Some(field);
+        self.options.order_by_property = None;
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the value of the given property.
+    ///
+    /// Numeric values are ordered numerically and string values lexicographically.
+    /// The executions that don't have the property are placed last regardless of `asc`.
+    ///
+    /// This overrides [`order_by`](Self::order_by) and vice versa.
+    pub fn order_by_property(mut self, name: &str, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = Some((name.to_owned(), false));
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the value of the given custom property.
+    ///
+    /// See [`order_by_property`](Self::order_by_property) for the details.
+    pub fn order_by_custom_property(mut self, name: &str, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = Some((name.to_owned(), true));
         self.options.desc = !asc;
         self
     }
@@ -626,6 +1176,14 @@ impl<'a> GetExecutionsRequest<'a> {
         self
     }
 
+    /// Specifies the page of the returned executions.
+    ///
+    /// This is equivalent to `self.limit(page_size).offset(page_index.saturating_mul(page_size))`.
+    pub fn page(self, page_index: usize, page_size: usize) -> Self {
+        self.limit(page_size)
+            .offset(page_index.saturating_mul(page_size))
+    }
+
     /// Specifies creation time range.
     pub fn create_time(mut self, range: impl RangeBounds<Duration>) -> Self {
         self.options.create_time = Some(Range {
@@ -661,17 +1219,24 @@ impl<'a> GetExecutionsRequest<'a> {
             .execute_count_items(options::GetItemsOptions::Execution(self.options))
             .await
     }
+
+    /// Returns the SQL that would be issued by [`execute`](Self::execute) without executing it.
+    ///
+    /// This is intended for debugging.
+    pub fn dry_run_sql(&self) -> DryRunSql {
+        DryRunSql::new(self.store.query.get_executions(&self.options, false))
+    }
 }
 
 /// Request builder for [`MetadataStore::get_contexts`].
 #[derive(Debug)]
 pub struct GetContextsRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetContextsOptions,
 }
 
 impl<'a> GetContextsRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: Default::default(),
@@ -681,14 +1246,36 @@ impl<'a> GetContextsRequest<'a> {
     /// Specifies the type of the target contexts.
     pub fn ty(mut self, type_name: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
+        self
+    }
+
+    /// Specifies the types of the target contexts.
+    ///
+    /// Contexts that belong to any of the given types are returned.
+    /// This overrides the type specified by the previous call of [`ty`](Self::ty) and its variants.
+    pub fn types<'b>(mut self, type_names: impl Iterator<Item = &'b str>) -> Self {
+        self.options.type_names = type_names.map(|x| x.to_owned()).collect();
+        self.options.type_name = None;
         self
     }
 
     /// Specifies the type and name of the target context.
     pub fn type_and_name(mut self, type_name: &str, context_name: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
         self.options.context_name = Some(context_name.to_owned());
         self.options.context_name_pattern = None;
+        self.options.ignore_case = false;
+        self
+    }
+
+    /// Specifies the type and name of the target context in a case-insensitive manner.
+    ///
+    /// Note that only ASCII characters are case-folded on SQLite.
+    pub fn type_and_name_ci(mut self, type_name: &str, context_name: &str) -> Self {
+        self = self.type_and_name(type_name, context_name);
+        self.options.ignore_case = true;
         self
     }
 
@@ -697,8 +1284,10 @@ impl<'a> GetContextsRequest<'a> {
     /// `context_name_pattern` can contain wildcard characters for the SQL LIKE statement.
     pub fn type_and_name_pattern(mut self, type_name: &str, context_name_pattern: &str) -> Self {
         self.options.type_name = Some(type_name.to_owned());
+        self.options.type_names.clear();
         self.options.context_name_pattern = Some(context_name_pattern.to_owned());
         self.options.context_name = None;
+        self.options.ignore_case = false;
         self
     }
 
@@ -737,9 +1326,71 @@ impl<'a> GetContextsRequest<'a> {
         self
     }
 
+    /// Specifies the parent context of the target contexts (i.e., gets the child contexts of `context_id`).
+    ///
+    /// See also: [`MetadataStore::put_parent_context`]
+    pub fn parent(mut self, context_id: ContextId) -> Self {
+        self.options.parent_context_id = Some(context_id);
+        self
+    }
+
+    /// Specifies the child context of the target contexts (i.e., gets the parent contexts of `context_id`).
+    ///
+    /// See also: [`MetadataStore::put_parent_context`]
+    pub fn child(mut self, context_id: ContextId) -> Self {
+        self.options.child_context_id = Some(context_id);
+        self
+    }
+
+    /// Specifies a property that the target contexts have.
+    ///
+    /// Both the properties defined by context types and custom properties are checked.
+    /// If this method (or [`missing_property`](Self::missing_property)) is called multiple times,
+    /// the contexts satisfying all the conditions are returned.
+    pub fn has_property(mut self, name: &str) -> Self {
+        self.options.property_presence.push((name.to_owned(), true));
+        self
+    }
+
+    /// Specifies a property that the target contexts don't have.
+    ///
+    /// See [`has_property`](Self::has_property) for the details.
+    pub fn missing_property(mut self, name: &str) -> Self {
+        self.options
+            .property_presence
+            .push((name.to_owned(), false));
+        self
+    }
+
     /// Specifies how to order the result.
+    ///
+    /// The contexts having the same value of `field` are ordered by their (internal) IDs.
     pub fn order_by(mut self, field: ContextOrderByField, asc: bool) -> Self {
         self.options.order_by = Some(field);
+        self.options.order_by_property = None;
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the value of the given property.
+    ///
+    /// Numeric values are ordered numerically and string values lexicographically.
+    /// The contexts that don't have the property are placed last regardless of `asc`.
+    ///
+    /// This overrides [`order_by`](Self::order_by) and vice versa.
+    pub fn order_by_property(mut self, name: &str, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = Some((name.to_owned(), false));
+        self.options.desc = !asc;
+        self
+    }
+
+    /// Specifies to order the result by the value of the given custom property.
+    ///
+    /// See [`order_by_property`](Self::order_by_property) for the details.
+    pub fn order_by_custom_property(mut self, name: &str, asc: bool) -> Self {
+        self.options.order_by = None;
+        self.options.order_by_property = Some((name.to_owned(), true));
         self.options.desc = !asc;
         self
     }
@@ -758,6 +1409,14 @@ impl<'a> GetContextsRequest<'a> {
         self
     }
 
+    /// Specifies the page of the returned contexts.
+    ///
+    /// This is equivalent to `self.limit(page_size).offset(page_index.saturating_mul(page_size))`.
+    pub fn page(self, page_index: usize, page_size: usize) -> Self {
+        self.limit(page_size)
+            .offset(page_index.saturating_mul(page_size))
+    }
+
     /// Specifies creation time range.
     pub fn create_time(mut self, range: impl RangeBounds<Duration>) -> Self {
         self.options.create_time = Some(Range {
@@ -793,6 +1452,13 @@ impl<'a> GetContextsRequest<'a> {
             .execute_count_items(options::GetItemsOptions::Context(self.options))
             .await
     }
+
+    /// Returns the SQL that would be issued by [`execute`](Self::execute) without executing it.
+    ///
+    /// This is intended for debugging.
+    pub fn dry_run_sql(&self) -> DryRunSql {
+        DryRunSql::new(self.store.query.get_contexts(&self.options, false))
+    }
 }
 
 /// Request builder for [`MetadataStore::post_artifact`].
@@ -836,6 +1502,22 @@ impl<'a> PostArtifactRequest<'a> {
         self
     }
 
+    /// Sets the creation time of the artifact.
+    ///
+    /// If this is not specified, the current time is used.
+    pub fn create_time_since_epoch(mut self, time: Duration) -> Self {
+        self.options.create_time_since_epoch = Some(time);
+        self
+    }
+
+    /// Sets the last update time of the artifact.
+    ///
+    /// If this is not specified, the current time is used.
+    pub fn last_update_time_since_epoch(mut self, time: Duration) -> Self {
+        self.options.last_update_time_since_epoch = Some(time);
+        self
+    }
+
     /// Adds a property to the artifact.
     pub fn property<T>(mut self, key: &str, value: T) -> Self
     where
@@ -906,6 +1588,22 @@ impl<'a> PostExecutionRequest<'a> {
         self
     }
 
+    /// Sets the creation time of the execution.
+    ///
+    /// If this is not specified, the current time is used.
+    pub fn create_time_since_epoch(mut self, time: Duration) -> Self {
+        self.options.create_time_since_epoch = Some(time);
+        self
+    }
+
+    /// Sets the last update time of the execution.
+    ///
+    /// If this is not specified, the current time is used.
+    pub fn last_update_time_since_epoch(mut self, time: Duration) -> Self {
+        self.options.last_update_time_since_epoch = Some(time);
+        self
+    }
+
     /// Adds a property to the execution.
     pub fn property<T>(mut self, key: &str, value: T) -> Self
     where
@@ -974,6 +1672,22 @@ impl<'a> PostContextRequest<'a> {
         self
     }
 
+    /// Sets the creation time of the context.
+    ///
+    /// If this is not specified, the current time is used.
+    pub fn create_time_since_epoch(mut self, time: Duration) -> Self {
+        self.options.create_time_since_epoch = Some(time);
+        self
+    }
+
+    /// Sets the last update time of the context.
+    ///
+    /// If this is not specified, the current time is used.
+    pub fn last_update_time_since_epoch(mut self, time: Duration) -> Self {
+        self.options.last_update_time_since_epoch = Some(time);
+        self
+    }
+
     /// Adds a property to the context.
     pub fn property<T>(mut self, key: &str, value: T) -> Self
     where
@@ -1044,6 +1758,16 @@ impl<'a> PutArtifactRequest<'a> {
         self
     }
 
+    /// Replaces all the custom properties of the artifact with the given ones.
+    ///
+    /// Unlike [`custom_properties`](Self::custom_properties) which only adds or updates the given properties,
+    /// this removes the existing custom properties that are not included in `properties`.
+    pub fn replace_custom_properties(mut self, properties: PropertyValues) -> Self {
+        self.options.custom_properties = properties;
+        self.options.replace_custom_properties = true;
+        self
+    }
+
     /// Adds a property to the artifact.
     pub fn property<T>(mut self, key: &str, value: T) -> Self
     where
@@ -1064,12 +1788,42 @@ impl<'a> PutArtifactRequest<'a> {
         self
     }
 
+    /// Removes a property from the artifact.
+    ///
+    /// The removals are applied before the other property updates in this request.
+    pub fn remove_property(mut self, key: &str) -> Self {
+        self.options
+            .removed_properties
+            .insert((key.to_owned(), false));
+        self
+    }
+
+    /// Removes a custom property from the artifact.
+    ///
+    /// The removals are applied before the other property updates in this request.
+    pub fn remove_custom_property(mut self, key: &str) -> Self {
+        self.options
+            .removed_properties
+            .insert((key.to_owned(), true));
+        self
+    }
+
     /// Sets the state of the artifact.
     pub fn state(mut self, state: ArtifactState) -> Self {
         self.options.state = Some(state);
         self
     }
 
+    /// Rejects custom properties whose value types differ from the ones of their stored values.
+    ///
+    /// When specified, [`PutError::CustomPropertyTypeMismatch`] is returned if, for example,
+    /// an existing int custom property is updated with a string value.
+    /// Note that int and bool values are regarded as the same type as both are stored as integers.
+    pub fn strict_custom_properties(mut self) -> Self {
+        self.options.strict_custom_properties = true;
+        self
+    }
+
     /// Updates this artifact.
     pub async fn execute(self) -> Result<(), PutError> {
         self.store
@@ -1116,6 +1870,16 @@ impl<'a> PutExecutionRequest<'a> {
         self
     }
 
+    /// Replaces all the custom properties of the execution with the given ones.
+    ///
+    /// Unlike [`custom_properties`](Self::custom_properties) which only adds or updates the given properties,
+    /// this removes the existing custom properties that are not included in `properties`.
+    pub fn replace_custom_properties(mut self, properties: PropertyValues) -> Self {
+        self.options.custom_properties = properties;
+        self.options.replace_custom_properties = true;
+        self
+    }
+
     /// Adds a property to the execution.
     pub fn property<T>(mut self, key: &str, value: T) -> Self
     where
@@ -1136,6 +1900,26 @@ impl<'a> PutExecutionRequest<'a> {
         self
     }
 
+    /// Removes a property from the execution.
+    ///
+    /// The removals are applied before the other property updates in this request.
+    pub fn remove_property(mut self, key: &str) -> Self {
+        self.options
+            .removed_properties
+            .insert((key.to_owned(), false));
+        self
+    }
+
+    /// Removes a custom property from the execution.
+    ///
+    /// The removals are applied before the other property updates in this request.
+    pub fn remove_custom_property(mut self, key: &str) -> Self {
+        self.options
+            .removed_properties
+            .insert((key.to_owned(), true));
+        self
+    }
+
     /// Sets the state of the execution.
     pub fn state(mut self, state: ExecutionState) -> Self {
         self.options.last_known_state = Some(state);
@@ -1188,6 +1972,16 @@ impl<'a> PutContextRequest<'a> {
         self
     }
 
+    /// Replaces all the custom properties of the context with the given ones.
+    ///
+    /// Unlike [`custom_properties`](Self::custom_properties) which only adds or updates the given properties,
+    /// this removes the existing custom properties that are not included in `properties`.
+    pub fn replace_custom_properties(mut self, properties: PropertyValues) -> Self {
+        self.options.custom_properties = properties;
+        self.options.replace_custom_properties = true;
+        self
+    }
+
     /// Adds a property to the context.
     pub fn property<T>(mut self, key: &str, value: T) -> Self
     where
@@ -1208,6 +2002,26 @@ impl<'a> PutContextRequest<'a> {
         self
     }
 
+    /// Removes a property from the context.
+    ///
+    /// The removals are applied before the other property updates in this request.
+    pub fn remove_property(mut self, key: &str) -> Self {
+        self.options
+            .removed_properties
+            .insert((key.to_owned(), false));
+        self
+    }
+
+    /// Removes a custom property from the context.
+    ///
+    /// The removals are applied before the other property updates in this request.
+    pub fn remove_custom_property(mut self, key: &str) -> Self {
+        self.options
+            .removed_properties
+            .insert((key.to_owned(), true));
+        self
+    }
+
     /// Update this context.
     pub async fn execute(self) -> Result<(), PutError> {
         self.store
@@ -1242,8 +2056,9 @@ impl<'a> PutAttributionRequest<'a> {
 
     /// Inserts a new attribution.
     ///
-    /// If the same entry already exists, this call will be just ignored.
-    pub async fn execute(self) -> Result<(), PutError> {
+    /// If the same entry already exists, this call will be just ignored
+    /// and [`RelationOutcome::AlreadyExisted`] is returned.
+    pub async fn execute(self) -> Result<RelationOutcome, PutError> {
         self.store
             .execute_put_relation(self.context_id, Id::Artifact(self.artifact_id))
             .await
@@ -1273,14 +2088,79 @@ impl<'a> PutAssociationRequest<'a> {
 
     /// Inserts a new association.
     ///
-    /// If the same entry already exists, this call will be just ignored.
-    pub async fn execute(self) -> Result<(), PutError> {
+    /// If the same entry already exists, this call will be just ignored
+    /// and [`RelationOutcome::AlreadyExisted`] is returned.
+    pub async fn execute(self) -> Result<RelationOutcome, PutError> {
         self.store
             .execute_put_relation(self.context_id, Id::Execution(self.execution_id))
             .await
     }
 }
 
+/// Request builder for [`MetadataStore::put_parent_type`].
+#[derive(Debug)]
+pub struct PutParentTypeRequest<'a> {
+    store: &'a mut MetadataStore,
+    child_type_id: TypeId,
+    parent_type_id: TypeId,
+}
+
+impl<'a> PutParentTypeRequest<'a> {
+    pub(crate) fn new(
+        store: &'a mut MetadataStore,
+        child_type_id: TypeId,
+        parent_type_id: TypeId,
+    ) -> Self {
+        Self {
+            store,
+            child_type_id,
+            parent_type_id,
+        }
+    }
+
+    /// Inserts a new parent-child relationship.
+    ///
+    /// If the same entry already exists, this call will be just ignored
+    /// and [`RelationOutcome::AlreadyExisted`] is returned.
+    pub async fn execute(self) -> Result<RelationOutcome, PutError> {
+        self.store
+            .execute_put_parent_type(self.child_type_id, self.parent_type_id)
+            .await
+    }
+}
+
+/// Request builder for [`MetadataStore::put_parent_context`].
+#[derive(Debug)]
+pub struct PutParentContextRequest<'a> {
+    store: &'a mut MetadataStore,
+    parent_context_id: ContextId,
+    child_context_id: ContextId,
+}
+
+impl<'a> PutParentContextRequest<'a> {
+    pub(crate) fn new(
+        store: &'a mut MetadataStore,
+        parent_context_id: ContextId,
+        child_context_id: ContextId,
+    ) -> Self {
+        Self {
+            store,
+            parent_context_id,
+            child_context_id,
+        }
+    }
+
+    /// Inserts a new parent-child relationship.
+    ///
+    /// If the same entry already exists, this call will be just ignored
+    /// and [`RelationOutcome::AlreadyExisted`] is returned.
+    pub async fn execute(self) -> Result<RelationOutcome, PutError> {
+        self.store
+            .execute_put_parent_context(self.parent_context_id, self.child_context_id)
+            .await
+    }
+}
+
 /// Request builder for [`MetadataStore::put_event`].
 #[derive(Debug)]
 pub struct PutEventRequest<'a> {
@@ -1322,6 +2202,18 @@ impl<'a> PutEventRequest<'a> {
         self
     }
 
+    /// Sets the time of this event.
+    ///
+    /// If not specified, the current time is used.
+    /// Note that the time is stored in milliseconds and the sub-millisecond part is truncated.
+    ///
+    /// Events always have a time (i.e., there is no way to store an event without a time)
+    /// as [`Event::create_time_since_epoch`] isn't optional.
+    pub fn time(mut self, time: Duration) -> Self {
+        self.options.time = Some(time);
+        self
+    }
+
     /// Inserts a new event.
     pub async fn execute(self) -> Result<(), PutError> {
         self.store
@@ -1333,12 +2225,12 @@ impl<'a> PutEventRequest<'a> {
 /// Request builder for [`MetadataStore::get_events`].
 #[derive(Debug)]
 pub struct GetEventsRequest<'a> {
-    store: &'a mut MetadataStore,
+    store: &'a MetadataStore,
     options: options::GetEventsOptions,
 }
 
 impl<'a> GetEventsRequest<'a> {
-    pub(crate) fn new(store: &'a mut MetadataStore) -> Self {
+    pub(crate) fn new(store: &'a MetadataStore) -> Self {
         Self {
             store,
             options: Default::default(),
@@ -1371,6 +2263,15 @@ impl<'a> GetEventsRequest<'a> {
         self
     }
 
+    /// Specifies the (execution, artifact) pairs related to the target events.
+    ///
+    /// Unlike specifying both [`executions`](Self::executions) and [`artifacts`](Self::artifacts)
+    /// (which matches any combination of them), only the events between the paired items are returned.
+    pub fn pairs(mut self, pairs: impl Iterator<Item = (ExecutionId, ArtifactId)>) -> Self {
+        self.options.pairs = pairs.collect();
+        self
+    }
+
     /// Specifies the maximum number of the returned events.
     pub fn limit(mut self, n: usize) -> Self {
         self.options.limit = Some(n);
@@ -1385,7 +2286,17 @@ impl<'a> GetEventsRequest<'a> {
         self
     }
 
+    /// Specifies the page of the returned events.
+    ///
+    /// This is equivalent to `self.limit(page_size).offset(page_index.saturating_mul(page_size))`.
+    pub fn page(self, page_index: usize, page_size: usize) -> Self {
+        self.limit(page_size)
+            .offset(page_index.saturating_mul(page_size))
+    }
+
     /// Specifies how to order the result.
+    ///
+    /// The events having the same value of `field` are ordered by their (internal) IDs.
     pub fn order_by(mut self, field: EventOrderByField, asc: bool) -> Self {
         self.options.order_by = Some(field);
         self.options.desc = !asc;
@@ -1405,4 +2316,11 @@ impl<'a> GetEventsRequest<'a> {
     pub async fn count(self) -> Result<usize, GetError> {
         self.store.execute_count_events(self.options).await
     }
+
+    /// Returns the SQL that would be issued by [`execute`](Self::execute) without executing it.
+    ///
+    /// Note that the event paths are fetched by another query after this one.
+    pub fn dry_run_sql(&self) -> DryRunSql {
+        DryRunSql::new(self.store.query.get_events(&self.options, false))
+    }
 }
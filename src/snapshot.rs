@@ -0,0 +1,154 @@
+//! In-memory snapshot of a metadata store.
+use crate::metadata::{
+    Artifact, ArtifactId, ArtifactType, Context, ContextType, Event, Execution, ExecutionId,
+    ExecutionType, PropertyValue, TypeId,
+};
+
+/// In-memory snapshot of all the types, items and events in a metadata store.
+///
+/// A snapshot is created by [`MetadataStore::snapshot`](crate::MetadataStore::snapshot)
+/// and can be queried repeatedly without accessing the database.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub(crate) artifact_types: Vec<ArtifactType>,
+    pub(crate) execution_types: Vec<ExecutionType>,
+    pub(crate) context_types: Vec<ContextType>,
+    pub(crate) artifacts: Vec<Artifact>,
+    pub(crate) executions: Vec<Execution>,
+    pub(crate) contexts: Vec<Context>,
+    pub(crate) events: Vec<Event>,
+}
+
+impl Snapshot {
+    /// Returns all the artifact types.
+    pub fn artifact_types(&self) -> &[ArtifactType] {
+        &self.artifact_types
+    }
+
+    /// Returns all the execution types.
+    pub fn execution_types(&self) -> &[ExecutionType] {
+        &self.execution_types
+    }
+
+    /// Returns all the context types.
+    pub fn context_types(&self) -> &[ContextType] {
+        &self.context_types
+    }
+
+    /// Returns all the artifacts.
+    pub fn artifacts(&self) -> &[Artifact] {
+        &self.artifacts
+    }
+
+    /// Returns all the executions.
+    pub fn executions(&self) -> &[Execution] {
+        &self.executions
+    }
+
+    /// Returns all the contexts.
+    pub fn contexts(&self) -> &[Context] {
+        &self.contexts
+    }
+
+    /// Returns all the events.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Returns the artifacts of which type has the given name.
+    pub fn artifacts_by_type<'a>(&'a self, type_name: &str) -> impl Iterator<Item = &'a Artifact> {
+        let type_ids = type_ids(
+            self.artifact_types.iter().map(|t| (t.id, &t.name)),
+            type_name,
+        );
+        self.artifacts
+            .iter()
+            .filter(move |x| type_ids.contains(&x.type_id))
+    }
+
+    /// Returns the artifacts that have the given property value.
+    pub fn artifacts_by_property<'a>(
+        &'a self,
+        name: &'a str,
+        value: &'a PropertyValue,
+    ) -> impl Iterator<Item = &'a Artifact> {
+        self.artifacts
+            .iter()
+            .filter(move |x| x.properties.get(name) == Some(value))
+    }
+
+    /// Returns the artifacts that have the given URI.
+    pub fn artifacts_by_uri<'a>(&'a self, uri: &'a str) -> impl Iterator<Item = &'a Artifact> {
+        self.artifacts
+            .iter()
+            .filter(move |x| x.uri.as_deref() == Some(uri))
+    }
+
+    /// Returns the executions of which type has the given name.
+    pub fn executions_by_type<'a>(
+        &'a self,
+        type_name: &str,
+    ) -> impl Iterator<Item = &'a Execution> {
+        let type_ids = type_ids(
+            self.execution_types.iter().map(|t| (t.id, &t.name)),
+            type_name,
+        );
+        self.executions
+            .iter()
+            .filter(move |x| type_ids.contains(&x.type_id))
+    }
+
+    /// Returns the executions that have the given property value.
+    pub fn executions_by_property<'a>(
+        &'a self,
+        name: &'a str,
+        value: &'a PropertyValue,
+    ) -> impl Iterator<Item = &'a Execution> {
+        self.executions
+            .iter()
+            .filter(move |x| x.properties.get(name) == Some(value))
+    }
+
+    /// Returns the contexts of which type has the given name.
+    pub fn contexts_by_type<'a>(&'a self, type_name: &str) -> impl Iterator<Item = &'a Context> {
+        let type_ids = type_ids(
+            self.context_types.iter().map(|t| (t.id, &t.name)),
+            type_name,
+        );
+        self.contexts
+            .iter()
+            .filter(move |x| type_ids.contains(&x.type_id))
+    }
+
+    /// Returns the contexts that have the given property value.
+    pub fn contexts_by_property<'a>(
+        &'a self,
+        name: &'a str,
+        value: &'a PropertyValue,
+    ) -> impl Iterator<Item = &'a Context> {
+        self.contexts
+            .iter()
+            .filter(move |x| x.properties.get(name) == Some(value))
+    }
+
+    /// Returns the events related to the given artifact.
+    pub fn events_by_artifact(&self, artifact_id: ArtifactId) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(move |x| x.artifact_id == artifact_id)
+    }
+
+    /// Returns the events related to the given execution.
+    pub fn events_by_execution(&self, execution_id: ExecutionId) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(move |x| x.execution_id == execution_id)
+    }
+}
+
+fn type_ids<'a>(types: impl Iterator<Item = (TypeId, &'a String)>, type_name: &str) -> Vec<TypeId> {
+    types
+        .filter(|(_, name)| *name == type_name)
+        .map(|(id, _)| id)
+        .collect()
+}